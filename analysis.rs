@@ -0,0 +1,254 @@
+//! Analysis of the widths used in text.
+
+use std::cmp::Ordering;
+
+use super::policy::Context;
+use super::{compose_kana, forms_category, pair_category, to_fullwidth, to_halfwidth, FormsCategory,
+            WidthPolicy, WidthTarget};
+
+/// The width form of characters that exist in both widths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WidthForm {
+    /// Half-width forms, such as ASCII and half-width katakana.
+    Halfwidth,
+    /// Full-width forms, such as full-width ASCII and standard katakana.
+    Fullwidth,
+}
+
+impl WidthForm {
+    /// Returns the form of `ch`, or `None` if `ch` has no counterpart in the
+    /// other width.
+    pub fn of(ch: char) -> Option<WidthForm> {
+        if to_fullwidth(ch).is_some() {
+            Some(WidthForm::Halfwidth)
+        } else if to_halfwidth(ch).is_some() {
+            Some(WidthForm::Fullwidth)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the conversion target producing the form.
+    pub fn target(self) -> WidthTarget {
+        match self {
+            WidthForm::Halfwidth => WidthTarget::Halfwidth,
+            WidthForm::Fullwidth => WidthTarget::Fullwidth,
+        }
+    }
+}
+
+/// Returns the width of the standard form of `ch`, the form outside the
+/// "Halfwidth and Fullwidth Forms" block, for a character of the block or its
+/// counterpart. Returns `None` for characters without width variants.
+///
+/// # Example
+/// ```rust
+/// use unicode_hfwidth::{standard_form_width, WidthForm};
+///
+/// assert_eq!(standard_form_width('ｶ'), Some(WidthForm::Fullwidth));
+/// assert_eq!(standard_form_width('ａ'), Some(WidthForm::Halfwidth));
+/// assert_eq!(standard_form_width('漢'), None);
+/// ```
+pub fn standard_form_width(ch: char) -> Option<WidthForm> {
+    match pair_category(ch)? {
+        FormsCategory::FullwidthAscii
+        | FormsCategory::FullwidthBrackets
+        | FormsCategory::FullwidthSymbols     => Some(WidthForm::Halfwidth),
+        FormsCategory::HalfwidthPunctuation
+        | FormsCategory::HalfwidthKatakana
+        | FormsCategory::HalfwidthHangul
+        | FormsCategory::HalfwidthSymbols     => Some(WidthForm::Fullwidth),
+    }
+}
+
+/// Returns the form most characters of `s` that exist in both widths are
+/// in. If there are no such characters or both forms are equally common,
+/// returns `None`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::WidthForm;
+///
+/// assert_eq!(unicode_hfwidth::detect_dominant_form("ＡＢＣ-1"), Some(WidthForm::Fullwidth));
+/// assert_eq!(unicode_hfwidth::detect_dominant_form("ｶﾀｶﾅ"), Some(WidthForm::Halfwidth));
+/// assert_eq!(unicode_hfwidth::detect_dominant_form("漢字"), None);
+/// # }
+/// ```
+pub fn detect_dominant_form<S: AsRef<str>>(s: S) -> Option<WidthForm> {
+    let s = s.as_ref();
+    let (mut half, mut full) = (0, 0);
+    for ch in s.chars() {
+        match WidthForm::of(ch) {
+            Some(WidthForm::Halfwidth) => half += 1,
+            Some(WidthForm::Fullwidth) => full += 1,
+            None                       => {}
+        }
+    }
+    match half.cmp(&full) {
+        Ordering::Greater => Some(WidthForm::Halfwidth),
+        Ordering::Less    => Some(WidthForm::Fullwidth),
+        Ordering::Equal   => None,
+    }
+}
+
+/// Converts `s` to its dominant form as found by `detect_dominant_form`,
+/// making it consistent. If there is no dominant form, returns `s` unchanged.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// assert_eq!(unicode_hfwidth::normalize_to_dominant("ＴＯＫＹＯ 2020"), "ＴＯＫＹＯ ２０２０");
+/// assert_eq!(unicode_hfwidth::normalize_to_dominant("ｶﾀカﾅ"), "ｶﾀｶﾅ");
+/// # }
+/// ```
+pub fn normalize_to_dominant<S: AsRef<str>>(s: S) -> String {
+    let s = s.as_ref();
+    let form = match detect_dominant_form(s) {
+        Some(form) => form,
+        None       => return s.to_owned(),
+    };
+    let policy = WidthPolicy::builder().all(form.target()).build();
+    let mut out = String::with_capacity(converted_len_utf8(s, form));
+    let mut cx = Context::default();
+    for ch in s.chars() {
+        policy.push_char(ch, &mut cx, &mut out);
+    }
+    cx.flush(&mut out);
+    out
+}
+
+/// Returns the length in bytes of `s` converted to `form`, as
+/// `normalize_to_dominant` converts it, without converting it. Half-width
+/// voiced sound marks composed with the kana before them are accounted for.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::WidthForm;
+///
+/// assert_eq!(unicode_hfwidth::converted_len_utf8("ｶﾞ AB", WidthForm::Fullwidth), 10);
+/// assert_eq!(unicode_hfwidth::converted_len_utf8("ガ ＡＢ", WidthForm::Halfwidth), 6);
+/// # }
+/// ```
+pub fn converted_len_utf8<S: AsRef<str>>(s: S, form: WidthForm) -> usize {
+    let s = s.as_ref();
+    let policy = WidthPolicy::builder().all(form.target()).build();
+    let mut len = 0;
+    let mut base = None;
+    for ch in s.chars() {
+        let converted = policy.convert_char(ch);
+        let composed = base.filter(|_| matches!(ch, 'ﾞ' | 'ﾟ'))
+            .and_then(|held: char| Some((held, compose_kana(held, converted)?)));
+        if let Some((held, composed)) = composed {
+            len = len - held.len_utf8() + composed.len_utf8();
+            base = None;
+            continue;
+        }
+        len += converted.len_utf8();
+        base = Some(converted);
+    }
+    len
+}
+
+/// The number of characters of each kind in a string, as counted by
+/// `analyze`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WidthStats {
+    /// Full-width ASCII variants and white parentheses.
+    pub fullwidth_ascii: usize,
+    /// Half-width katakana and CJK punctuation.
+    pub halfwidth_kana: usize,
+    /// Half-width Hangul.
+    pub halfwidth_hangul: usize,
+    /// Full-width and half-width symbol variants.
+    pub symbols: usize,
+    /// Characters outside the "Halfwidth and Fullwidth Forms" block.
+    pub standard: usize,
+}
+
+impl WidthStats {
+    /// Returns the number of characters in the "Halfwidth and Fullwidth
+    /// Forms" block.
+    pub fn nonstandard(&self) -> usize {
+        self.fullwidth_ascii + self.halfwidth_kana + self.halfwidth_hangul + self.symbols
+    }
+
+    /// Returns `true` if converting to standard width could change the
+    /// string.
+    pub fn needs_normalization(&self) -> bool {
+        self.nonstandard() > 0
+    }
+}
+
+/// Counts the characters of `s` by sub-block.
+///
+/// # Example
+/// ```rust
+/// let stats = unicode_hfwidth::analyze("ＡＢ ｶﾅ ￥1");
+/// assert_eq!((stats.fullwidth_ascii, stats.halfwidth_kana, stats.symbols), (2, 2, 1));
+/// assert_eq!(stats.standard, 3);
+/// assert!(stats.needs_normalization());
+/// ```
+pub fn analyze<S: AsRef<str>>(s: S) -> WidthStats {
+    let s = s.as_ref();
+    let mut stats = WidthStats::default();
+    for ch in s.chars() {
+        let count = match forms_category(ch) {
+            Some(FormsCategory::FullwidthAscii | FormsCategory::FullwidthBrackets) => {
+                &mut stats.fullwidth_ascii
+            }
+            Some(FormsCategory::HalfwidthPunctuation | FormsCategory::HalfwidthKatakana) => {
+                &mut stats.halfwidth_kana
+            }
+            Some(FormsCategory::HalfwidthHangul) => &mut stats.halfwidth_hangul,
+            Some(FormsCategory::FullwidthSymbols | FormsCategory::HalfwidthSymbols) => {
+                &mut stats.symbols
+            }
+            None => &mut stats.standard,
+        };
+        *count += 1;
+    }
+    stats
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_dominant_form() {
+    assert_eq!(detect_dominant_form(""), None);
+    assert_eq!(detect_dominant_form("ab ＡＢ"), None);
+    assert_eq!(detect_dominant_form("カタカナ ok"), Some(WidthForm::Fullwidth));
+    assert_eq!(normalize_to_dominant("カタカナ ok"), "カタカナ ｏｋ");
+    assert_eq!(normalize_to_dominant("ab ＡＢ"), "ab ＡＢ");
+}
+
+#[test]
+fn test_converted_len_utf8() {
+    for s in ["", "ｶﾞﾞｶﾟﾊﾟ ＡＢ ab", "か\u{ff9e}ﾞ\u{3000}￥ ﾡￂ", "ﾞｶ😀ﾟ", "ガパ ￩ ←"] {
+        for form in [WidthForm::Halfwidth, WidthForm::Fullwidth] {
+            let converted = WidthPolicy::builder().all(form.target()).build().convert(s);
+            assert_eq!(converted_len_utf8(s, form), converted.len(), "{} {:?}", s, form);
+            assert_eq!(converted.capacity(), converted.len(), "{} {:?}", s, form);
+        }
+    }
+}
+
+#[test]
+fn test_analyze() {
+    let stats = analyze("ﾊﾝｸﾞﾙ ﾡﾢ （ｘ）\u{ff5f}￨");
+    assert_eq!(stats, WidthStats {
+        fullwidth_ascii: 4, halfwidth_kana: 5, halfwidth_hangul: 2, symbols: 1, standard: 2,
+    });
+    assert_eq!(stats.nonstandard(), 12);
+    assert!(!analyze("plain カナ").needs_normalization());
+}
+
+#[cfg(all(feature = "hangul", feature = "kana", feature = "latin", feature = "symbols"))]
+#[test]
+fn test_standard_form_width() {
+    for ch in ('\u{ff01}'..='\u{ffee}').filter(|&ch| forms_category(ch).is_some()) {
+        let standard = super::to_standard_width(ch).unwrap();
+        assert_eq!(standard_form_width(ch), WidthForm::of(standard), "{}", ch);
+        assert_eq!(standard_form_width(standard), standard_form_width(ch), "{}", ch);
+    }
+}
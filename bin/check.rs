@@ -0,0 +1,98 @@
+//! Policy violations for `--check`.
+
+use std::fmt::Write;
+use std::iter;
+use std::ops::Range;
+
+use unicode_hfwidth::Edit;
+
+/// Returns `true` if `offset` lies in one of `spans`, or if there are no spans
+/// to restrict to.
+pub fn in_spans(spans: Option<&[Range<usize>]>, offset: usize) -> bool {
+    spans.is_none_or(|spans| spans.iter().any(|span| span.contains(&offset)))
+}
+
+/// Removes the `protected` spans, sorted by start, from `spans`, or from the
+/// whole input of `len` bytes if there are no spans to restrict to.
+pub fn unprotected(spans: Option<Vec<Range<usize>>>, len: usize, protected: &[Range<usize>])
+                   -> Option<Vec<Range<usize>>> {
+    if protected.is_empty() {
+        return spans;
+    }
+    let mut out = Vec::new();
+    for span in spans.unwrap_or_else(|| iter::once(0..len).collect()) {
+        let mut start = span.start;
+        for cut in protected {
+            if cut.start >= span.end {
+                break;
+            }
+            if cut.end > start && !cut.is_empty() {
+                if cut.start > start {
+                    out.push(start..cut.start);
+                }
+                start = cut.end;
+            }
+        }
+        if start < span.end {
+            out.push(start..span.end);
+        }
+    }
+    Some(out)
+}
+
+/// Formats `s` with the code points of its characters.
+fn describe(s: &str) -> String {
+    let points: Vec<String> = s.chars().map(|ch| format!("U+{:04X}", ch as u32)).collect();
+    format!("{:?} ({})", s, points.join(" "))
+}
+
+/// Lists the `edits` made to `input`, one per line as `path:line:column:
+/// message`, with 1-based line and character columns. If `spans` is given,
+/// only edits starting inside them are listed.
+pub fn violations(path: &str, input: &str, edits: &[Edit], spans: Option<&[Range<usize>]>)
+                  -> String {
+    let mut out = String::new();
+    let (mut line, mut column, mut pos) = (1, 1, 0);
+    for edit in edits.iter().filter(|edit| in_spans(spans, edit.range.start)) {
+        for ch in input[pos..edit.range.start].chars() {
+            if ch == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        pos = edit.range.start;
+        let _ = writeln!(out, "{}:{}:{}: {} should be {}", path, line, column,
+                         describe(&input[edit.range.clone()]), describe(&edit.replacement));
+    }
+    out
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_violations() {
+    use unicode_hfwidth::{WidthConverter, WidthPolicy};
+
+    let converter = WidthConverter::new(WidthPolicy::builder().contextual_prolonged_mark(true)
+                                                              .build())
+        .map('〜', '～');
+    let check = |input, spans| violations("a.txt", input, &converter.diff(input), spans);
+    assert_eq!(check("ok\nxＡ\n", None),
+               "a.txt:2:2: \"Ａ\" (U+FF21) should be \"A\" (U+0041)\n");
+    assert_eq!(check("ok", None), "");
+    assert_eq!(check("Ａ Ｂ", Some(&[0..0, 4..7])),
+               "a.txt:1:3: \"Ｂ\" (U+FF22) should be \"B\" (U+0042)\n");
+    assert_eq!(check("ｶﾞ\nー1", None),
+               "a.txt:1:1: \"ｶ\\u{ff9e}\" (U+FF76 U+FF9E) should be \"ガ\" (U+30AC)\n\
+                a.txt:2:1: \"ー\" (U+30FC) should be \"-\" (U+002D)\n");
+    assert_eq!(check("a〜b", None),
+               "a.txt:1:2: \"〜\" (U+301C) should be \"～\" (U+FF5E)\n");
+}
+
+#[test]
+fn test_unprotected() {
+    assert_eq!(unprotected(None, 10, &[]), None);
+    assert_eq!(unprotected(None, 10, &[2..4, 3..5, 8..10]), Some(vec![0..2, 5..8]));
+    assert_eq!(unprotected(Some(vec![0..3, 6..9]), 10, &[2..7, 8..8]), Some(vec![0..2, 7..9]));
+}
@@ -0,0 +1,164 @@
+//! CSV column selection for `--csv`.
+
+use std::ops::Range;
+
+use unicode_hfwidth::WidthConverter;
+
+/// A field of a CSV record, as a span of the input.
+#[derive(Clone, Debug, PartialEq)]
+struct Field {
+    /// The raw text of the field, including any enclosing quotes.
+    raw: Range<usize>,
+    quoted: bool,
+}
+
+impl Field {
+    /// Returns the span of the field's content, without enclosing quotes.
+    fn content(&self, input: &str) -> Range<usize> {
+        if !self.quoted {
+            return self.raw.clone();
+        }
+        let end = if self.raw.len() > 1 && input[..self.raw.end].ends_with('"') {
+            self.raw.end - 1
+        } else {
+            self.raw.end
+        };
+        self.raw.start + 1..end
+    }
+
+    /// Returns the unescaped value of the field.
+    fn value(&self, input: &str) -> String {
+        let content = &input[self.content(input)];
+        if self.quoted { content.replace("\"\"", "\"") } else { content.to_owned() }
+    }
+}
+
+/// Splits `input` into records of fields, following RFC 4180: fields may be
+/// quoted, and quoted fields may contain commas, line breaks and doubled
+/// quotes.
+fn parse(input: &str) -> Vec<Vec<Field>> {
+    let bytes = input.as_bytes();
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let quoted = bytes[i] == b'"';
+        if quoted {
+            i += 1;
+            while i < bytes.len() {
+                if bytes[i] == b'"' {
+                    if bytes.get(i + 1) == Some(&b'"') {
+                        i += 2;
+                        continue;
+                    }
+                    i += 1;
+                    break;
+                }
+                i += 1;
+            }
+        }
+        while i < bytes.len() && !matches!(bytes[i], b',' | b'\n' | b'\r') {
+            i += 1;
+        }
+        record.push(Field { raw: start..i, quoted });
+        match bytes.get(i) {
+            Some(b',') => {
+                i += 1;
+                if i == bytes.len() {
+                    record.push(Field { raw: i..i, quoted: false });
+                }
+            }
+            Some(_) => {
+                i += if input[i..].starts_with("\r\n") { 2 } else { 1 };
+                records.push(std::mem::take(&mut record));
+            }
+            None => {}
+        }
+    }
+    if !record.is_empty() {
+        records.push(record);
+    }
+    records
+}
+
+/// Resolves `columns`, given as header names or 1-based indices, to column
+/// indices.
+fn resolve_columns(input: &str, header: &[Field], columns: &[String])
+                   -> Result<Vec<usize>, String> {
+    columns.iter().map(|column| {
+        header.iter().position(|field| field.value(input) == *column)
+            .or_else(|| column.parse::<usize>().ok().filter(|&n| n > 0).map(|n| n - 1))
+            .ok_or_else(|| format!("unknown CSV column '{}'", column))
+    }).collect()
+}
+
+/// Returns the content spans of the fields in the selected columns, skipping
+/// the header record.
+pub fn selected_spans(input: &str, columns: &[String]) -> Result<Vec<Range<usize>>, String> {
+    let records = parse(input);
+    let header = match records.first() {
+        Some(header) => header,
+        None         => return Ok(Vec::new()),
+    };
+    let indices = resolve_columns(input, header, columns)?;
+    Ok(records[1..].iter().flat_map(|record| {
+        record.iter().enumerate()
+            .filter(|(i, _)| indices.contains(i))
+            .map(|(_, field)| field.content(input))
+    }).collect())
+}
+
+/// Converts the fields in the selected columns, quoting them if conversion
+/// introduced characters that need it.
+pub fn convert(input: &str, columns: &[String], converter: &WidthConverter)
+               -> Result<String, String> {
+    let records = parse(input);
+    let header = match records.first() {
+        Some(header) => header,
+        None         => return Ok(input.to_owned()),
+    };
+    let indices = resolve_columns(input, header, columns)?;
+    let mut out = String::with_capacity(input.len());
+    let mut copied = 0;
+    for record in &records[1..] {
+        for (_, field) in record.iter().enumerate().filter(|(i, _)| indices.contains(i)) {
+            let value = field.value(input);
+            let converted = converter.convert(&value);
+            if converted == value {
+                continue;
+            }
+            out.push_str(&input[copied..field.raw.start]);
+            if field.quoted || converted.contains([',', '"', '\n', '\r']) {
+                out.push('"');
+                out.push_str(&converted.replace('"', "\"\""));
+                out.push('"');
+            } else {
+                out.push_str(&converted);
+            }
+            copied = field.raw.end;
+        }
+    }
+    out.push_str(&input[copied..]);
+    Ok(out)
+}
+
+#[test]
+fn test_parse() {
+    let input = "a,\"b,\"\"c\"\"\n\",\r\nd,e,";
+    let values: Vec<Vec<String>> = parse(input).iter()
+        .map(|record| record.iter().map(|field| field.value(input)).collect())
+        .collect();
+    assert_eq!(values, [vec!["a", "b,\"c\"\n", ""], vec!["d", "e", ""]]);
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_convert() {
+    let input = "code,name,address\nＡ１,ﾔﾏｶﾜ,\"東京都，１－２\"\nＢ２,ｽｽｷ，ｲﾁﾛｳ,大阪\n";
+    let columns = ["name".to_owned(), "3".to_owned()];
+    assert_eq!(convert(input, &columns, &WidthConverter::default()).unwrap(),
+               "code,name,address\nＡ１,ヤマカワ,\"東京都,1-2\"\nＢ２,\"ススキ,イチロウ\",大阪\n");
+    assert!(convert(input, &["phone".to_owned()], &WidthConverter::default()).is_err());
+    assert_eq!(selected_spans(input, &columns[..1]).unwrap(), [25..37, 69..93]);
+}
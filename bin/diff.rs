@@ -0,0 +1,174 @@
+//! Diff output for `--diff`.
+//!
+//! Conversion only adds or removes line breaks if a profile maps characters
+//! to or from them, so the old and new texts usually have the same lines and
+//! are compared line by line without a general diff algorithm. Otherwise the
+//! lines between their common first and last lines are shown as one change.
+
+use std::fmt::Write;
+use std::ops::Range;
+
+use unicode_hfwidth::{pad_right, str_width, Fill};
+
+/// Lines of context around changes in unified diffs.
+const CONTEXT: usize = 3;
+
+/// The style of `--diff` output.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiffStyle {
+    Unified,
+    SideBySide,
+}
+
+/// A run of changed lines, as the ranges of the old and new lines.
+type Change = (Range<usize>, Range<usize>);
+
+/// Returns the runs of lines that differ, sorted and not touching.
+fn changes(old: &[&str], new: &[&str]) -> Vec<Change> {
+    if old.len() == new.len() {
+        let mut changes: Vec<Change> = Vec::new();
+        for i in (0..old.len()).filter(|&i| old[i] != new[i]) {
+            match changes.last_mut() {
+                Some((old, new)) if old.end == i => {
+                    old.end += 1;
+                    new.end += 1;
+                }
+                _ => changes.push((i..i + 1, i..i + 1)),
+            }
+        }
+        return changes;
+    }
+    let prefix = old.iter().zip(new).take_while(|(old, new)| old == new).count();
+    let suffix = old[prefix..].iter().rev().zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    vec![(prefix..old.len() - suffix, prefix..new.len() - suffix)]
+}
+
+/// Writes `line` with a diff prefix, marking a missing final newline.
+fn push_line(out: &mut String, prefix: char, line: &str) {
+    out.push(prefix);
+    out.push_str(line);
+    if !line.ends_with('\n') {
+        out.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+/// Renders a unified diff of `old` and `new`, or an empty string if they are
+/// equal.
+pub fn unified(path: &str, old: &str, new: &str) -> String {
+    let old: Vec<_> = old.split_inclusive('\n').collect();
+    let new: Vec<_> = new.split_inclusive('\n').collect();
+    let changes = changes(&old, &new);
+    let mut out = String::new();
+    if changes.is_empty() {
+        return out;
+    }
+    let _ = writeln!(out, "--- {}\n+++ {}", path, path);
+    let mut i = 0;
+    while i < changes.len() {
+        let mut last = i;
+        while last + 1 < changes.len()
+            && changes[last + 1].0.start <= changes[last].0.end + 2 * CONTEXT {
+            last += 1;
+        }
+        let (first_old, first_new) = &changes[i];
+        let (last_old, last_new) = &changes[last];
+        let start = first_old.start.saturating_sub(CONTEXT);
+        let new_start = first_new.start - (first_old.start - start);
+        let end = (last_old.end + CONTEXT).min(old.len());
+        let new_end = last_new.end + (end - last_old.end);
+        let _ = writeln!(out, "@@ -{},{} +{},{} @@", start + 1, end - start, new_start + 1,
+                         new_end - new_start);
+        let mut line = start;
+        for (old_lines, new_lines) in &changes[i..=last] {
+            for context in &old[line..old_lines.start] {
+                push_line(&mut out, ' ', context);
+            }
+            for old_line in &old[old_lines.clone()] {
+                push_line(&mut out, '-', old_line);
+            }
+            for new_line in &new[new_lines.clone()] {
+                push_line(&mut out, '+', new_line);
+            }
+            line = old_lines.end;
+        }
+        for context in &old[line..end] {
+            push_line(&mut out, ' ', context);
+        }
+        i = last + 1;
+    }
+    out
+}
+
+/// Renders the changed lines of `old` and `new` next to each other, with line
+/// numbers, or an empty string if they are equal. Lines added by the change
+/// have no number.
+pub fn side_by_side(path: &str, old: &str, new: &str) -> String {
+    let old: Vec<_> = old.lines().collect();
+    let new: Vec<_> = new.lines().collect();
+    let changes = changes(&old, &new);
+    let mut out = String::new();
+    if changes.is_empty() {
+        return out;
+    }
+    let width = changes.iter().flat_map(|(old_lines, _)| &old[old_lines.clone()])
+        .map(str_width)
+        .max()
+        .unwrap_or(0);
+    let _ = writeln!(out, "{}:", path);
+    for (old_lines, new_lines) in changes {
+        for k in 0..old_lines.len().max(new_lines.len()) {
+            let i = old_lines.start + k;
+            let number = if i < old_lines.end { (i + 1).to_string() } else { String::new() };
+            let old_line = if i < old_lines.end { old[i] } else { "" };
+            let new_line = new[new_lines.clone()].get(k).copied().unwrap_or("");
+            let _ = writeln!(out, "{:>6} {} | {}", number, pad_right(old_line, width, Fill::Space),
+                             new_line);
+        }
+    }
+    out
+}
+
+#[test]
+fn test_unified() {
+    let old = "1\n2\nＡ\n4\n5\n6\n7\n8\n9\n10\n11\nＢ";
+    let new = "1\n2\nA\n4\n5\n6\n7\n8\n9\n10\n11\nB";
+    assert_eq!(unified("f", old, new), "\
+--- f
++++ f
+@@ -1,6 +1,6 @@
+ 1
+ 2
+-Ａ
++A
+ 4
+ 5
+ 6
+@@ -9,4 +9,4 @@
+ 9
+ 10
+ 11
+-Ｂ
+\\ No newline at end of file
++B
+\\ No newline at end of file
+");
+    assert_eq!(unified("f", "same\n", "same\n"), "");
+}
+
+#[test]
+fn test_side_by_side() {
+    assert_eq!(side_by_side("f", "ok\nｶﾅ\nＡＢ\n", "ok\nカナ\nAB\n"),
+               "f:\n     2 ｶﾅ   | カナ\n     3 ＡＢ | AB\n");
+}
+
+#[test]
+fn test_line_breaks() {
+    let old = "1\n2\na|b\n4\n";
+    let new = "1\n2\na\nb\n4\n";
+    assert_eq!(unified("f", old, new), "--- f\n+++ f\n@@ -1,4 +1,5 @@\n 1\n 2\n-a|b\n+a\n+b\n 4\n");
+    assert_eq!(unified("f", new, old), "--- f\n+++ f\n@@ -1,5 +1,4 @@\n 1\n 2\n-a\n-b\n+a|b\n 4\n");
+    assert_eq!(side_by_side("f", old, new), "f:\n     3 a|b | a\n           | b\n");
+    assert_eq!(side_by_side("f", new, old), "f:\n     3 a | a|b\n     4 b | \n");
+}
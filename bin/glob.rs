@@ -0,0 +1,142 @@
+//! Minimal glob patterns for `--include` and `--exclude`.
+
+/// A glob pattern supporting `*`, `**`, `?` and `[...]` character classes.
+///
+/// Patterns without a `/` are matched against the file name only; others are
+/// matched against the path relative to the directory being walked.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Glob {
+    /// The `/`-separated segments of the pattern.
+    segments: Vec<Vec<char>>,
+    name_only: bool,
+}
+
+impl Glob {
+    /// Creates a glob from `pattern`. A segment consisting of `**` matches
+    /// any number of path segments, while `*` and `?` never match a `/`. A
+    /// `[` without a closing `]` matches itself.
+    pub fn new(pattern: &str) -> Glob {
+        Glob {
+            segments: pattern.split('/').map(|segment| segment.chars().collect()).collect(),
+            name_only: !pattern.contains('/'),
+        }
+    }
+
+    /// Returns `true` if the pattern matches `path`, a `/`-separated relative
+    /// path.
+    pub fn matches(&self, path: &str) -> bool {
+        let subject = if self.name_only {
+            path.rsplit('/').next().unwrap_or(path)
+        } else {
+            path
+        };
+        let names: Vec<Vec<char>> = subject.split('/').map(|name| name.chars().collect()).collect();
+        match_path(&self.segments, &names)
+    }
+}
+
+/// Matches a character class starting after its `[`. Returns whether `ch`
+/// matched and the remaining pattern after the closing `]`, or `None` if the
+/// class is not terminated.
+fn match_class(pattern: &[char], ch: char) -> Option<(bool, &[char])> {
+    let (negated, mut rest) = match pattern.first() {
+        Some('!' | '^') => (true, &pattern[1..]),
+        _               => (false, pattern),
+    };
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        match rest {
+            [']', tail @ ..] if !first => return Some((matched != negated, tail)),
+            [lo, '-', hi, tail @ ..] if *hi != ']' => {
+                matched |= (*lo..=*hi).contains(&ch);
+                rest = tail;
+            }
+            [c, tail @ ..] => {
+                matched |= *c == ch;
+                rest = tail;
+            }
+            [] => return None,
+        }
+        first = false;
+    }
+}
+
+/// Returns the length of the pattern element at the start of `pattern`, a
+/// character, `?` or a class, if it matches `ch`.
+fn match_one(pattern: &[char], ch: char) -> Option<usize> {
+    match pattern {
+        ['?', ..]         => Some(1),
+        ['[', class @ ..] => match match_class(class, ch) {
+            Some((matched, rest)) => matched.then_some(pattern.len() - rest.len()),
+            None                  => (ch == '[').then_some(1),
+        },
+        ['*', ..] | []    => None,
+        [c, ..]           => (*c == ch).then_some(1),
+    }
+}
+
+/// Matches a path segment, in which `*` matches any run of characters. The
+/// last `*` passed is where matching resumes on a mismatch, which is enough
+/// as a `*` matches anything an earlier one could.
+fn match_segment(pattern: &[char], name: &[char]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    let mut star = None;
+    while n < name.len() {
+        if pattern.get(p) == Some(&'*') {
+            p += 1;
+            star = Some((p, n));
+        } else if let Some(len) = match_one(&pattern[p..], name[n]) {
+            p += len;
+            n += 1;
+        } else if let Some((after, from)) = star {
+            p = after;
+            n = from + 1;
+            star = Some((after, n));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Matches the segments of a path, in which a `**` segment matches any number
+/// of segments, resuming on a mismatch like `match_segment`.
+fn match_path(segments: &[Vec<char>], names: &[Vec<char>]) -> bool {
+    let globstar = |segment: &Vec<char>| segment[..] == ['*', '*'];
+    let (mut p, mut n) = (0, 0);
+    let mut star = None;
+    while n < names.len() {
+        if segments.get(p).is_some_and(globstar) {
+            p += 1;
+            star = Some((p, n));
+        } else if segments.get(p).is_some_and(|segment| match_segment(segment, &names[n])) {
+            p += 1;
+            n += 1;
+        } else if let Some((after, from)) = star {
+            p = after;
+            n = from + 1;
+            star = Some((after, n));
+        } else {
+            return false;
+        }
+    }
+    segments[p..].iter().all(globstar)
+}
+
+#[test]
+fn test_glob() {
+    assert!(Glob::new("*.md").matches("docs/guide.md"));
+    assert!(!Glob::new("*.md").matches("docs/guide.mdx"));
+    assert!(Glob::new("docs/*.txt").matches("docs/a.txt"));
+    assert!(!Glob::new("docs/*.txt").matches("docs/sub/a.txt"));
+    assert!(Glob::new("docs/**/*.txt").matches("docs/sub/deep/a.txt"));
+    assert!(Glob::new("docs/**/*.txt").matches("docs/a.txt"));
+    assert!(Glob::new("file?.[ch]").matches("file1.c"));
+    assert!(!Glob::new("file?.[!ch]").matches("file1.c"));
+    assert!(Glob::new("[a-c]*").matches("banana"));
+    assert!(Glob::new("**/[a-c").matches("x/y/[a-c"));
+    assert!(Glob::new("**").matches("a/b"));
+    assert!(!Glob::new("*a*a*a*a*a*a*a*a*a*a*b").matches(&"a".repeat(100)));
+    assert!(!Glob::new("**/**/**/**/**/**/**/x").matches(&"a/".repeat(100)));
+}
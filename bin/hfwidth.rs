@@ -0,0 +1,552 @@
+//! Command-line tool converting text to standard width.
+
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process;
+
+#[cfg(any(feature = "toml", feature = "yaml"))]
+use unicode_hfwidth::Profile;
+use unicode_hfwidth::{Edit, WidthConverter, WidthPolicy, WidthTarget};
+
+use diff::DiffStyle;
+use glob::Glob;
+use report::FileReport;
+
+mod check;
+mod csv;
+mod diff;
+mod glob;
+mod report;
+
+const USAGE: &str = "\
+Usage: hfwidth [OPTIONS] [FILE]...
+
+Converts characters in the Unicode \"Halfwidth and Fullwidth Forms\" block to
+their standard-width forms. Reads standard input if no files are given, in
+which case it is converted as it arrives, unless the profile protects spans.
+
+Options:
+  -i, --in-place[=SUFFIX]  Edit files in place, keeping a backup with SUFFIX
+  -r, --recursive          Convert files in directories recursively, skipping
+                           hidden and binary files
+      --include=GLOB       Only convert files matching GLOB when recursing
+      --exclude=GLOB       Skip files and directories matching GLOB when recursing
+      --ascii=TARGET       Convert full-width ASCII variants to TARGET
+      --kana=TARGET        Convert half-width katakana and punctuation to TARGET
+      --hangul=TARGET      Convert half-width Hangul to TARGET
+      --symbols=TARGET     Convert symbol variants to TARGET
+      --space=TARGET       Convert spaces to TARGET, where 'ascii' and
+                           'ideographic' are also accepted
+      --profile=FILE       Load the targets and protected spans from a TOML or
+                           YAML profile instead of the options above
+      --diff[=STYLE]       Print the changes instead of converting, as a 'unified'
+                           (default) or 'side-by-side' diff
+      --csv                Treat input as CSV and only convert the fields in the
+                           columns given with --columns
+      --columns=LIST       Comma-separated header names or 1-based indices of the
+                           CSV columns to convert
+      --check              List text that would be converted as FILE:LINE:COLUMN
+                           and exit with status 1 if any, or 2 on errors
+  -z, --null               Delimit records on standard input with NUL instead of
+                           newline, flushing the output after each record
+      --line-buffered      Flush the output after each record
+      --report=json        Print a JSON report of the changes, to standard error
+                           unless editing in place
+  -h, --help               Print this help and exit
+  -V, --version            Print the version and exit
+
+TARGET is one of 'keep', 'standard', 'half' or 'full'. Everything is
+converted to standard width by default.
+";
+
+/// Command-line options.
+#[derive(Debug, Default, PartialEq)]
+struct Options {
+    in_place: bool,
+    backup_suffix: Option<String>,
+    recursive: bool,
+    includes: Vec<Glob>,
+    excludes: Vec<Glob>,
+    report: bool,
+    diff: Option<DiffStyle>,
+    check: bool,
+    csv: bool,
+    columns: Vec<String>,
+    null: bool,
+    line_buffered: bool,
+    policy: WidthPolicy,
+    profile: Option<PathBuf>,
+    files: Vec<PathBuf>,
+}
+
+impl Options {
+    /// Returns `true` if the output is just the converted text, so that input
+    /// can be converted as it is read.
+    fn is_plain(&self) -> bool {
+        self.diff.is_none() && !self.check && !self.csv && !self.report
+    }
+}
+
+/// The outcome of parsing the command line.
+#[derive(Debug, PartialEq)]
+enum Command {
+    Run(Options),
+    Help,
+    Version,
+}
+
+fn parse_target(option: &str, value: &str) -> Result<WidthTarget, String> {
+    match (option, value) {
+        ("--space", "ascii")       => Ok(WidthTarget::Halfwidth),
+        ("--space", "ideographic") => Ok(WidthTarget::Fullwidth),
+        _ => value.parse().map_err(|_| format!("invalid value '{}' for {}", value, option)),
+    }
+}
+
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut options = Options::default();
+    let mut policy = WidthPolicy::builder();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value)),
+            _ => (arg.as_str(), None),
+        };
+        match (name, value) {
+            ("-h" | "--help", None)    => return Ok(Command::Help),
+            ("-V" | "--version", None) => return Ok(Command::Version),
+            ("-i" | "--in-place", suffix) => {
+                if suffix == Some("") {
+                    return Err("empty backup suffix".to_owned());
+                }
+                options.in_place = true;
+                options.backup_suffix = suffix.map(str::to_owned);
+            }
+            ("-r" | "--recursive", None) => options.recursive = true,
+            ("--include", Some(glob)) => options.includes.push(Glob::new(glob)),
+            ("--exclude", Some(glob)) => options.excludes.push(Glob::new(glob)),
+            ("--ascii", Some(value))   => policy = policy.ascii(parse_target(name, value)?),
+            ("--kana", Some(value))    => policy = policy.kana(parse_target(name, value)?),
+            ("--hangul", Some(value))  => policy = policy.hangul(parse_target(name, value)?),
+            ("--symbols", Some(value)) => policy = policy.symbols(parse_target(name, value)?),
+            ("--space", Some(value))   => policy = policy.space(parse_target(name, value)?),
+            ("--profile", Some(path))  => options.profile = Some(PathBuf::from(path)),
+            ("--diff", None | Some("unified")) => options.diff = Some(DiffStyle::Unified),
+            ("--diff", Some("side-by-side"))   => options.diff = Some(DiffStyle::SideBySide),
+            ("--diff", Some(style)) => return Err(format!("unsupported diff style '{}'", style)),
+            ("--check", None) => options.check = true,
+            ("--csv", None)   => options.csv = true,
+            ("-z" | "--null", None)    => options.null = true,
+            ("--line-buffered", None) => options.line_buffered = true,
+            ("--columns", Some(list)) => {
+                options.columns.extend(list.split(',').map(str::to_owned));
+            }
+            ("--report", Some("json")) => options.report = true,
+            ("--report", Some(format)) => {
+                return Err(format!("unsupported report format '{}'", format));
+            }
+            ("--", None) => {
+                options.files.extend(args.by_ref().map(PathBuf::from));
+            }
+            (name, _) if name.starts_with('-') && name != "-" => {
+                return Err(format!("unrecognized option '{}'", arg));
+            }
+            _ => options.files.push(PathBuf::from(arg)),
+        }
+    }
+    let modes = [options.in_place, options.diff.is_some(), options.check];
+    if modes.iter().filter(|&&set| set).count() > 1 {
+        return Err("only one of --in-place, --diff and --check can be used".to_owned());
+    }
+    if (options.null || options.line_buffered) && !options.is_plain() {
+        return Err("--null and --line-buffered cannot be used with --diff, --check, --csv or \
+                    --report".to_owned());
+    }
+    if options.csv && options.columns.is_empty() {
+        return Err("--csv requires --columns".to_owned());
+    }
+    if options.in_place && options.files.is_empty() {
+        return Err("--in-place requires at least one file".to_owned());
+    }
+    options.policy = policy.build();
+    if options.profile.is_some() && options.policy != WidthPolicy::default() {
+        return Err("--profile cannot be combined with --ascii, --kana, --hangul, --symbols or \
+                    --space".to_owned());
+    }
+    Ok(Command::Run(options))
+}
+
+/// A file to convert.
+struct Input {
+    path: PathBuf,
+    /// Whether the file was found by walking a directory, in which case
+    /// binary files are skipped rather than reported.
+    discovered: bool,
+}
+
+/// Returns `true` if `data` looks like a binary file: it contains a NUL byte
+/// near the start or is not valid UTF-8.
+fn is_binary(data: &[u8]) -> bool {
+    data[..data.len().min(8192)].contains(&0) || std::str::from_utf8(data).is_err()
+}
+
+/// Collects the files below `dir` that pass the include and exclude filters,
+/// in sorted order. `relative` is the `/`-separated path of `dir` below the
+/// directory given on the command line.
+fn walk(dir: &Path, relative: &str, options: &Options, inputs: &mut Vec<Input>) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') {
+            continue;
+        }
+        let relative = match relative {
+            "" => name.into_owned(),
+            _  => format!("{}/{}", relative, name),
+        };
+        if options.excludes.iter().any(|glob| glob.matches(&relative)) {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            walk(&entry.path(), &relative, options, inputs)?;
+        } else if file_type.is_file()
+                  && (options.includes.is_empty()
+                      || options.includes.iter().any(|glob| glob.matches(&relative))) {
+            inputs.push(Input { path: entry.path(), discovered: true });
+        }
+    }
+    Ok(())
+}
+
+/// Expands the files and directories given on the command line.
+fn collect_inputs(options: &Options) -> Result<Vec<Input>, String> {
+    let mut inputs = Vec::new();
+    for path in &options.files {
+        if options.recursive && path.is_dir() {
+            walk(path, "", options, &mut inputs)
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+        } else {
+            inputs.push(Input { path: path.clone(), discovered: false });
+        }
+    }
+    Ok(inputs)
+}
+
+/// Returns `path` with `suffix` appended to its file name.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Replaces the contents of `path` with `contents` by writing a temporary
+/// file next to it and renaming it over the original, so readers never see a
+/// partially written file. If `backup_suffix` is given, the original is first
+/// copied to a file with that suffix.
+fn write_atomic(path: &Path, contents: &str, backup_suffix: Option<&str>) -> io::Result<()> {
+    let permissions = fs::metadata(path)?.permissions();
+    let temp = with_suffix(path, &format!(".hfwidth-{}.tmp", process::id()));
+    let result = (|| {
+        let mut file = fs::File::create(&temp)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::set_permissions(&temp, permissions)?;
+        if let Some(suffix) = backup_suffix {
+            fs::copy(path, with_suffix(path, suffix))?;
+        }
+        fs::rename(&temp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Returns what to print for an input when not editing in place: the
+/// converted text, a diff with `--diff`, or the violations with `--check`.
+fn display(options: &Options, path: &str, input: &str, output: &str, edits: &[Edit],
+           spans: Option<&[Range<usize>]>) -> String {
+    if options.check {
+        return check::violations(path, input, edits, spans);
+    }
+    match options.diff {
+        None                        => output.to_owned(),
+        Some(DiffStyle::Unified)    => diff::unified(path, input, output),
+        Some(DiffStyle::SideBySide) => diff::side_by_side(path, input, output),
+    }
+}
+
+/// Reads the file of `input`, returning `None` for skipped binary files.
+fn read_input(input: &Input) -> io::Result<Option<String>> {
+    let data = fs::read(&input.path)?;
+    if input.discovered && is_binary(&data) {
+        return Ok(None);
+    }
+    String::from_utf8(data)
+        .map(Some)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "not valid UTF-8"))
+}
+
+/// Bytes of standard input read at a time.
+const READ_LEN: usize = 64 * 1024;
+
+/// Converts `reader` to `writer` as it is read, so that the whole input is
+/// never held in memory. If `flush`, output is flushed after each record,
+/// ending with `delimiter`. The protectors of `converter` are not applied, as
+/// their spans may depend on the whole input; callers convert the input at
+/// once if there are any.
+fn stream<R: Read, W: Write>(mut reader: R, mut writer: W, converter: &WidthConverter,
+                             delimiter: u8, flush: bool) -> io::Result<()> {
+    let invalid = |_| io::Error::new(io::ErrorKind::InvalidData, "not valid UTF-8");
+    let mut stream = converter.stream();
+    let mut buf = vec![0; READ_LEN];
+    let mut out = String::new();
+    loop {
+        let len = match reader.read(&mut buf) {
+            Ok(0)                                            => break,
+            Ok(len)                                          => len,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e)                                           => return Err(e),
+        };
+        let mut data = &buf[..len];
+        while !data.is_empty() {
+            /* Records end with the delimiter, which flushes anything held back */
+            let end = match data.iter().position(|&byte| byte == delimiter) {
+                Some(pos) if flush => pos + 1,
+                _                  => data.len(),
+            };
+            stream.feed(&data[..end], &mut out).map_err(invalid)?;
+            writer.write_all(out.as_bytes())?;
+            out.clear();
+            if flush && data[end - 1] == delimiter {
+                writer.flush()?;
+            }
+            data = &data[end..];
+        }
+    }
+    stream.finish(&mut out).map_err(invalid)?;
+    writer.write_all(out.as_bytes())?;
+    writer.flush()
+}
+
+/// Returns the converter described by `--profile`, or by the target options.
+fn converter(options: &Options) -> Result<WidthConverter, String> {
+    match &options.profile {
+        #[cfg(any(feature = "toml", feature = "yaml"))]
+        Some(path) => Profile::load(path)
+            .map(|profile| profile.converter().clone())
+            .map_err(|e| format!("{}: {}", path.display(), e)),
+        #[cfg(not(any(feature = "toml", feature = "yaml")))]
+        Some(_) => Err("--profile requires building with the toml or yaml feature".to_owned()),
+        None => Ok(WidthConverter::new(options.policy.clone())),
+    }
+}
+
+/// Converts the inputs. Returns `false` if `--check` found violations.
+fn run(options: &Options) -> Result<bool, String> {
+    let converter = converter(options)?;
+    if options.files.is_empty() && options.is_plain() && !converter.has_protectors() {
+        let delimiter = if options.null { b'\0' } else { b'\n' };
+        return stream(io::stdin().lock(), io::stdout().lock(), &converter, delimiter,
+                      options.null || options.line_buffered)
+            .map(|()| true)
+            .map_err(|e| format!("stdin: {}", e));
+    }
+    let mut reports = Vec::new();
+    let mut clean = true;
+    let mut stdout = io::stdout().lock();
+    let mut inputs = collect_inputs(options)?.into_iter().map(Some).collect::<Vec<_>>();
+    if options.files.is_empty() {
+        inputs.push(None);
+    }
+    for source in inputs {
+        let (name, input) = match &source {
+            None => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input).map_err(|e| format!("stdin: {}", e))?;
+                ("-".to_owned(), input)
+            }
+            Some(file) => match read_input(file) {
+                Ok(Some(input)) => (file.path.display().to_string(), input),
+                Ok(None)        => continue,
+                Err(e)          => return Err(format!("{}: {}", file.path.display(), e)),
+            },
+        };
+        let (output, spans) = if options.csv {
+            let error = |e| format!("{}: {}", name, e);
+            (csv::convert(&input, &options.columns, &converter).map_err(error)?,
+             Some(csv::selected_spans(&input, &options.columns).map_err(error)?))
+        } else {
+            (converter.convert(&input), None)
+        };
+        let spans = check::unprotected(spans, input.len(), &converter.protected_spans(&input));
+        let edits = if options.check || options.report {
+            converter.diff(&input)
+        } else {
+            Vec::new()
+        };
+        clean &= output == input;
+        match source {
+            Some(file) if options.in_place => {
+                if output != input {
+                    write_atomic(&file.path, &output, options.backup_suffix.as_deref())
+                        .map_err(|e| format!("{}: {}", name, e))?;
+                }
+            }
+            _ => {
+                let text = display(options, &name, &input, &output, &edits, spans.as_deref());
+                stdout.write_all(text.as_bytes())
+                    .map_err(|e| format!("stdout: {}", e))?;
+            }
+        }
+        if options.report {
+            reports.push(FileReport::new(name, &input, &edits, spans.as_deref()));
+        }
+    }
+    if options.report {
+        let json = report::to_json(&reports);
+        let written = if options.in_place {
+            stdout.write_all(json.as_bytes())
+        } else {
+            io::stderr().write_all(json.as_bytes())
+        };
+        written.map_err(|e| format!("report: {}", e))?;
+    }
+    Ok(clean || !options.check)
+}
+
+fn main() {
+    let command = match parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("hfwidth: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+    match command {
+        Command::Help    => print!("{}", USAGE),
+        Command::Version => println!("hfwidth {}", env!("CARGO_PKG_VERSION")),
+        Command::Run(options) => {
+            match run(&options) {
+                Ok(true)  => {}
+                Ok(false) => process::exit(1),
+                Err(message) => {
+                    eprintln!("hfwidth: {}", message);
+                    process::exit(2);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn parse(args: &[&str]) -> Result<Command, String> {
+    parse_args(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn test_parse_in_place() {
+    let expected = Options { in_place: true, backup_suffix: Some(".bak".to_owned()),
+                             files: vec![PathBuf::from("a.txt")], ..Options::default() };
+    assert_eq!(parse(&["--in-place=.bak", "a.txt"]), Ok(Command::Run(expected)));
+    assert!(parse(&["-i"]).is_err());
+    assert!(parse(&["--in-place=", "a.txt"]).is_err());
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_parse_targets() {
+    let options = match parse(&["--ascii=half", "--kana=full", "--space=ascii", "--symbols=keep"]) {
+        Ok(Command::Run(options)) => options,
+        other => panic!("{:?}", other),
+    };
+    assert_eq!(options.policy.convert("ＡＢ\u{3000}ｶﾅ￥"), "AB カナ￥");
+    assert!(parse(&["--kana=ascii"]).is_err());
+    assert!(parse(&["--kana=fullwidth", "--space=halfwidth"]).is_ok());
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_stream() {
+    /// Reads a byte at a time.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = self.0.len().min(buf.len()).min(1);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    /// Records the length written at each flush.
+    #[derive(Default)]
+    struct Flushes(Vec<u8>, Vec<usize>);
+
+    impl Write for Flushes {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.1.push(self.0.len());
+            Ok(())
+        }
+    }
+
+    let mut out = Vec::new();
+    let converter = WidthConverter::default();
+    stream("ｶﾅ\0ＡＢ\0x".as_bytes(), &mut out, &converter, b'\0', true).unwrap();
+    assert_eq!(out, "カナ\0AB\0x".as_bytes());
+    let mut out = Flushes::default();
+    stream(Trickle("ｶﾞ\0ＡＢ\0x".as_bytes()), &mut out, &converter, b'\0', true).unwrap();
+    assert_eq!(out.0, "ガ\0AB\0x".as_bytes());
+    assert_eq!(out.1, [4, 7, 8]);
+    let converter = WidthConverter::default().map('～', '〜');
+    let mut out = Flushes::default();
+    stream(Trickle("ｱ～\nｲ".as_bytes()), &mut out, &converter, b'\n', false).unwrap();
+    assert_eq!(out.0, "ア〜\nイ".as_bytes());
+    assert_eq!(out.1, [out.0.len()]);
+    assert!(parse(&["--null", "--check"]).is_err());
+}
+
+#[test]
+fn test_write_atomic() {
+    let dir = env::temp_dir().join(format!("hfwidth-test-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("input.txt");
+    fs::write(&path, "ｶﾅ").unwrap();
+    write_atomic(&path, "カナ", Some(".bak")).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "カナ");
+    assert_eq!(fs::read_to_string(dir.join("input.txt.bak")).unwrap(), "ｶﾅ");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_collect_inputs() {
+    let dir = env::temp_dir().join(format!("hfwidth-walk-{}", process::id()));
+    fs::create_dir_all(dir.join("docs/.hidden")).unwrap();
+    fs::create_dir_all(dir.join("target")).unwrap();
+    for name in ["docs/a.md", "docs/b.txt", "docs/.hidden/c.md", "target/d.md", "e.md"] {
+        fs::write(dir.join(name), "").unwrap();
+    }
+    let args = ["-r", "--include=*.md", "--exclude=target", dir.to_str().unwrap()];
+    let options = match parse(&args) {
+        Ok(Command::Run(options)) => options,
+        other => panic!("{:?}", other),
+    };
+    let found: Vec<_> = collect_inputs(&options).unwrap().into_iter()
+        .map(|input| input.path.strip_prefix(&dir).unwrap().to_owned())
+        .collect();
+    assert_eq!(found, [Path::new("docs/a.md"), Path::new("e.md")]);
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(is_binary(b"\x89PNG\r\n\x1a\n\0\0"));
+    assert!(!is_binary("ｶﾅ".as_bytes()));
+}
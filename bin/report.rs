@@ -0,0 +1,121 @@
+//! Change reports for `--report`.
+
+use std::fmt::Write;
+use std::ops::Range;
+
+use unicode_hfwidth::{forms_category, Edit, FormsCategory};
+
+use super::check::in_spans;
+
+/// Returns the name used for `category` in reports and options.
+pub fn category_name(category: FormsCategory) -> &'static str {
+    match category {
+        FormsCategory::FullwidthAscii       => "fullwidth_ascii",
+        FormsCategory::FullwidthBrackets    => "fullwidth_brackets",
+        FormsCategory::HalfwidthPunctuation => "halfwidth_punctuation",
+        FormsCategory::HalfwidthKatakana    => "halfwidth_katakana",
+        FormsCategory::HalfwidthHangul      => "halfwidth_hangul",
+        FormsCategory::FullwidthSymbols     => "fullwidth_symbols",
+        FormsCategory::HalfwidthSymbols     => "halfwidth_symbols",
+    }
+}
+
+/// The changes made to one input.
+#[derive(Debug, Default, PartialEq)]
+pub struct FileReport {
+    pub path: String,
+    /// Number of converted characters per category, indexed like
+    /// `FormsCategory::ALL`, followed by characters outside the block.
+    pub counts: [usize; 8],
+    /// Byte offsets of the converted characters in the input.
+    pub offsets: Vec<usize>,
+}
+
+impl FileReport {
+    /// Records the characters of `input` replaced by `edits`. If `spans` is
+    /// given, only characters inside them are considered.
+    pub fn new(path: String, input: &str, edits: &[Edit], spans: Option<&[Range<usize>]>)
+               -> FileReport {
+        let mut report = FileReport { path, ..FileReport::default() };
+        for edit in edits {
+            let replaced = || edit.replacement.chars().find_map(forms_category);
+            for (i, ch) in input[edit.range.clone()].char_indices() {
+                let offset = edit.range.start + i;
+                if !in_spans(spans, offset) {
+                    continue;
+                }
+                let category = forms_category(ch).or_else(replaced);
+                report.counts[category.map_or(7, |category| category as usize)] += 1;
+                report.offsets.push(offset);
+            }
+        }
+        report
+    }
+
+    fn write_json(&self, out: &mut String) {
+        out.push_str("{\"path\":");
+        write_json_string(out, &self.path);
+        let _ = write!(out, ",\"changed\":{},\"categories\":{{", self.offsets.len());
+        let names = FormsCategory::ALL.iter().map(|&category| category_name(category));
+        let mut first = true;
+        for (name, &count) in names.chain(Some("other")).zip(&self.counts) {
+            if count > 0 {
+                let _ = write!(out, "{}\"{}\":{}", if first { "" } else { "," }, name, count);
+                first = false;
+            }
+        }
+        out.push_str("},\"offsets\":[");
+        for (i, offset) in self.offsets.iter().enumerate() {
+            let _ = write!(out, "{}{}", if i == 0 { "" } else { "," }, offset);
+        }
+        out.push_str("]}");
+    }
+}
+
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"'  => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ if (ch as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", ch as u32);
+            }
+            _ => out.push(ch),
+        }
+    }
+    out.push('"');
+}
+
+/// Renders the reports of all inputs as a JSON document.
+pub fn to_json(reports: &[FileReport]) -> String {
+    let mut out = String::from("{\"files\":[");
+    for (i, report) in reports.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        report.write_json(&mut out);
+    }
+    out.push_str("]}\n");
+    out
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_report_json() {
+    use unicode_hfwidth::{WidthConverter, WidthPolicy};
+
+    let converter = WidthConverter::new(WidthPolicy::default()).map('〜', '～');
+    let report = |input| FileReport::new("a\"b.txt".to_owned(), input, &converter.diff(input),
+                                         None);
+    assert_eq!(report("a〜b").offsets, [1]);
+    let report = report("ＡB ｶﾅ");
+    assert_eq!(report.offsets, [0, 5, 8]);
+    assert_eq!(to_json(&[report]),
+               "{\"files\":[{\"path\":\"a\\\"b.txt\",\"changed\":3,\
+                \"categories\":{\"fullwidth_ascii\":1,\"halfwidth_katakana\":2},\
+                \"offsets\":[0,5,8]}]}\n");
+}
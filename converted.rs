@@ -0,0 +1,149 @@
+//! Per-character conversions producing more than one character.
+
+use std::array;
+use std::fmt::{self, Write};
+use std::iter::Take;
+use std::ops::Deref;
+
+use super::{compose_kana, to_fullwidth, to_halfwidth};
+
+/// The one or two characters a character converts to, or decomposes to as
+/// returned by `nfkc_decomposition`.
+///
+/// `Converted` dereferences to a slice of the characters and can be iterated
+/// by value, so per-character conversions can be used with `flat_map`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Converted {
+    chars: [char; 2],
+    len: u8,
+}
+
+impl Converted {
+    /// Returns the conversion to a single character `ch`.
+    pub fn one(ch: char) -> Converted {
+        Converted { chars: [ch, '\0'], len: 1 }
+    }
+
+    /// Returns the conversion to `first` followed by `second`.
+    pub fn two(first: char, second: char) -> Converted {
+        Converted { chars: [first, second], len: 2 }
+    }
+
+    /// Returns the conversion to the characters of `s`, if `s` has one or two
+    /// characters.
+    pub(crate) fn from_short(s: &str) -> Option<Converted> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(first), None, _)            => Some(Converted::one(first)),
+            (Some(first), Some(second), None) => Some(Converted::two(first, second)),
+            _                                 => None,
+        }
+    }
+}
+
+impl From<char> for Converted {
+    fn from(ch: char) -> Converted {
+        Converted::one(ch)
+    }
+}
+
+impl Deref for Converted {
+    type Target = [char];
+
+    fn deref(&self) -> &[char] {
+        &self.chars[..self.len as usize]
+    }
+}
+
+impl fmt::Display for Converted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.iter().try_for_each(|&ch| f.write_char(ch))
+    }
+}
+
+impl IntoIterator for Converted {
+    type Item = char;
+    type IntoIter = Take<array::IntoIter<char, 2>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.chars.into_iter().take(self.len as usize)
+    }
+}
+
+impl<'a> IntoIterator for &'a Converted {
+    type Item = &'a char;
+    type IntoIter = std::slice::Iter<'a, char>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Returns the half-width form of `ch`, decomposing voiced and semi-voiced
+/// katakana into a half-width kana and a half-width sound mark, which have no
+/// single-character half-width form. Characters without a half-width form are
+/// returned unchanged.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::halfwidth_chars;
+///
+/// assert_eq!(halfwidth_chars('ガ')[..], ['ｶ', 'ﾞ']);
+/// assert_eq!(halfwidth_chars('Ａ')[..], ['A']);
+/// let s: String = "パンダ ＡＢ".chars().flat_map(halfwidth_chars).collect();
+/// assert_eq!(s, "ﾊﾟﾝﾀﾞ AB");
+/// # }
+/// ```
+pub fn halfwidth_chars(ch: char) -> Converted {
+    if let Some(half) = to_halfwidth(ch) {
+        return Converted::one(half);
+    }
+    decompose_kana(ch)
+        .and_then(|(base, mark)| Some(Converted::two(to_halfwidth(base)?, to_halfwidth(mark)?)))
+        .unwrap_or(Converted::one(ch))
+}
+
+/// Returns the full-width form of `ch`, or `ch` unchanged if it has none.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::fullwidth_chars;
+///
+/// let s: String = "ｶﾞ AB".chars().flat_map(fullwidth_chars).collect();
+/// assert_eq!(s, "カ\u{3099} ＡＢ");
+/// # }
+/// ```
+pub fn fullwidth_chars(ch: char) -> Converted {
+    Converted::one(to_fullwidth(ch).unwrap_or(ch))
+}
+
+/// Splits precomposed kana into the kana and the combining sound mark it is
+/// composed of.
+fn decompose_kana(ch: char) -> Option<(char, char)> {
+    let c = ch as u32;
+    for mark in ['\u{3099}', '\u{309a}'] {
+        for offset in [1, 2, 8, 0x4e] {
+            let base = c.checked_sub(offset).and_then(char::from_u32);
+            if let Some(base) = base.filter(|&base| compose_kana(base, mark) == Some(ch)) {
+                return Some((base, mark));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(feature = "kana")]
+#[test]
+fn test_halfwidth_chars() {
+    let full = "ガギグゲゴザジズゼゾダヂヅデドバビブベボパピプペポヴヷヺ";
+    let half = "ｶﾞｷﾞｸﾞｹﾞｺﾞｻﾞｼﾞｽﾞｾﾞｿﾞﾀﾞﾁﾞﾂﾞﾃﾞﾄﾞﾊﾞﾋﾞﾌﾞﾍﾞﾎﾞﾊﾟﾋﾟﾌﾟﾍﾟﾎﾟｳﾞﾜﾞｦﾞ";
+    assert_eq!(full.chars().flat_map(halfwidth_chars).collect::<String>(), half);
+    for ch in ['が', 'ヸ', 'ヾ', 'ㄱ', 'a', '漢'] {
+        assert_eq!(halfwidth_chars(ch), Converted::one(to_halfwidth(ch).unwrap_or(ch)));
+    }
+    assert_eq!(Converted::from_short("->"), Some(Converted::two('-', '>')));
+    assert_eq!(Converted::from_short("abc"), None);
+    assert_eq!(Converted::two('ｶ', 'ﾞ').to_string(), "ｶﾞ");
+}
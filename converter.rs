@@ -0,0 +1,556 @@
+//! String conversion with options beyond a `WidthPolicy`.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::trace::traced;
+use super::{StreamConverter, WidthPolicy};
+
+/// Finds spans of text that must not be converted.
+///
+/// Implemented for closures returning the spans, and for `regex::Regex` with
+/// the `regex` feature. Spans must lie on character boundaries.
+pub trait Protector {
+    /// Returns the byte ranges of `s` to leave unchanged.
+    fn protected_spans(&self, s: &str) -> Vec<Range<usize>>;
+}
+
+impl<F: Fn(&str) -> Vec<Range<usize>>> Protector for F {
+    fn protected_spans(&self, s: &str) -> Vec<Range<usize>> {
+        self(s)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl Protector for regex::Regex {
+    fn protected_spans(&self, s: &str) -> Vec<Range<usize>> {
+        self.find_iter(s).map(|m| m.range()).collect()
+    }
+}
+
+/// Returns the start of the run of characters matching `f` at the end of `s`.
+fn start_of_run<F: Fn(char) -> bool>(s: &str, f: F) -> usize {
+    s.char_indices().rev().find(|&(_, ch)| !f(ch)).map_or(0, |(i, ch)| i + ch.len_utf8())
+}
+
+/// Protects URLs with a scheme, such as `https://example.com/ｶﾀﾛｸﾞ`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Urls;
+
+impl Protector for Urls {
+    fn protected_spans(&self, s: &str) -> Vec<Range<usize>> {
+        let ends_url = |ch: char| {
+            ch.is_whitespace() || matches!(ch, '<' | '>' | '"' | '`' | '\u{3001}'..='\u{3011}')
+        };
+        let mut spans = Vec::new();
+        let mut from = 0;
+        while let Some(found) = s[from..].find("://") {
+            let colon = from + found;
+            let scheme = |ch: char| ch.is_ascii_alphanumeric() || matches!(ch, '+' | '-' | '.');
+            let start = start_of_run(&s[..colon], scheme);
+            let len = s[colon..].find(ends_url).unwrap_or(s.len() - colon);
+            let url = s[start..colon + len].trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+            if start < colon && s[start..].starts_with(|ch: char| ch.is_ascii_alphabetic())
+                && url.len() > colon + 3 - start {
+                spans.push(start..start + url.len());
+            }
+            from = colon + len.max(3);
+        }
+        spans
+    }
+}
+
+/// Protects email addresses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Emails;
+
+impl Protector for Emails {
+    fn protected_spans(&self, s: &str) -> Vec<Range<usize>> {
+        let local = |ch: char| {
+            ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '%' | '+' | '-')
+        };
+        let domain = |ch: char| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-');
+        let mut spans = Vec::new();
+        let mut from = 0;
+        while let Some(found) = s[from..].find('@') {
+            let at = from + found;
+            let user = s[start_of_run(&s[..at], local)..at].trim_start_matches('.');
+            let len = s[at + 1..].find(|ch| !domain(ch)).unwrap_or(s.len() - at - 1);
+            let host = s[at + 1..at + 1 + len].trim_end_matches('.');
+            if !user.is_empty() && host.contains('.') {
+                spans.push(at - user.len()..at + 1 + host.len());
+            }
+            from = at + 1;
+        }
+        spans
+    }
+}
+
+/// Protects template placeholders: `{name}`, `{{name}}`, `${name}`, and
+/// printf-style conversions such as `%s`, `%d` and `%1$s`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Placeholders;
+
+impl Protector for Placeholders {
+    fn protected_spans(&self, s: &str) -> Vec<Range<usize>> {
+        let bytes = s.as_bytes();
+        let mut spans = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'{' => {
+                    let start = if i > 0 && bytes[i - 1] == b'$' { i - 1 } else { i };
+                    let open = bytes[i..].iter().take_while(|&&b| b == b'{').count();
+                    let body = i + open;
+                    let close = bytes[body..].iter()
+                        .position(|&b| matches!(b, b'{' | b'}' | b'\n'))
+                        .map(|len| body + len);
+                    match close {
+                        Some(end) if end > body && bytes[end..].starts_with(&vec![b'}'; open]) => {
+                            spans.push(start..end + open);
+                            i = end + open;
+                        }
+                        _ => i = body,
+                    }
+                }
+                b'%' => {
+                    let len = bytes[i + 1..].iter()
+                        .position(|b| !(b.is_ascii_digit() || b"$-+ #.".contains(b)))
+                        .map(|len| i + 1 + len)
+                        .filter(|&end| b"sdifuxXoeEgGcp@%".contains(&bytes[end]));
+                    match len {
+                        Some(end) => {
+                            spans.push(i..end + 1);
+                            i = end + 1;
+                        }
+                        None => i += 1,
+                    }
+                }
+                _ => i += 1,
+            }
+        }
+        spans
+    }
+}
+
+/// Protects the text between pairs of quotation marks, such as `「` and `」`.
+///
+/// Pairs with distinct marks may nest. For pairs using the same mark twice, a
+/// mark directly after a letter or digit does not open a quotation, so that
+/// apostrophes in words like `don't` are not mistaken for quotes. A
+/// quotation must close on the line it opens on. An opening mark left
+/// unclosed protects nothing, and quotations after it are still found.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "kana")] {
+/// use unicode_hfwidth::{Quotes, WidthConverter, WidthPolicy};
+///
+/// let converter = WidthConverter::new(WidthPolicy::default()).protect(Quotes::default());
+/// assert_eq!(converter.convert("ﾎﾞﾀﾝ「ＯＫ」を押す"), "ボタン「ＯＫ」を押す");
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quotes {
+    pairs: Vec<(char, char)>,
+}
+
+impl Default for Quotes {
+    /// Returns `「」`, `『』`, `""` and `''`.
+    fn default() -> Quotes {
+        Quotes::new(&[('「', '」'), ('『', '』'), ('"', '"'), ('\'', '\'')])
+    }
+}
+
+impl Quotes {
+    /// Creates a protector for the given pairs of opening and closing marks.
+    pub fn new(pairs: &[(char, char)]) -> Quotes {
+        Quotes { pairs: pairs.to_vec() }
+    }
+
+    /// Returns the position of the closing mark of each opening mark in `s`
+    /// that has one on its line, keyed by the index of the pair and the
+    /// position of the opening mark. Marks of the same pair nest.
+    fn closing_marks(&self, s: &str) -> HashMap<(usize, usize), usize> {
+        let mut closes = HashMap::new();
+        let mut open = vec![Vec::new(); self.pairs.len()];
+        for (i, ch) in s.char_indices() {
+            if matches!(ch, '\n' | '\r') {
+                open.iter_mut().for_each(Vec::clear);
+                continue;
+            }
+            for (k, &(opening, close)) in self.pairs.iter().enumerate() {
+                if ch == close {
+                    if let Some(start) = open[k].pop() {
+                        closes.insert((k, start), i);
+                    }
+                }
+                if ch == opening {
+                    open[k].push(i);
+                }
+            }
+        }
+        closes
+    }
+}
+
+impl Protector for Quotes {
+    fn protected_spans(&self, s: &str) -> Vec<Range<usize>> {
+        let closes = self.closing_marks(s);
+        let mut spans = Vec::new();
+        let mut pos = 0;
+        let mut prev = None;
+        for (i, ch) in s.char_indices() {
+            if i >= pos {
+                let word = prev.is_some_and(char::is_alphanumeric);
+                let pair = self.pairs.iter()
+                    .position(|&(opening, close)| ch == opening && !(opening == close && word));
+                /* An opening mark left open is literal, and the text after it is searched */
+                if let Some(&close) = pair.and_then(|k| closes.get(&(k, i))) {
+                    spans.push(i + ch.len_utf8()..close);
+                    pos = close + 1;
+                }
+            }
+            prev = Some(ch);
+        }
+        spans
+    }
+}
+
+/// Bytes of input converted between progress reports and cancellation checks.
+const CHUNK_LEN: usize = 64 * 1024;
+
+/// A flag for aborting a conversion from another thread.
+///
+/// Clones share the flag, so a clone can be kept to cancel a conversion run
+/// by a `WidthConverter` created with `cancel_with`.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancels conversions checking the token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if `cancel` has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The error returned when a conversion is cancelled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("conversion cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+/// Converts strings according to a `WidthPolicy`, with additional options.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// use unicode_hfwidth::{WidthConverter, WidthPolicy, WidthTarget};
+///
+/// let policy = WidthPolicy::builder().all(WidthTarget::Fullwidth).build();
+/// let converter = WidthConverter::new(policy).protect_defaults();
+/// assert_eq!(converter.convert("Hi {name}, see https://example.com/a"),
+///            "Ｈｉ {name}， ｓｅｅ https://example.com/a");
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct WidthConverter {
+    policy: WidthPolicy,
+    protectors: Vec<Arc<dyn Protector + Send + Sync>>,
+    progress: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    cancel: Option<CancellationToken>,
+    overrides: HashMap<char, char>,
+}
+
+impl fmt::Debug for WidthConverter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WidthConverter")
+            .field("policy", &self.policy)
+            .field("protectors", &self.protectors.len())
+            .field("progress", &self.progress.is_some())
+            .field("cancel", &self.cancel)
+            .field("overrides", &self.overrides)
+            .finish()
+    }
+}
+
+impl WidthConverter {
+    /// Creates a converter applying `policy`.
+    pub fn new(policy: WidthPolicy) -> WidthConverter {
+        WidthConverter { policy, ..WidthConverter::default() }
+    }
+
+    /// Returns the policy the converter applies.
+    pub fn policy(&self) -> &WidthPolicy {
+        &self.policy
+    }
+
+    /// Leaves the spans found by `protector` unchanged.
+    pub fn protect<P: Protector + Send + Sync + 'static>(mut self, protector: P) -> WidthConverter {
+        self.protectors.push(Arc::new(protector));
+        self
+    }
+
+    /// Leaves URLs, email addresses and placeholders unchanged.
+    pub fn protect_defaults(self) -> WidthConverter {
+        self.protect(Urls).protect(Emails).protect(Placeholders)
+    }
+
+    /// Returns `true` if any protector was added.
+    pub fn has_protectors(&self) -> bool {
+        !self.protectors.is_empty()
+    }
+
+    /// Calls `progress` with the number of bytes of input processed so far,
+    /// every 64 KiB and at the end of each conversion.
+    pub fn on_progress<F: Fn(usize) + Send + Sync + 'static>(mut self, progress: F)
+                                                           -> WidthConverter {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// Makes `try_convert` stop with an error once `token` is cancelled. The
+    /// token is checked every 64 KiB of input.
+    pub fn cancel_with(mut self, token: CancellationToken) -> WidthConverter {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Converts `from` to `to`, overriding the policy, such as to follow a
+    /// house style the built-in tables don't. Mapping `from` to itself leaves
+    /// it unchanged.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "latin")] {
+    /// use unicode_hfwidth::{WidthConverter, WidthPolicy};
+    ///
+    /// let converter = WidthConverter::new(WidthPolicy::default()).map('～', '〜');
+    /// assert_eq!(converter.convert("ＡＢ～ＣＤ"), "AB〜CD");
+    /// # }
+    /// ```
+    pub fn map(mut self, from: char, to: char) -> WidthConverter {
+        self.overrides.insert(from, to);
+        self
+    }
+
+    /// Adds each pair of `mappings` like `map`.
+    pub fn mappings<I: IntoIterator<Item = (char, char)>>(mut self, mappings: I) -> WidthConverter {
+        self.overrides.extend(mappings);
+        self
+    }
+
+    /// Returns the character `ch` is converted to if it is mapped with `map`.
+    pub fn mapping(&self, ch: char) -> Option<char> {
+        self.overrides.get(&ch).copied()
+    }
+
+    /// Returns a converter for input fed in chunks, applying the policy and
+    /// the mappings added with `map`. Protectors are not applied, as the
+    /// spans they find can depend on the whole input.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "latin")] {
+    /// use unicode_hfwidth::{WidthConverter, WidthPolicy};
+    ///
+    /// let converter = WidthConverter::new(WidthPolicy::default()).map('～', '〜');
+    /// let mut stream = converter.stream();
+    /// let mut out = String::new();
+    /// stream.feed("ＡＢ～".as_bytes(), &mut out).unwrap();
+    /// stream.feed("ＣＤ".as_bytes(), &mut out).unwrap();
+    /// stream.finish(&mut out).unwrap();
+    /// assert_eq!(out, "AB〜CD");
+    /// # }
+    /// ```
+    pub fn stream(&self) -> StreamConverter {
+        StreamConverter::with_mappings(self.policy.clone(), self.overrides.clone())
+    }
+
+    /// Returns the spans of `s` left unchanged, sorted by start. Spans may
+    /// overlap.
+    pub fn protected_spans<S: AsRef<str>>(&self, s: S) -> Vec<Range<usize>> {
+        let s = s.as_ref();
+        let mut spans: Vec<_> = self.protectors.iter()
+            .flat_map(|protector| protector.protected_spans(s))
+            .collect();
+        spans.sort_by_key(|span| span.start);
+        spans
+    }
+
+    /// Converts `s`, reporting progress but ignoring cancellation.
+    pub fn convert<S: AsRef<str>>(&self, s: S) -> String {
+        let s = s.as_ref();
+        traced("WidthConverter::convert", s, || self.run(s, false).unwrap())
+    }
+
+    /// Converts `s`, or returns an error if the converter's cancellation
+    /// token is cancelled before the conversion is done.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "latin")] {
+    /// use unicode_hfwidth::{CancellationToken, WidthConverter, WidthPolicy};
+    ///
+    /// let token = CancellationToken::new();
+    /// let converter = WidthConverter::new(WidthPolicy::default())
+    ///     .cancel_with(token.clone())
+    ///     .on_progress(move |done| if done > 100_000 { token.cancel() });
+    /// assert_eq!(converter.try_convert("ＡＢＣ").unwrap(), "ABC");
+    /// assert!(converter.try_convert(&"ｶﾅ".repeat(100_000)).is_err());
+    /// # }
+    /// ```
+    pub fn try_convert<S: AsRef<str>>(&self, s: S) -> Result<String, Cancelled> {
+        let s = s.as_ref();
+        traced("WidthConverter::try_convert", s, || self.run(s, true))
+    }
+
+    /// Converts `s` in chunks, reporting progress and checking for
+    /// cancellation between them if `cancellable`.
+    fn run(&self, s: &str, cancellable: bool) -> Result<String, Cancelled> {
+        let cancelled = || cancellable && self.cancel.as_ref().is_some_and(|t| t.is_cancelled());
+        let report = |done| if let Some(progress) = &self.progress { progress(done) };
+        if cancelled() {
+            return Err(Cancelled);
+        }
+        let mut out = if self.overrides.is_empty() && self.protectors.is_empty() {
+            String::with_capacity(self.policy.capacity_for(s))
+        } else {
+            String::with_capacity(s.len())
+        };
+        let mut next_check = CHUNK_LEN;
+        let protected = self.protected_spans(s);
+        self.policy.push_except(s, &protected, |ch| self.mapping(ch), &mut out, |i| {
+            if i >= next_check {
+                report(i);
+                if cancelled() {
+                    return Err(Cancelled);
+                }
+                next_check = i + CHUNK_LEN;
+            }
+            Ok(())
+        })?;
+        report(s.len());
+        Ok(out)
+    }
+}
+
+#[test]
+fn test_protectors() {
+    let s = "mail ａ.b+c@example.co.jp. or see http://ex.com/ｶﾅ、then {{x}} ${y} %1$s 50%";
+    let found = |spans: Vec<Range<usize>>| -> Vec<&str> {
+        spans.into_iter().map(|span| &s[span]).collect()
+    };
+    assert_eq!(found(Urls.protected_spans(s)), ["http://ex.com/ｶﾅ"]);
+    assert_eq!(found(Emails.protected_spans(s)), ["b+c@example.co.jp"]);
+    assert_eq!(found(Placeholders.protected_spans(s)), ["{{x}}", "${y}", "%1$s"]);
+}
+
+#[cfg(feature = "latin")]
+#[test]
+fn test_converter_protect() {
+    let converter = WidthConverter::new(WidthPolicy::default())
+        .protect(|s: &str| s.match_indices("ＳＫＵ").map(|(i, m)| i..i + m.len()).collect());
+    assert_eq!(converter.convert("ＳＫＵ-ＳＫＵ１"), "ＳＫＵ-ＳＫＵ1");
+    assert!(converter.has_protectors());
+    assert!(!WidthConverter::default().has_protectors());
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_converter_map() {
+    let converter = WidthConverter::new(WidthPolicy::default())
+        .mappings([('～', '〜'), ('〜', '～'), ('ﾞ', 'ﾞ')])
+        .protect(Urls);
+    assert_eq!(converter.convert("Ａ～Ｂ〜ｶﾞﾊﾞ http://x/～"), "A〜B～カﾞハﾞ http://x/～");
+    assert_eq!(converter.mapping('～'), Some('〜'));
+    assert_eq!(converter.mapping('Ａ'), None);
+}
+
+#[test]
+fn test_quotes() {
+    let s = "don't 「ａ「ｂ」ｃ」 'ｄ' \"ｅ";
+    let spans = Quotes::default().protected_spans(s);
+    let found: Vec<_> = spans.into_iter().map(|span| &s[span]).collect();
+    assert_eq!(found, ["ａ「ｂ」ｃ", "ｄ"]);
+    let s = "「ａ 『ｂ』\n「ｃ」 「ｄ 『ｅ』 「ｆ";
+    let spans = Quotes::default().protected_spans(s);
+    let found: Vec<_> = spans.into_iter().map(|span| &s[span]).collect();
+    assert_eq!(found, ["ｂ", "ｃ", "ｅ"]);
+    let s = format!("{}」 \"ａ 'ｂ'", "「".repeat(100_000));
+    let spans = Quotes::default().protected_spans(&s);
+    let found: Vec<_> = spans.into_iter().map(|span| &s[span]).collect();
+    assert_eq!(found, ["", "ｂ"]);
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_converter_paths_agree() {
+    use super::{MinusMapping, QuoteMapping};
+
+    let policy = WidthPolicy::builder()
+        .quotes(QuoteMapping::Curly)
+        .minus(MinusMapping::HyphenMinus)
+        .contextual_prolonged_mark(true)
+        .build();
+    let converter = WidthConverter::new(policy).protect(Placeholders).map('〜', '～');
+    let tracked = converter.clone().on_progress(|_| {});
+    for s in ["{ｶ}＇", "{ｶ}ｰ", "ｱ{x}ｰ１", "〜＂ａ＂ {0}＂", "ｶ{0}ﾞ", "＂{x}＂"] {
+        let converted = converter.convert(s);
+        assert_eq!(tracked.convert(s), converted, "{:?}", s);
+        assert_eq!(converter.try_convert(s).unwrap(), converted, "{:?}", s);
+        let mut patched = s.to_owned();
+        for edit in converter.diff(s).iter().rev() {
+            patched.replace_range(edit.range.clone(), &edit.replacement);
+        }
+        assert_eq!(patched, converted, "{:?}", s);
+    }
+    assert_eq!(converter.convert("{ｶ}＇"), "{ｶ}’");
+}
+
+#[test]
+fn test_progress() {
+    use std::sync::Mutex;
+
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let log = reports.clone();
+    let converter = WidthConverter::new(WidthPolicy::default())
+        .protect(Urls)
+        .on_progress(move |done| log.lock().unwrap().push(done));
+    let s = format!("{} http://ex.com/ｶ {}", "ｶﾞ".repeat(20_000), "Ａ".repeat(30_000));
+    let expected = WidthConverter::new(WidthPolicy::default()).protect(Urls).convert(&s);
+    assert_eq!(converter.convert(&s), expected);
+    let reports = reports.lock().unwrap();
+    assert!(reports.len() > 2 && reports.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(reports.last(), Some(&s.len()));
+}
+
+#[cfg(feature = "latin")]
+#[test]
+fn test_cancel() {
+    let token = CancellationToken::new();
+    let converter = WidthConverter::new(WidthPolicy::default()).cancel_with(token.clone());
+    token.cancel();
+    assert_eq!(converter.try_convert("ＡＢ"), Err(Cancelled));
+    assert_eq!(converter.convert("ＡＢ"), "AB");
+}
@@ -0,0 +1,68 @@
+//! The characters Windows code page 932 decodes differently from JIS.
+
+/// Characters JIS-based Shift_JIS decoders produce, and the characters
+/// Windows CP932 decodes the same bytes to.
+const PAIRS: [(char, char); 9] = [
+    ('\u{301c}', '\u{ff5e}'), /* 〜 wave dash, ～ full-width tilde */
+    ('\u{2016}', '\u{2225}'), /* ‖ double vertical line, ∥ parallel to */
+    ('\u{2212}', '\u{ff0d}'), /* − minus sign, － full-width hyphen-minus */
+    ('\u{2014}', '\u{2015}'), /* — em dash, ― horizontal bar */
+    ('\u{00a2}', '\u{ffe0}'), /* ¢ cent sign, ￠ full-width cent sign */
+    ('\u{00a3}', '\u{ffe1}'), /* £ pound sign, ￡ full-width pound sign */
+    ('\u{00ac}', '\u{ffe2}'), /* ¬ not sign, ￢ full-width not sign */
+    ('\u{00a5}', '\u{005c}'), /* ¥ yen sign, \ reverse solidus, byte 0x5C */
+    ('\u{203e}', '\u{007e}'), /* ‾ overline, ~ tilde, byte 0x7E */
+];
+
+/// Replaces the characters of `s` that JIS-based Shift_JIS and EUC-JP
+/// decoders produce with the characters Windows code page 932 decodes the
+/// same bytes to, so text from those systems can be encoded as CP932 without
+/// unmappable characters or changed bytes. The wave dash `〜` becomes `～`,
+/// the minus sign `−` becomes `－`, and the yen sign `¥` of JIS X 0201
+/// becomes `\`, which CP932 fonts draw as a yen sign.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::to_cp932_compatible("10〜20 −5 ¥100"), "10～20 －5 \\100");
+/// ```
+pub fn to_cp932_compatible<S: AsRef<str>>(s: S) -> String {
+    s.as_ref().chars()
+        .map(|ch| PAIRS.iter().find(|&&(jis, _)| jis == ch).map_or(ch, |&(_, windows)| windows))
+        .collect()
+}
+
+/// Replaces the characters of `s` that Windows code page 932 decodes
+/// differently from JIS-based decoders with the JIS characters, the reverse
+/// of `to_cp932_compatible`, so text from Windows systems round-trips through
+/// JIS-based Shift_JIS and EUC-JP. ASCII `\` and `~` are left unchanged, as
+/// they usually are a backslash and a tilde rather than a yen sign and an
+/// overline.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::from_cp932_compatible("10～20 －5 C:\\"), "10〜20 −5 C:\\");
+/// ```
+pub fn from_cp932_compatible<S: AsRef<str>>(s: S) -> String {
+    s.as_ref().chars()
+        .map(|ch| match PAIRS.iter().find(|&&(_, windows)| windows == ch) {
+            Some(&(jis, _)) if !ch.is_ascii() => jis,
+            _                                 => ch,
+        })
+        .collect()
+}
+
+#[test]
+fn test_cp932_compatible() {
+    let jis = "〜‖−—¢£¬";
+    let windows = "～∥－―￠￡￢";
+    assert_eq!(to_cp932_compatible(jis), windows);
+    assert_eq!(from_cp932_compatible(windows), jis);
+    assert_eq!(to_cp932_compatible("‾¥"), "~\\");
+    assert_eq!(from_cp932_compatible("~\\"), "~\\");
+    #[cfg(feature = "encoding")]
+    {
+        let (bytes, _, unmappable) = encoding_rs::SHIFT_JIS.encode(windows);
+        assert!(!unmappable);
+        assert_eq!(encoding_rs::SHIFT_JIS.decode(&bytes).0, windows);
+    }
+}
@@ -0,0 +1,752 @@
+//! Display width measurement and layout in terminal columns.
+
+use std::borrow::Cow;
+
+use super::{to_fullwidth, to_halfwidth};
+
+/// Ranges of zero-width characters: controls, combining marks, zero-width
+/// format characters and variation selectors.
+static ZERO_WIDTH: &[(u32, u32)] = &[
+    (0x0000, 0x001f), (0x007f, 0x009f), (0x0300, 0x036f), (0x0483, 0x0489),
+    (0x0591, 0x05bd), (0x1160, 0x11ff), (0x1ab0, 0x1aff), (0x1dc0, 0x1dff),
+    (0x200b, 0x200f), (0x2028, 0x202e), (0x2060, 0x2064), (0x20d0, 0x20ff),
+    (0x302a, 0x302d), (0x3099, 0x309a), (0xfe00, 0xfe0f), (0xfe20, 0xfe2f),
+    (0xfeff, 0xfeff), (0xe0000, 0xe007f), (0xe0100, 0xe01ef),
+];
+
+/// Ranges of East Asian Wide and Fullwidth characters.
+static WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115f), (0x231a, 0x231b), (0x2329, 0x232a), (0x23e9, 0x23ec),
+    (0x23f0, 0x23f0), (0x23f3, 0x23f3), (0x25fd, 0x25fe), (0x2614, 0x2615),
+    (0x2648, 0x2653), (0x267f, 0x267f), (0x2693, 0x2693), (0x26a1, 0x26a1),
+    (0x26aa, 0x26ab), (0x26bd, 0x26be), (0x26c4, 0x26c5), (0x26ce, 0x26ce),
+    (0x26d4, 0x26d4), (0x26ea, 0x26ea), (0x26f2, 0x26f3), (0x26f5, 0x26f5),
+    (0x26fa, 0x26fa), (0x26fd, 0x26fd), (0x2705, 0x2705), (0x270a, 0x270b),
+    (0x2728, 0x2728), (0x274c, 0x274c), (0x274e, 0x274e), (0x2753, 0x2755),
+    (0x2757, 0x2757), (0x2795, 0x2797), (0x27b0, 0x27b0), (0x27bf, 0x27bf),
+    (0x2b1b, 0x2b1c), (0x2b50, 0x2b50), (0x2b55, 0x2b55), (0x2e80, 0x303e),
+    (0x3041, 0x33ff), (0x3400, 0x4dbf), (0x4e00, 0x9fff), (0xa000, 0xa4cf),
+    (0xa960, 0xa97f), (0xac00, 0xd7a3), (0xf900, 0xfaff), (0xfe10, 0xfe19),
+    (0xfe30, 0xfe6f), (0xff00, 0xff60), (0xffe0, 0xffe6), (0x16fe0, 0x16fe4),
+    (0x17000, 0x18aff), (0x1b000, 0x1b2ff), (0x1f004, 0x1f004), (0x1f0cf, 0x1f0cf),
+    (0x1f18e, 0x1f18e), (0x1f191, 0x1f19a), (0x1f200, 0x1f202), (0x1f210, 0x1f23b),
+    (0x1f240, 0x1f248), (0x1f250, 0x1f251), (0x1f260, 0x1f265), (0x1f300, 0x1f64f),
+    (0x1f680, 0x1f6ff), (0x1f7e0, 0x1f7eb), (0x1f900, 0x1f9ff), (0x1fa70, 0x1faff),
+    (0x20000, 0x2fffd), (0x30000, 0x3fffd),
+];
+
+fn in_table(table: &[(u32, u32)], cp: u32) -> bool {
+    table.binary_search_by(|&(lo, hi)| {
+        if hi < cp {
+            std::cmp::Ordering::Less
+        } else if lo > cp {
+            std::cmp::Ordering::Greater
+        } else {
+            std::cmp::Ordering::Equal
+        }
+    }).is_ok()
+}
+
+/// Returns the number of terminal columns `ch` occupies: 2 for East Asian
+/// wide and full-width characters, 0 for control characters and combining
+/// marks, and 1 otherwise.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::char_width('a'), 1);
+/// assert_eq!(unicode_hfwidth::char_width('ｶ'), 1);
+/// assert_eq!(unicode_hfwidth::char_width('カ'), 2);
+/// assert_eq!(unicode_hfwidth::char_width('ａ'), 2);
+/// ```
+pub fn char_width(ch: char) -> usize {
+    let cp = ch as u32;
+    if (0x20..0x7f).contains(&cp) {
+        1
+    } else if in_table(ZERO_WIDTH, cp) {
+        0
+    } else if in_table(WIDE, cp) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Returns the number of terminal columns `s` occupies.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::str_width("abc"), 3);
+/// assert_eq!(unicode_hfwidth::str_width("カタカナ"), 8);
+/// assert_eq!(unicode_hfwidth::str_width("ｶﾀｶﾅ"), 4);
+/// ```
+pub fn str_width<S: AsRef<str>>(s: S) -> usize {
+    let s = s.as_ref();
+    s.chars().map(char_width).sum()
+}
+
+/// Iterator over the characters of a string outside ANSI escape sequences.
+struct VisibleChars<'a> {
+    chars: std::str::CharIndices<'a>,
+}
+
+impl VisibleChars<'_> {
+    /// Skips the rest of a CSI sequence, up to and including its final byte.
+    fn skip_csi(&mut self) {
+        for (_, ch) in self.chars.by_ref() {
+            if ('\x40'..='\x7e').contains(&ch) {
+                break;
+            }
+        }
+    }
+
+    /// Skips the rest of an escape sequence with intermediate bytes, such as
+    /// a character set designation, up to and including its final byte.
+    fn skip_intermediates(&mut self) {
+        for (_, ch) in self.chars.by_ref() {
+            if !('\x20'..='\x2f').contains(&ch) {
+                break;
+            }
+        }
+    }
+
+    /// Skips the rest of an OSC or other string sequence, up to and including
+    /// its BEL or ST terminator.
+    fn skip_string(&mut self) {
+        while let Some((_, ch)) = self.chars.next() {
+            match ch {
+                '\x07' | '\u{9c}' => break,
+                '\x1b' => {
+                    if self.chars.clone().next().map(|(_, ch)| ch) == Some('\\') {
+                        self.chars.next();
+                    }
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Iterator for VisibleChars<'_> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        loop {
+            let (i, ch) = self.chars.next()?;
+            match ch {
+                '\x1b' => match self.chars.next() {
+                    Some((_, '['))                          => self.skip_csi(),
+                    Some((_, ']' | 'P' | 'X' | '^' | '_')) => self.skip_string(),
+                    Some((_, '\x20'..='\x2f'))              => self.skip_intermediates(),
+                    _                                       => {}
+                },
+                '\u{9b}' => self.skip_csi(),
+                '\u{9d}' => self.skip_string(),
+                _ => return Some((i, ch)),
+            }
+        }
+    }
+}
+
+fn visible_chars(s: &str) -> VisibleChars<'_> {
+    VisibleChars { chars: s.char_indices() }
+}
+
+/// Returns the number of terminal columns `s` occupies, ignoring ANSI escape
+/// sequences such as CSI color codes and OSC hyperlinks.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::str_width_ansi("\x1b[31mカナ\x1b[0m"), 4);
+/// assert_eq!(unicode_hfwidth::str_width_ansi("\x1b]8;;https://example.com\x07リンク\x1b]8;;\x07"), 6);
+/// ```
+pub fn str_width_ansi<S: AsRef<str>>(s: S) -> usize {
+    let s = s.as_ref();
+    visible_chars(s).map(|(_, ch)| char_width(ch)).sum()
+}
+
+/// Returns `s` with ANSI escape sequences removed.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::strip_ansi("\x1b[1;32mＯＫ\x1b[m"), "ＯＫ");
+/// ```
+pub fn strip_ansi(s: &str) -> Cow<'_, str> {
+    if !s.contains(['\x1b', '\u{9b}', '\u{9d}']) {
+        return Cow::Borrowed(s);
+    }
+    Cow::Owned(visible_chars(s).map(|(_, ch)| ch).collect())
+}
+
+/// The result of `shrink_to_width` or `expand_to_width`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fitted {
+    /// The converted text.
+    pub text: String,
+    /// Whether `text` fits in the requested width.
+    pub fits: bool,
+}
+
+/// Returns `true` if `ch` is a half-width katakana voiced or semi-voiced sound
+/// mark.
+fn is_halfwidth_sound_mark(ch: char) -> bool {
+    matches!(ch, '\u{ff9e}' | '\u{ff9f}')
+}
+
+/// Converts full-width characters of `s` to half-width, left to right, until
+/// it fits in `width` terminal columns. Characters are only converted when
+/// that saves a column, so the rest of the text keeps its original form.
+///
+/// If `s` cannot be made to fit, every convertible character is converted and
+/// `fits` is `false`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "kana")] {
+/// let fitted = unicode_hfwidth::shrink_to_width("カタカナ", 6);
+/// assert_eq!(fitted.text, "ｶﾀカナ");
+/// assert!(fitted.fits);
+/// assert!(!unicode_hfwidth::shrink_to_width("漢字", 3).fits);
+/// # }
+/// ```
+pub fn shrink_to_width<S: AsRef<str>>(s: S, width: usize) -> Fitted {
+    let s = s.as_ref();
+    let mut excess = str_width(s).saturating_sub(width);
+    let text = s.chars().map(|ch| {
+        if excess == 0 {
+            return ch;
+        }
+        match to_halfwidth(ch) {
+            Some(half) if char_width(ch) == 2 && char_width(half) == 1 => {
+                excess -= 1;
+                half
+            }
+            _ => ch,
+        }
+    }).collect();
+    Fitted { text, fits: excess == 0 }
+}
+
+/// Converts half-width characters of `s` to full-width, left to right, for as
+/// long as the result still fits in `width` terminal columns. Half-width kana
+/// followed by a sound mark are left alone, since converting them would not
+/// widen the text.
+///
+/// `fits` is `false` only if `s` was already wider than `width`, in which case
+/// it is returned unchanged.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "kana")] {
+/// let fitted = unicode_hfwidth::expand_to_width("ｶﾀｶﾅ", 6);
+/// assert_eq!(fitted.text, "カタｶﾅ");
+/// assert!(fitted.fits);
+/// # }
+/// ```
+pub fn expand_to_width<S: AsRef<str>>(s: S, width: usize) -> Fitted {
+    let s = s.as_ref();
+    let used = str_width(s);
+    if used > width {
+        return Fitted { text: s.to_owned(), fits: false };
+    }
+    let mut slack = width - used;
+    let mut chars = s.chars().peekable();
+    let mut text = String::with_capacity(s.len());
+    while let Some(ch) = chars.next() {
+        let before_mark = chars.peek().is_some_and(|&next| is_halfwidth_sound_mark(next));
+        match to_fullwidth(ch) {
+            Some(full) if slack > 0 && !before_mark && !is_halfwidth_sound_mark(ch)
+                          && char_width(ch) == 1 && char_width(full) == 2 => {
+                slack -= 1;
+                text.push(full);
+            }
+            _ => text.push(ch),
+        }
+    }
+    Fitted { text, fits: true }
+}
+
+/// Returns the terminal column the cursor is at after rendering `s` from
+/// column 0.
+///
+/// Wide characters advance the cursor by two columns, tabs advance it to the
+/// next multiple of `tab_width`, line feeds and carriage returns move it back
+/// to column 0, and other control characters do not move it.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::cursor_column("ｶﾅ\tカナ", 8), 12);
+/// assert_eq!(unicode_hfwidth::cursor_column("一行目\n二", 8), 2);
+/// ```
+pub fn cursor_column<S: AsRef<str>>(s: S, tab_width: usize) -> usize {
+    let s = s.as_ref();
+    s.chars().fold(0, |column, ch| advance(column, ch, tab_width))
+}
+
+/// Returns the column the cursor is at after rendering `ch` at `column`.
+fn advance(column: usize, ch: char, tab_width: usize) -> usize {
+    match ch {
+        '\t' if tab_width > 0 => (column / tab_width + 1) * tab_width,
+        '\n' | '\r'           => 0,
+        _                     => column + char_width(ch),
+    }
+}
+
+/// Returns the column at which the character at byte index `index` of `s`
+/// starts when `s` is rendered from column 0, moving the cursor as
+/// `cursor_column` does. An index inside a character counts as the start of
+/// the character, and an index past the end gives the column after `s`.
+///
+/// # Example
+/// ```rust
+/// let s = "aカｶb\tc";
+/// assert_eq!(unicode_hfwidth::column_at_byte(s, 1, 8), 1);
+/// assert_eq!(unicode_hfwidth::column_at_byte(s, 4, 8), 3);
+/// assert_eq!(unicode_hfwidth::column_at_byte(s, 7, 8), 4);
+/// assert_eq!(unicode_hfwidth::column_at_byte(s, 9, 8), 8);
+/// ```
+pub fn column_at_byte<S: AsRef<str>>(s: S, index: usize, tab_width: usize) -> usize {
+    let s = s.as_ref();
+    s.char_indices()
+        .take_while(|&(i, ch)| i + ch.len_utf8() <= index)
+        .fold(0, |column, (_, ch)| advance(column, ch, tab_width))
+}
+
+/// Returns the byte index of the character occupying `column` of the line
+/// `s` when it is rendered from column 0, moving the cursor as
+/// `cursor_column` does. A column in the middle of a wide character or a tab
+/// gives the start of the character, and a column past the end gives the
+/// length of `s`. Zero-width characters belong to the character before them.
+///
+/// # Example
+/// ```rust
+/// let s = "aカｶ\tb";
+/// assert_eq!(unicode_hfwidth::byte_at_column(s, 1, 8), 1);
+/// assert_eq!(unicode_hfwidth::byte_at_column(s, 2, 8), 1);
+/// assert_eq!(unicode_hfwidth::byte_at_column(s, 3, 8), 4);
+/// assert_eq!(unicode_hfwidth::byte_at_column(s, 6, 8), 7);
+/// assert_eq!(unicode_hfwidth::byte_at_column(s, 8, 8), 8);
+/// assert_eq!(unicode_hfwidth::byte_at_column(s, 9, 8), s.len());
+/// ```
+pub fn byte_at_column<S: AsRef<str>>(s: S, column: usize, tab_width: usize) -> usize {
+    let s = s.as_ref();
+    let mut start = 0;
+    for (i, ch) in s.char_indices() {
+        start = advance(start, ch, tab_width);
+        if start > column {
+            return i;
+        }
+    }
+    s.len()
+}
+
+/// The character used to fill padding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Fill {
+    /// ASCII space, one column wide.
+    #[default]
+    Space,
+    /// Ideographic space `U+3000`, two columns wide. An odd column left over
+    /// is filled with an ASCII space.
+    IdeographicSpace,
+}
+
+impl Fill {
+    fn push(self, out: &mut String, columns: usize) {
+        match self {
+            Fill::Space => out.extend(std::iter::repeat_n(' ', columns)),
+            Fill::IdeographicSpace => {
+                out.extend(std::iter::repeat_n('\u{3000}', columns / 2));
+                if columns % 2 == 1 {
+                    out.push(' ');
+                }
+            }
+        }
+    }
+}
+
+fn pad(s: &str, width: usize, fill: Fill, left_share: impl FnOnce(usize) -> usize) -> String {
+    let missing = width.saturating_sub(str_width(s));
+    let left = left_share(missing);
+    let mut out = String::with_capacity(s.len() + missing * 3);
+    fill.push(&mut out, left);
+    out.push_str(s);
+    fill.push(&mut out, missing - left);
+    out
+}
+
+/// Pads `s` on the left to `width` terminal columns, aligning it to the right.
+/// If `s` is already at least `width` columns wide, it is returned unchanged.
+///
+/// # Example
+/// ```rust
+/// use unicode_hfwidth::Fill;
+///
+/// assert_eq!(unicode_hfwidth::pad_left("カナ", 6, Fill::Space), "  カナ");
+/// assert_eq!(unicode_hfwidth::pad_left("カナ", 6, Fill::IdeographicSpace), "　カナ");
+/// ```
+pub fn pad_left<S: AsRef<str>>(s: S, width: usize, fill: Fill) -> String {
+    let s = s.as_ref();
+    pad(s, width, fill, |missing| missing)
+}
+
+/// Pads `s` on the right to `width` terminal columns, aligning it to the left.
+/// If `s` is already at least `width` columns wide, it is returned unchanged.
+///
+/// # Example
+/// ```rust
+/// use unicode_hfwidth::Fill;
+///
+/// assert_eq!(unicode_hfwidth::pad_right("ｶﾅ", 4, Fill::Space), "ｶﾅ  ");
+/// ```
+pub fn pad_right<S: AsRef<str>>(s: S, width: usize, fill: Fill) -> String {
+    let s = s.as_ref();
+    pad(s, width, fill, |_| 0)
+}
+
+/// Pads `s` on both sides to `width` terminal columns, centering it. An odd
+/// column left over goes to the right.
+///
+/// # Example
+/// ```rust
+/// use unicode_hfwidth::Fill;
+///
+/// assert_eq!(unicode_hfwidth::center("カ", 5, Fill::Space), " カ  ");
+/// ```
+pub fn center<S: AsRef<str>>(s: S, width: usize, fill: Fill) -> String {
+    let s = s.as_ref();
+    pad(s, width, fill, |missing| missing / 2)
+}
+
+/// Returns the longest prefix of `s` that fits in `width` columns. Zero-width
+/// characters following the last fitting character are kept with it.
+fn prefix_within(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, ch) in s.char_indices() {
+        used += char_width(ch);
+        if used > width {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+/// The marker appended by `truncate_to_width` when text is cut off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Ellipsis {
+    /// Append nothing.
+    #[default]
+    None,
+    /// Append `…`, one column wide.
+    Unicode,
+    /// Append `...`, three columns wide.
+    Ascii,
+}
+
+impl Ellipsis {
+    /// Returns the marker text.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Ellipsis::None    => "",
+            Ellipsis::Unicode => "\u{2026}",
+            Ellipsis::Ascii   => "...",
+        }
+    }
+}
+
+/// Truncates `s` to at most `width` terminal columns. A wide character that
+/// would straddle the limit is dropped rather than split.
+///
+/// If `s` has to be cut, `ellipsis` is appended with the text shortened
+/// further so that the result still fits in `width` columns. If not even the
+/// ellipsis fits, it is left out.
+///
+/// # Example
+/// ```rust
+/// use unicode_hfwidth::Ellipsis;
+///
+/// assert_eq!(unicode_hfwidth::truncate_to_width("カタカナ", 5, Ellipsis::None), "カタ");
+/// assert_eq!(unicode_hfwidth::truncate_to_width("カタカナ", 5, Ellipsis::Unicode), "カタ…");
+/// assert_eq!(unicode_hfwidth::truncate_to_width("カタカナ", 8, Ellipsis::Ascii), "カタカナ");
+/// ```
+pub fn truncate_to_width(s: &str, width: usize, ellipsis: Ellipsis) -> Cow<'_, str> {
+    if str_width(s) <= width {
+        return Cow::Borrowed(s);
+    }
+    let marker = ellipsis.as_str();
+    let marker_width = str_width(marker);
+    if marker_width == 0 || marker_width > width {
+        return Cow::Borrowed(prefix_within(s, width));
+    }
+    let mut out = prefix_within(s, width - marker_width).to_owned();
+    out.push_str(marker);
+    Cow::Owned(out)
+}
+
+/// Returns the length of the first line to emit from `line` when wrapping
+/// to `width` columns. At least one character is always taken.
+fn wrap_point(line: &str, width: usize) -> usize {
+    let end = prefix_within(line, width).len();
+    if end > 0 {
+        return end;
+    }
+    let mut chars = line.char_indices().skip(1);
+    chars.find(|&(_, ch)| char_width(ch) > 0).map_or(line.len(), |(i, _)| i)
+}
+
+/// Line breaking rules for Japanese text (kinsoku shori): characters that may
+/// not start a line and characters that may not end one.
+///
+/// # Example
+/// ```rust
+/// use unicode_hfwidth::Kinsoku;
+///
+/// let rules = Kinsoku::default();
+/// assert!(rules.is_no_start('。'));
+/// assert!(rules.is_no_end('「'));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Kinsoku {
+    no_start: String,
+    no_end: String,
+}
+
+impl Kinsoku {
+    /// Creates rules from the characters that may not start a line and those
+    /// that may not end one.
+    pub fn new(no_start: &str, no_end: &str) -> Kinsoku {
+        Kinsoku { no_start: no_start.to_owned(), no_end: no_end.to_owned() }
+    }
+
+    /// Returns `true` if `ch` may not start a line.
+    pub fn is_no_start(&self, ch: char) -> bool {
+        self.no_start.contains(ch)
+    }
+
+    /// Returns `true` if `ch` may not end a line.
+    pub fn is_no_end(&self, ch: char) -> bool {
+        self.no_end.contains(ch)
+    }
+
+    /// Returns a copy of the rules with `chars` added to the characters that
+    /// may not start a line.
+    pub fn with_no_start(mut self, chars: &str) -> Kinsoku {
+        self.no_start.push_str(chars);
+        self
+    }
+
+    /// Returns a copy of the rules with `chars` added to the characters that
+    /// may not end a line.
+    pub fn with_no_end(mut self, chars: &str) -> Kinsoku {
+        self.no_end.push_str(chars);
+        self
+    }
+
+    /// Moves the break at `end` backwards until neither rule is violated. If
+    /// that is not possible, returns `end` unchanged.
+    fn adjust(&self, line: &str, end: usize) -> usize {
+        let mut at = end;
+        loop {
+            let next = line[at..].chars().next();
+            let prev = line[..at].char_indices().rev().find(|&(_, ch)| char_width(ch) > 0);
+            let (prev_start, prev) = match prev {
+                Some(prev) => prev,
+                None       => return end,
+            };
+            if !next.is_some_and(|ch| self.is_no_start(ch)) && !self.is_no_end(prev) {
+                return at;
+            }
+            if prev_start == 0 {
+                return end;
+            }
+            at = prev_start;
+        }
+    }
+}
+
+impl Default for Kinsoku {
+    /// The customary rules: closing brackets, punctuation, small kana, iteration
+    /// marks and the prolonged sound mark may not start a line, and opening
+    /// brackets may not end one. Both full-width and half-width forms are
+    /// included.
+    fn default() -> Kinsoku {
+        Kinsoku::new(
+            "、。，．・：；？！ー」』）〕］｝〉》】’”ゝゞヽヾ々〻‐゠〜～\
+             ぁぃぅぇぉっゃゅょゎゕゖァィゥェォッャュョヮヵヶ\
+             ｡､｣･ｰｧｨｩｪｫｬｭｮｯﾞﾟ)]},.:;!?",
+            "「『（〔［｛〈《【‘“｢([{",
+        )
+    }
+}
+
+/// Iterator returned by `wrap_to_width`.
+#[derive(Clone, Debug)]
+pub struct WrapToWidth<'a> {
+    remaining: &'a str,
+    width: usize,
+    kinsoku: Option<Kinsoku>,
+}
+
+impl<'a> WrapToWidth<'a> {
+    /// Applies kinsoku shori when breaking lines: a break that would start a
+    /// line with a prohibited character, or end one with a prohibited
+    /// character, is moved earlier so the offending characters are carried
+    /// over to the next line together.
+    ///
+    /// # Example
+    /// ```rust
+    /// use unicode_hfwidth::Kinsoku;
+    ///
+    /// let lines: Vec<_> = unicode_hfwidth::wrap_to_width("これはペンです。", 14).collect();
+    /// assert_eq!(lines, ["これはペンです", "。"]);
+    /// let lines: Vec<_> = unicode_hfwidth::wrap_to_width("これはペンです。", 14)
+    ///     .kinsoku(Kinsoku::default())
+    ///     .collect();
+    /// assert_eq!(lines, ["これはペンで", "す。"]);
+    /// ```
+    pub fn kinsoku(mut self, rules: Kinsoku) -> WrapToWidth<'a> {
+        self.kinsoku = Some(rules);
+        self
+    }
+}
+
+impl<'a> Iterator for WrapToWidth<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+        let (line, after) = match self.remaining.find('\n') {
+            Some(nl) => (&self.remaining[..nl], &self.remaining[nl + 1..]),
+            None     => (self.remaining, ""),
+        };
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if str_width(line) <= self.width {
+            self.remaining = after;
+            return Some(line);
+        }
+        let mut end = wrap_point(line, self.width);
+        if let Some(rules) = &self.kinsoku {
+            end = rules.adjust(line, end);
+        }
+        self.remaining = &self.remaining[end..];
+        Some(&line[..end])
+    }
+}
+
+/// Wraps `s` into lines of at most `width` terminal columns, counting
+/// full-width characters as two columns. Existing line breaks are kept, and
+/// wide characters are never split. A character wider than `width` is put on a
+/// line of its own.
+///
+/// # Example
+/// ```rust
+/// let lines: Vec<_> = unicode_hfwidth::wrap_to_width("アイウエオ\nabc", 4).collect();
+/// assert_eq!(lines, ["アイ", "ウエ", "オ", "abc"]);
+/// ```
+pub fn wrap_to_width(s: &str, width: usize) -> WrapToWidth<'_> {
+    WrapToWidth { remaining: s, width, kinsoku: None }
+}
+
+#[test]
+fn test_char_width() {
+    assert_eq!(char_width('\u{3000}'), 2);
+    assert_eq!(char_width('\u{3099}'), 0);
+    assert_eq!(char_width('\t'), 0);
+    assert_eq!(char_width('한'), 2);
+    assert_eq!(char_width('ﾡ'), 1);
+    assert_eq!(char_width('😀'), 2);
+}
+
+#[test]
+fn test_column_positions() {
+    let s = "ガ\u{3099}e\u{301}x\tｶ";
+    let columns: Vec<_> = (0..=s.len()).map(|i| column_at_byte(s, i, 8)).collect();
+    assert_eq!(columns, [0, 0, 0, 2, 2, 2, 2, 3, 3, 3, 4, 8, 8, 8, 9]);
+    let bytes: Vec<_> = (0..10).map(|column| byte_at_column(s, column, 8)).collect();
+    assert_eq!(bytes, [0, 0, 6, 9, 10, 10, 10, 10, 11, s.len()]);
+    for i in 0..=s.len() {
+        if s.is_char_boundary(i) {
+            assert_eq!(column_at_byte(s, i, 4), cursor_column(&s[..i], 4));
+        }
+    }
+    assert_eq!(column_at_byte(s, s.len(), 0), 5);
+}
+
+#[test]
+fn test_pad() {
+    assert_eq!(pad_left("abc", 2, Fill::Space), "abc");
+    assert_eq!(pad_right("カ", 5, Fill::IdeographicSpace), "カ　 ");
+    assert_eq!(center("ab", 6, Fill::IdeographicSpace), "　ab　");
+    assert_eq!(str_width(center("漢字", 9, Fill::Space)), 9);
+}
+
+#[test]
+fn test_truncate_to_width() {
+    assert_eq!(truncate_to_width("aカ", 2, Ellipsis::None), "a");
+    assert_eq!(truncate_to_width("ｶﾞｷﾞ", 2, Ellipsis::None), "ｶﾞ");
+    assert_eq!(truncate_to_width("ガ\u{3099}b", 2, Ellipsis::None), "ガ\u{3099}");
+    assert_eq!(truncate_to_width("状態：接続中", 7, Ellipsis::Ascii), "状態...");
+    assert_eq!(truncate_to_width("状態：接続中", 2, Ellipsis::Ascii), "状");
+    assert_eq!(truncate_to_width("カナ", 1, Ellipsis::Unicode), "…");
+}
+
+#[test]
+fn test_wrap_to_width() {
+    let lines: Vec<_> = wrap_to_width("aアbイ\r\n\nｶﾞｷﾞｸﾞ", 3).collect();
+    assert_eq!(lines, ["aア", "bイ", "", "ｶﾞｷ", "ﾞｸﾞ"]);
+    let lines: Vec<_> = wrap_to_width("漢字", 1).collect();
+    assert_eq!(lines, ["漢", "字"]);
+    assert_eq!(wrap_to_width("", 10).count(), 0);
+}
+
+#[test]
+fn test_wrap_kinsoku() {
+    let wrap = |s, width| wrap_to_width(s, width).kinsoku(Kinsoku::default()).collect::<Vec<_>>();
+    assert_eq!(wrap("「はい」と言った", 6), ["「は", "い」と", "言った"]);
+    assert_eq!(wrap("あいう「えお」", 8), ["あいう", "「えお」"]);
+    assert_eq!(wrap("チョコレート", 8), ["チョコ", "レート"]);
+    assert_eq!(wrap("。。。。", 4), ["。。", "。。"]);
+    let rules = Kinsoku::new("", "").with_no_start("x");
+    let lines: Vec<_> = wrap_to_width("abxd", 2).kinsoku(rules).collect();
+    assert_eq!(lines, ["a", "bx", "d"]);
+}
+
+#[test]
+fn test_cursor_column() {
+    assert_eq!(cursor_column("", 4), 0);
+    assert_eq!(cursor_column("a\tb", 4), 5);
+    assert_eq!(cursor_column("abcd\t", 4), 8);
+    assert_eq!(cursor_column("\t", 0), 0);
+    assert_eq!(cursor_column("\x1b\x07漢", 4), 2);
+    assert_eq!(cursor_column("> 入力\r$ ", 4), 2);
+}
+
+#[test]
+fn test_str_width_ansi() {
+    assert_eq!(str_width_ansi("plain"), 5);
+    assert_eq!(str_width_ansi("\x1b[38;5;196m赤\x1b[0m\x1b[K"), 2);
+    assert_eq!(str_width_ansi("\x1b]0;title\x1b\\ａ"), 2);
+    assert_eq!(str_width_ansi("\x1b(Bx"), 1);
+    assert_eq!(str_width_ansi("\x1b[31"), 0);
+    assert_eq!(strip_ansi("\u{9b}4mｶﾅ\u{9b}0m"), "ｶﾅ");
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_shrink_to_width() {
+    assert_eq!(shrink_to_width("ＡＢＣ", 6).text, "ＡＢＣ");
+    assert_eq!(shrink_to_width("ガッコウ", 5).text, "ガｯｺｳ");
+    let fitted = shrink_to_width("ＡＢ漢", 3);
+    assert_eq!(fitted.text, "AB漢");
+    assert!(!fitted.fits);
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_expand_to_width() {
+    assert_eq!(expand_to_width("ｶﾞｷ", 4).text, "ｶﾞキ");
+    assert_eq!(expand_to_width("abc", 100).text, "ａｂｃ");
+    assert!(!expand_to_width("abc", 2).fits);
+}
@@ -0,0 +1,121 @@
+//! Conversion expressed as edits to the input.
+
+use std::convert::Infallible;
+use std::ops::Range;
+
+use super::pretokenize::{width_pieces, Piece};
+use super::{WidthConverter, WidthPolicy};
+
+/// A replacement of a byte range of the original text.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Edit {
+    /// The byte range of the original text to replace.
+    pub range: Range<usize>,
+    /// The text to replace it with.
+    pub replacement: String,
+}
+
+/// Returns the edits that convert `s` according to `policy`, sorted by
+/// position and not touching each other. Applying them from last to first
+/// gives the same result as `WidthPolicy::convert`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::{Edit, WidthPolicy};
+///
+/// let edits = unicode_hfwidth::diff_normalize("ＡＢ and ｶﾞ", &WidthPolicy::default());
+/// assert_eq!(edits, [
+///     Edit { range: 0..6, replacement: "AB".to_owned() },
+///     Edit { range: 11..17, replacement: "ガ".to_owned() },
+/// ]);
+/// # }
+/// ```
+pub fn diff_normalize<S: AsRef<str>>(s: S, policy: &WidthPolicy) -> Vec<Edit> {
+    let s = s.as_ref();
+    let mut edits = Vec::new();
+    for (converted, range) in width_pieces(s, policy) {
+        push_edit(&mut edits, s, converted, range);
+    }
+    edits
+}
+
+/// Appends the edit replacing `range` of `s` with `converted`, merging it with
+/// the last edit if they touch. Nothing is appended if `converted` is already
+/// the text of the range.
+fn push_edit(edits: &mut Vec<Edit>, s: &str, converted: char, range: Range<usize>) {
+    let mut original = s[range.clone()].chars();
+    if original.next() == Some(converted) && original.next().is_none() {
+        return;
+    }
+    match edits.last_mut() {
+        Some(edit) if edit.range.end == range.start => {
+            edit.range.end = range.end;
+            edit.replacement.push(converted);
+        }
+        _ => edits.push(Edit { range, replacement: converted.to_string() }),
+    }
+}
+
+impl WidthConverter {
+    /// Returns the edits that convert `s` like `convert`, sorted by position
+    /// and not touching each other, leaving the protected spans unchanged and
+    /// following the converter's `map` overrides.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(all(feature = "kana", feature = "latin"))] {
+    /// use unicode_hfwidth::{Edit, Urls, WidthConverter, WidthPolicy};
+    ///
+    /// let converter = WidthConverter::new(WidthPolicy::default()).protect(Urls);
+    /// let edits = converter.diff("ｶﾞ http://x/ｶ");
+    /// assert_eq!(edits, [Edit { range: 0..6, replacement: "ガ".to_owned() }]);
+    /// # }
+    /// ```
+    pub fn diff<S: AsRef<str>>(&self, s: S) -> Vec<Edit> {
+        let s = s.as_ref();
+        let mut pieces: Vec<Piece> = Vec::with_capacity(s.len());
+        let Ok(()) = self.policy().push_except(s, &self.protected_spans(s), |ch| self.mapping(ch),
+                                               &mut pieces, |_| Ok::<_, Infallible>(()));
+        let mut edits = Vec::new();
+        for (converted, range) in pieces {
+            push_edit(&mut edits, s, converted, range);
+        }
+        edits
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_diff_normalize() {
+    let s = "ﾊﾟｿｺﾝ は ｶ ﾞ と ＰＣ";
+    let policy = WidthPolicy::default();
+    let edits = diff_normalize(s, &policy);
+    assert_eq!(edits.len(), 4);
+    let mut patched = s.to_owned();
+    for edit in edits.iter().rev() {
+        patched.replace_range(edit.range.clone(), &edit.replacement);
+    }
+    assert_eq!(patched, policy.convert(s));
+    assert!(diff_normalize("plain", &policy).is_empty());
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_converter_diff() {
+    use super::Urls;
+
+    let converter = WidthConverter::new(WidthPolicy::builder().contextual_prolonged_mark(true)
+                                                              .build())
+        .mappings([('〜', '～'), ('ｶ', 'カ'), ('1', '１')])
+        .protect(Urls);
+    for s in ["ｶﾞ ｰ1 http://x/ｶﾞ ＡＢ", "http://x/ﾊﾟ", "ﾊﾟ http://x/", "a〜b", "plain"] {
+        let mut patched = s.to_owned();
+        for edit in converter.diff(s).iter().rev() {
+            patched.replace_range(edit.range.clone(), &edit.replacement);
+        }
+        assert_eq!(patched, converter.convert(s), "{:?}", s);
+    }
+    assert!(converter.diff("http://x/ｶﾞ").is_empty());
+    assert_eq!(converter.diff("a〜b"), [Edit { range: 1..4, replacement: "～".to_owned() }]);
+}
@@ -0,0 +1,257 @@
+//! C interface.
+//!
+//! Enabled by the `ffi` feature. The `unicode_hfwidth_ffi` crate builds the
+//! shared and static libraries; use them with the header in
+//! `include/unicode_hfwidth.h`, which can be regenerated with `cbindgen`.
+
+use std::slice;
+use std::str;
+
+use super::{is_nonstandard_width_u32, to_fullwidth_u32, to_halfwidth_u32, to_standard_width_u32,
+            StreamConverter, WidthPolicy, WidthTarget};
+
+/// Returned by the string functions when the input is not valid UTF-8.
+pub const HFWIDTH_INVALID_UTF8: usize = usize::MAX;
+
+/// Selects conversion to standard width in `hfwidth_stream_create`.
+pub const HFWIDTH_STANDARD: u32 = 0;
+/// Selects conversion to half-width in `hfwidth_stream_create`.
+pub const HFWIDTH_HALFWIDTH: u32 = 1;
+/// Selects conversion to full-width in `hfwidth_stream_create`.
+pub const HFWIDTH_FULLWIDTH: u32 = 2;
+
+/// Checks if the code point `ch` is in the Unicode "Halfwidth and Fullwidth
+/// Forms" block.
+#[no_mangle]
+pub extern "C" fn hfwidth_is_nonstandard_width(ch: u32) -> bool {
+    is_nonstandard_width_u32(ch)
+}
+
+/// Returns the standard-width form of the code point `ch`, or 0 if there is
+/// none.
+#[no_mangle]
+pub extern "C" fn hfwidth_to_standard_width(ch: u32) -> u32 {
+    to_standard_width_u32(ch).unwrap_or(0)
+}
+
+/// Returns the half-width form of the code point `ch`, or 0 if there is none.
+#[no_mangle]
+pub extern "C" fn hfwidth_to_halfwidth(ch: u32) -> u32 {
+    to_halfwidth_u32(ch).unwrap_or(0)
+}
+
+/// Returns the full-width form of the code point `ch`, or 0 if there is none.
+#[no_mangle]
+pub extern "C" fn hfwidth_to_fullwidth(ch: u32) -> u32 {
+    to_fullwidth_u32(ch).unwrap_or(0)
+}
+
+/// Converts `input` with `policy` into the caller-allocated `output` buffer.
+///
+/// # Safety
+/// `input` must point to `input_len` readable bytes and `output` to
+/// `output_cap` writable bytes. Either may be null if its length is 0.
+unsafe fn convert_buffer(policy: &WidthPolicy, input: *const u8, input_len: usize,
+                         output: *mut u8, output_cap: usize) -> usize {
+    let input = if input_len == 0 { &[][..] } else { slice::from_raw_parts(input, input_len) };
+    let input = match str::from_utf8(input) {
+        Ok(input) => input,
+        Err(_)    => return HFWIDTH_INVALID_UTF8,
+    };
+    let converted = policy.convert(input);
+    if converted.len() <= output_cap && !converted.is_empty() {
+        slice::from_raw_parts_mut(output, converted.len()).copy_from_slice(converted.as_bytes());
+    }
+    converted.len()
+}
+
+/// Converts the UTF-8 string `input` of `input_len` bytes to standard width,
+/// writing the result to `output`. The output is not NUL-terminated.
+///
+/// Returns the length of the converted string in bytes. If that is greater
+/// than `output_cap`, nothing is written and the call can be repeated with a
+/// large enough buffer. Returns `HFWIDTH_INVALID_UTF8` if `input` is not valid
+/// UTF-8.
+///
+/// # Safety
+/// `input` must point to `input_len` readable bytes and `output` to
+/// `output_cap` writable bytes. Either may be null if its length is 0.
+#[no_mangle]
+pub unsafe extern "C" fn hfwidth_to_standard_width_str(input: *const u8, input_len: usize,
+                                                       output: *mut u8, output_cap: usize)
+                                                       -> usize {
+    convert_buffer(&WidthPolicy::default(), input, input_len, output, output_cap)
+}
+
+/// Like `hfwidth_to_standard_width_str`, but converts to half-width.
+///
+/// # Safety
+/// See `hfwidth_to_standard_width_str`.
+#[no_mangle]
+pub unsafe extern "C" fn hfwidth_to_halfwidth_str(input: *const u8, input_len: usize,
+                                                  output: *mut u8, output_cap: usize) -> usize {
+    let policy = WidthPolicy::builder().all(WidthTarget::Halfwidth).build();
+    convert_buffer(&policy, input, input_len, output, output_cap)
+}
+
+/// Like `hfwidth_to_standard_width_str`, but converts to full-width.
+///
+/// # Safety
+/// See `hfwidth_to_standard_width_str`.
+#[no_mangle]
+pub unsafe extern "C" fn hfwidth_to_fullwidth_str(input: *const u8, input_len: usize,
+                                                  output: *mut u8, output_cap: usize) -> usize {
+    let policy = WidthPolicy::builder().all(WidthTarget::Fullwidth).build();
+    convert_buffer(&policy, input, input_len, output, output_cap)
+}
+
+/// An incremental converter for input fed in chunks.
+pub struct HfwidthStream {
+    converter: StreamConverter,
+    /// Converted output not yet copied to the caller.
+    buffer: String,
+}
+
+impl HfwidthStream {
+    /// Copies the buffered output to `output` if it fits and returns its
+    /// length.
+    unsafe fn drain(&mut self, output: *mut u8, output_cap: usize) -> usize {
+        let len = self.buffer.len();
+        if len <= output_cap && len > 0 {
+            slice::from_raw_parts_mut(output, len).copy_from_slice(self.buffer.as_bytes());
+            self.buffer.clear();
+        }
+        len
+    }
+}
+
+/// Creates a streaming converter to the form selected by `target`, one of
+/// `HFWIDTH_STANDARD`, `HFWIDTH_HALFWIDTH` and `HFWIDTH_FULLWIDTH`. Returns
+/// null if `target` is not one of them. The converter must be freed with
+/// `hfwidth_stream_destroy`.
+#[no_mangle]
+pub extern "C" fn hfwidth_stream_create(target: u32) -> *mut HfwidthStream {
+    let target = match target {
+        HFWIDTH_STANDARD  => WidthTarget::Standard,
+        HFWIDTH_HALFWIDTH => WidthTarget::Halfwidth,
+        HFWIDTH_FULLWIDTH => WidthTarget::Fullwidth,
+        _                 => return std::ptr::null_mut(),
+    };
+    let policy = WidthPolicy::builder().all(target).build();
+    Box::into_raw(Box::new(HfwidthStream {
+        converter: StreamConverter::new(policy),
+        buffer: String::new(),
+    }))
+}
+
+/// Converts the next `input_len` bytes of UTF-8 input. Chunks may split
+/// characters anywhere; a kana at the end of a chunk is held back until it is
+/// known whether a voiced sound mark follows.
+///
+/// Returns the length of the output ready so far. If that is greater than
+/// `output_cap`, nothing is written and the output stays buffered; call again
+/// with more room, passing no input to only collect it. Besides output left
+/// uncollected by earlier calls, a call makes at most `3 * input_len + 4`
+/// bytes ready: each input byte converts to at most 3 bytes, a character
+/// split across calls adds at most 1 byte, and the kana held back by the
+/// previous call adds at most 3 bytes. Returns `HFWIDTH_INVALID_UTF8` if the
+/// input is not valid UTF-8, skipping the rest of the chunk; the converter
+/// can be fed further input afterwards.
+///
+/// # Safety
+/// `stream` must come from `hfwidth_stream_create`. `input` must point to
+/// `input_len` readable bytes and `output` to `output_cap` writable bytes.
+/// Either may be null if its length is 0.
+#[no_mangle]
+pub unsafe extern "C" fn hfwidth_stream_feed(stream: *mut HfwidthStream, input: *const u8,
+                                             input_len: usize, output: *mut u8,
+                                             output_cap: usize) -> usize {
+    let stream = &mut *stream;
+    let input = if input_len == 0 { &[][..] } else { slice::from_raw_parts(input, input_len) };
+    if stream.converter.feed(input, &mut stream.buffer).is_err() {
+        return HFWIDTH_INVALID_UTF8;
+    }
+    stream.drain(output, output_cap)
+}
+
+/// Ends the input, returning the remaining output like `hfwidth_stream_feed`.
+/// Returns `HFWIDTH_INVALID_UTF8` if the input ended inside a character.
+/// Afterwards the converter can be reused for new input.
+///
+/// # Safety
+/// `stream` must come from `hfwidth_stream_create` and `output` must point to
+/// `output_cap` writable bytes, or be null if `output_cap` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn hfwidth_stream_finish(stream: *mut HfwidthStream, output: *mut u8,
+                                               output_cap: usize) -> usize {
+    let stream = &mut *stream;
+    if stream.converter.finish(&mut stream.buffer).is_err() {
+        return HFWIDTH_INVALID_UTF8;
+    }
+    stream.drain(output, output_cap)
+}
+
+/// Frees a converter created by `hfwidth_stream_create`. Does nothing if
+/// `stream` is null.
+///
+/// # Safety
+/// `stream` must come from `hfwidth_stream_create` and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn hfwidth_stream_destroy(stream: *mut HfwidthStream) {
+    if !stream.is_null() {
+        drop(Box::from_raw(stream));
+    }
+}
+
+#[cfg(feature = "latin")]
+#[test]
+fn test_ffi_chars() {
+    assert_eq!(hfwidth_to_fullwidth('a' as u32), 'ａ' as u32);
+    assert_eq!(hfwidth_to_halfwidth('a' as u32), 0);
+    assert_eq!(hfwidth_to_standard_width(0xd800), 0);
+    assert!(hfwidth_is_nonstandard_width('ｶ' as u32));
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_ffi_str() {
+    let input = "ＡＢｶ";
+    let mut output = [0u8; 4];
+    unsafe {
+        let len = hfwidth_to_standard_width_str(input.as_ptr(), input.len(),
+                                                output.as_mut_ptr(), 2);
+        assert_eq!(len, 5);
+        assert_eq!(output, [0; 4]);
+        let mut output = vec![0u8; len];
+        hfwidth_to_standard_width_str(input.as_ptr(), input.len(), output.as_mut_ptr(), len);
+        assert_eq!(output, "ABカ".as_bytes());
+        assert_eq!(hfwidth_to_halfwidth_str(b"\xff".as_ptr(), 1, std::ptr::null_mut(), 0),
+                   HFWIDTH_INVALID_UTF8);
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_ffi_stream() {
+    let mut output = [0u8; 16];
+    unsafe {
+        let stream = hfwidth_stream_create(HFWIDTH_STANDARD);
+        let input = "Ａｶﾞ".as_bytes();
+        assert_eq!(hfwidth_stream_feed(stream, input.as_ptr(), 6, output.as_mut_ptr(), 0), 1);
+        assert_eq!(hfwidth_stream_feed(stream, input[6..].as_ptr(), 2, output.as_mut_ptr(), 16),
+                   1);
+        assert_eq!(&output[..1], b"A");
+        assert_eq!(hfwidth_stream_feed(stream, input[8..].as_ptr(), 1, output.as_mut_ptr(), 16),
+                   3);
+        assert_eq!(&output[..3], "ガ".as_bytes());
+        assert_eq!(hfwidth_stream_finish(stream, output.as_mut_ptr(), 16), 0);
+        let invalid = b"\xe3b";
+        assert_eq!(hfwidth_stream_feed(stream, invalid.as_ptr(), 1, output.as_mut_ptr(), 16), 0);
+        assert_eq!(hfwidth_stream_feed(stream, invalid[1..].as_ptr(), 1, output.as_mut_ptr(), 16),
+                   HFWIDTH_INVALID_UTF8);
+        assert_eq!(hfwidth_stream_feed(stream, input.as_ptr(), 3, output.as_mut_ptr(), 16), 2);
+        assert_eq!(&output[..2], b"bA");
+        hfwidth_stream_destroy(stream);
+        assert!(hfwidth_stream_create(7).is_null());
+    }
+}
@@ -0,0 +1,6 @@
+//! C library build of `unicode_hfwidth`.
+//!
+//! Build the shared and static libraries exporting the functions declared in
+//! `include/unicode_hfwidth.h` with `cargo build --release -p unicode_hfwidth_ffi`.
+
+pub use unicode_hfwidth::ffi::*;
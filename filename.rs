@@ -0,0 +1,106 @@
+//! Sanitization of user-supplied file names.
+
+use std::error::Error;
+use std::fmt;
+
+use super::{to_halfwidth, WidthPolicy};
+
+/// How `sanitize_filename` handles full-width lookalikes of path separators
+/// and wildcards, such as `／` and `＊`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Lookalikes {
+    /// Reject names containing them.
+    Reject,
+    /// Replace them with the given character.
+    Replace(char),
+    /// Fold them to ASCII like other characters, so they are rejected like
+    /// the characters they look like.
+    Fold,
+}
+
+/// The error returned by `sanitize_filename`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FilenameError {
+    /// The name is empty, `.` or `..`.
+    Empty,
+    /// The name contains a path separator, a wildcard, or another character
+    /// not allowed in file names on common systems.
+    Reserved(char),
+    /// The name contains a full-width lookalike of a reserved character, and
+    /// lookalikes are rejected.
+    Lookalike(char),
+}
+
+impl fmt::Display for FilenameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FilenameError::Empty         => f.write_str("empty file name"),
+            FilenameError::Reserved(ch)  => write!(f, "reserved character {:?} in file name", ch),
+            FilenameError::Lookalike(ch) => write!(f, "lookalike character {:?} in file name", ch),
+        }
+    }
+}
+
+impl Error for FilenameError {}
+
+/// Returns `true` if `ch` is not allowed in file names on Windows or Unix.
+fn is_reserved(ch: char) -> bool {
+    matches!(ch, '/' | '\\' | '*' | '?' | '"' | ':' | '<' | '>' | '|') || ch.is_control()
+}
+
+/// Converts the user-supplied file name `name` to standard width, handling
+/// full-width lookalikes of reserved characters as `lookalikes` says, and
+/// checks that the result is a single file name: not empty, `.` or `..`, and
+/// free of path separators, wildcards and control characters.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::{sanitize_filename, FilenameError, Lookalikes};
+///
+/// assert_eq!(sanitize_filename("ﾚﾎﾟｰﾄ／２０２４.pdf", Lookalikes::Replace('_')).unwrap(),
+///            "レポート_2024.pdf");
+/// assert_eq!(sanitize_filename("ﾚﾎﾟｰﾄ／２０２４.pdf", Lookalikes::Fold),
+///            Err(FilenameError::Reserved('/')));
+/// assert_eq!(sanitize_filename("ａ＊.txt", Lookalikes::Reject),
+///            Err(FilenameError::Lookalike('＊')));
+/// # }
+/// ```
+pub fn sanitize_filename<S: AsRef<str>>(name: S, lookalikes: Lookalikes)
+                                        -> Result<String, FilenameError> {
+    let name = name.as_ref();
+    let mut out = String::with_capacity(name.len());
+    for ch in name.chars() {
+        match (to_halfwidth(ch).is_some_and(is_reserved), lookalikes) {
+            (true, Lookalikes::Reject)               => return Err(FilenameError::Lookalike(ch)),
+            (true, Lookalikes::Replace(replacement)) => out.push(replacement),
+            _                                        => out.push(ch),
+        }
+    }
+    let name = WidthPolicy::default().convert(&out);
+    if let Some(ch) = name.chars().find(|&ch| is_reserved(ch)) {
+        return Err(FilenameError::Reserved(ch));
+    }
+    match name.as_str() {
+        "" | "." | ".." => Err(FilenameError::Empty),
+        _               => Ok(name),
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_sanitize_filename() {
+    let names = ["ｃ：＼ｗｉｎ", "ａ？ｂ＂ｃ＜ｄ＞ｅ｜ｆ"];
+    for name in names {
+        let replaced = sanitize_filename(name, Lookalikes::Replace('-')).unwrap();
+        assert!(replaced.is_ascii() && !replaced.chars().any(is_reserved), "{}", replaced);
+        assert!(sanitize_filename(name, Lookalikes::Fold).is_err());
+        assert!(sanitize_filename(name, Lookalikes::Reject).is_err());
+    }
+    assert_eq!(sanitize_filename("．．", Lookalikes::Fold), Err(FilenameError::Empty));
+    let reserved = Err(FilenameError::Reserved('/'));
+    assert_eq!(sanitize_filename("a/b", Lookalikes::Replace('_')), reserved);
+    assert_eq!(sanitize_filename("a／b", Lookalikes::Replace('/')), reserved);
+    assert_eq!(sanitize_filename("ﾒﾓ\u{0}", Lookalikes::Fold), Err(FilenameError::Reserved('\0')));
+    assert_eq!(sanitize_filename("ﾒﾓ.txt", Lookalikes::Reject).unwrap(), "メモ.txt");
+}
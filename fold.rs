@@ -0,0 +1,241 @@
+//! Width folding matching Unicode normalization.
+
+use super::{compose_kana, to_fullwidth, to_standard_width, Converted};
+
+/// Folds the width of `s` the way NFKC does, leaving all other characters
+/// unchanged, like ICU's `foldWidth`.
+///
+/// Characters with a `<wide>` or `<narrow>` compatibility decomposition are
+/// replaced with their full decompositions: full-width ASCII, the ideographic
+/// space, half-width katakana, and the full-width and half-width forms of
+/// signs. Half-width voiced sound marks become combining marks, and
+/// half-width Hangul becomes conjoining jamo. These are composed with the
+/// characters next to them into kana and Hangul syllables as NFKC composes
+/// them.
+///
+/// For text that is otherwise in NFC, the result is the same as NFKC, except
+/// that other compatibility characters, such as `①` or `㌔`, are kept, and
+/// that combining marks are not put in canonical order. Where a half-width
+/// voiced sound mark follows another combining mark, or a combining mark
+/// follows `￣`, NFKC reorders the marks and may compose the sound mark with
+/// the kana before them, while `fold_width` leaves them as they are.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "hangul", feature = "kana", feature = "latin", feature = "symbols"))] {
+/// assert_eq!(unicode_hfwidth::fold_width("ﾃﾞｰﾀ　ＡＢＣ ￥100"), "データ ABC ¥100");
+/// assert_eq!(unicode_hfwidth::fold_width("ﾎﾟｲﾝﾄ①"), "ポイント①");
+/// assert_eq!(unicode_hfwidth::fold_width("ﾞ"), "\u{3099}");
+/// assert_eq!(unicode_hfwidth::fold_width("ﾡￂ"), "가");
+/// # }
+/// ```
+pub fn fold_width<S: AsRef<str>>(s: S) -> String {
+    let s = s.as_ref();
+    let mut out = String::with_capacity(s.len());
+    /* The last character pushed, and whether it was folded */
+    let mut last: Option<(char, bool)> = None;
+    for ch in s.chars() {
+        let decomposition = nfkc_decomposition(ch);
+        let (next, rest) = match &decomposition {
+            Some(d) => (d[d.len() - 1], &d[..d.len() - 1]),
+            None    => (ch, &[][..]),
+        };
+        let folded = decomposition.is_some();
+        out.extend(rest);
+        if let Some((base, base_folded)) = last.filter(|&(_, f)| rest.is_empty() && (f || folded)) {
+            if let Some(composed) = compose(base, next) {
+                out.truncate(out.len() - base.len_utf8());
+                out.push(composed);
+                last = Some((composed, base_folded || folded));
+                continue;
+            }
+        }
+        out.push(next);
+        last = Some((next, folded));
+    }
+    out
+}
+
+/// Returns the full NFKC decomposition of `ch` if it has a `<wide>` or
+/// `<narrow>` compatibility decomposition, that is, if it is an assigned
+/// character of the "Halfwidth and Fullwidth Forms" block or the ideographic
+/// space. Otherwise, returns `None`.
+///
+/// Unlike `to_standard_width`, the decomposition is the one NFKC uses before
+/// composing: half-width voiced sound marks decompose to combining marks,
+/// half-width Hangul to conjoining jamo, and `￣` to a space and a combining
+/// macron. Use `nfkc_composition` for the characters they compose to.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "hangul", feature = "kana"))] {
+/// use unicode_hfwidth::nfkc_decomposition;
+///
+/// assert_eq!(nfkc_decomposition('ｶ').unwrap()[..], ['カ']);
+/// assert_eq!(nfkc_decomposition('ﾞ').unwrap()[..], ['\u{3099}']);
+/// assert_eq!(nfkc_decomposition('￣').unwrap()[..], [' ', '\u{304}']);
+/// assert_eq!(nfkc_decomposition('ﾡ').unwrap().to_string(), "\u{1100}");
+/// assert_eq!(nfkc_decomposition('カ'), None);
+/// # }
+/// ```
+pub fn nfkc_decomposition(ch: char) -> Option<Converted> {
+    match ch {
+        '\u{3000}' => Some(Converted::one(' ')),
+        '￣'       => Some(Converted::two(' ', '\u{304}')),
+        _          => conjoining_jamo(ch).or_else(|| to_standard_width(ch)).map(Converted::one),
+    }
+}
+
+/// Returns the character NFKC composes `first` followed by `second` to, if
+/// either has a width decomposition and they compose to a single character,
+/// such as `ｶ` and `ﾞ` composing to `ガ`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "hangul", feature = "kana"))] {
+/// use unicode_hfwidth::nfkc_composition;
+///
+/// assert_eq!(nfkc_composition('ﾊ', 'ﾟ'), Some('パ'));
+/// assert_eq!(nfkc_composition('か', 'ﾞ'), Some('が'));
+/// assert_eq!(nfkc_composition('ﾡ', 'ￂ'), Some('가'));
+/// assert_eq!(nfkc_composition('ｱ', 'ﾞ'), None);
+/// assert_eq!(nfkc_composition('か', '\u{3099}'), None);
+/// # }
+/// ```
+pub fn nfkc_composition(first: char, second: char) -> Option<char> {
+    let (a, b) = (nfkc_decomposition(first), nfkc_decomposition(second));
+    if a.is_none() && b.is_none() {
+        return None;
+    }
+    let single = |d: Option<Converted>, ch| match d {
+        Some(d) if d.len() == 1 => Some(d[0]),
+        Some(_)                 => None,
+        None                    => Some(ch),
+    };
+    compose(single(a, first)?, single(b, second)?)
+}
+
+/// Returns the canonical composition of `base` and `next` for kana and
+/// Hangul.
+fn compose(base: char, next: char) -> Option<char> {
+    compose_kana(base, next).or_else(|| compose_hangul(base, next))
+}
+
+/// Leading consonants and final consonant clusters NFKC decomposes the
+/// half-width Hangul consonants U+FFA1 to U+FFBE to.
+#[cfg(feature = "hangul")]
+const CONSONANTS: [u16; 30] = [
+    0x1100, 0x1101, 0x11aa, 0x1102, 0x11ac, 0x11ad, 0x1103, 0x1104, 0x1105, 0x11b0,
+    0x11b1, 0x11b2, 0x11b3, 0x11b4, 0x11b5, 0x111a, 0x1106, 0x1107, 0x1108, 0x1121,
+    0x1109, 0x110a, 0x110b, 0x110c, 0x110d, 0x110e, 0x110f, 0x1110, 0x1111, 0x1112,
+];
+
+/// Returns the conjoining jamo NFKC decomposes the half-width Hangul `ch` to.
+pub(crate) fn conjoining_jamo(ch: char) -> Option<char> {
+    let jamo = match ch as u32 {
+        #[cfg(feature = "hangul")]
+        0xffa0          => 0x1160,
+        #[cfg(feature = "hangul")]
+        0xffa1..=0xffbe => CONSONANTS[ch as usize - 0xffa1] as u32,
+        0xffc2..=0xffdc => to_fullwidth(ch)? as u32 - 0x314f + 0x1161,
+        _               => return None,
+    };
+    char::from_u32(jamo)
+}
+
+/// Returns the Hangul syllable composed of a leading consonant and a vowel,
+/// or of a syllable without a final consonant and a final consonant.
+pub(crate) fn compose_hangul(base: char, next: char) -> Option<char> {
+    let (b, n) = (base as u32, next as u32);
+    let syllable = match (b, n) {
+        (0x1100..=0x1112, 0x1161..=0x1175) => 0xac00 + ((b - 0x1100) * 21 + n - 0x1161) * 28,
+        (0xac00..=0xd7a3, 0x11a8..=0x11c2) if (b - 0xac00).is_multiple_of(28) => b + n - 0x11a7,
+        _ => return None,
+    };
+    char::from_u32(syllable)
+}
+
+/// Leading consonants and the final consonants of the same sound.
+const FINALS: [(u16, u16); 18] = [
+    (0x1100, 0x11a8), (0x1101, 0x11a9), (0x1102, 0x11ab), (0x1103, 0x11ae), (0x1105, 0x11af),
+    (0x1106, 0x11b7), (0x1107, 0x11b8), (0x1109, 0x11ba), (0x110a, 0x11bb), (0x110b, 0x11bc),
+    (0x110c, 0x11bd), (0x110e, 0x11be), (0x110f, 0x11bf), (0x1110, 0x11c0), (0x1111, 0x11c1),
+    (0x1112, 0x11c2), (0x111a, 0x11b6), (0x1121, 0x11b9),
+];
+
+/// Returns `true` if `ch` is a vowel jamo a syllable can be composed with.
+pub(crate) fn is_vowel(ch: char) -> bool {
+    matches!(ch, '\u{1161}'..='\u{1175}')
+}
+
+/// Returns the final consonant of the same sound as the leading consonant
+/// `ch`.
+pub(crate) fn final_form(ch: char) -> Option<char> {
+    let (_, t) = FINALS.iter().find(|&&(l, _)| l as u32 == ch as u32)?;
+    char::from_u32(*t as u32)
+}
+
+/// Splits a syllable with a final consonant into the syllable without it and
+/// the leading consonant of the same sound, if there is one.
+pub(crate) fn split_final(syllable: char) -> Option<(char, char)> {
+    let index = (syllable as u32).checked_sub(0xac00).filter(|&i| i < 11172)?;
+    let t = 0x11a7 + index % 28;
+    let &(l, _) = FINALS.iter().find(|&&(l, final_)| final_ as u32 == t && l <= 0x1112)?;
+    Some((char::from_u32(syllable as u32 - index % 28)?, char::from_u32(l as u32)?))
+}
+
+#[cfg(all(feature = "hangul", feature = "kana", feature = "latin", feature = "symbols"))]
+#[test]
+fn test_fold_width_nfkc() {
+    use unicode_normalization::UnicodeNormalization;
+
+    let nfkc = |s: &str| s.nfkc().collect::<String>();
+    for ch in ('\u{ff00}'..='\u{ffef}').chain(['\u{3000}']) {
+        let s = ch.to_string();
+        assert_eq!(fold_width(&s), nfkc(&s), "U+{:04X}", ch as u32);
+    }
+    let kana = ['\u{3041}'..='\u{3096}', '\u{309d}'..='\u{309e}', '\u{30a1}'..='\u{30fe}',
+                '\u{ff66}'..='\u{ff9d}'];
+    for base in kana.into_iter().flatten() {
+        for mark in ['ﾞ', 'ﾟ'] {
+            let s: String = [base, mark, mark].iter().collect();
+            assert_eq!(fold_width(&s), nfkc(&s), "{}", s);
+        }
+    }
+    for s in ["ﾡￂﾤﾤ", "ﾡￂﾣ ﾰ", "가ﾣ", "ﾡ\u{1161}", "\u{1100}ￂ", "ﾠￂ", "ｶ\u{3099}", "カﾞ", "ｶ　ﾞ ￣"] {
+        assert_eq!(fold_width(s), nfkc(s), "{}", s);
+    }
+    for s in ["ｶﾞ\u{301}", "ｶ\u{301}", "\u{301}ｶﾞ", "ﾊﾟ\u{3099}"] {
+        assert_eq!(fold_width(s), nfkc(s), "{}", s);
+    }
+    /* Combining marks are not reordered */
+    assert_eq!(fold_width("ｶ\u{301}ﾞ"), "カ\u{301}\u{3099}");
+    assert_eq!(nfkc("ｶ\u{301}ﾞ"), "ガ\u{301}");
+    assert_eq!(fold_width("￣\u{323}"), " \u{304}\u{323}");
+    assert_eq!(nfkc("￣\u{323}"), " \u{323}\u{304}");
+}
+
+#[cfg(all(feature = "hangul", feature = "kana"))]
+#[test]
+fn test_fold_width_only() {
+    assert_eq!(fold_width("ㄱￂ ①ｶ"), "ㄱ\u{1161} ①カ");
+    assert_eq!(fold_width("\u{1100}\u{1161}"), "\u{1100}\u{1161}");
+}
+
+#[cfg(all(feature = "hangul", feature = "kana"))]
+#[test]
+fn test_nfkc_data() {
+    use unicode_normalization::UnicodeNormalization;
+
+    for ch in ('\u{ff00}'..='\u{ffef}').chain(['\u{3000}']) {
+        if let Some(d) = nfkc_decomposition(ch) {
+            assert_eq!(d.to_string(), ch.nfkd().collect::<String>(), "U+{:04X}", ch as u32);
+        }
+        for second in '\u{ff61}'..='\u{ffdc}' {
+            let pair: String = [ch, second].iter().collect();
+            let nfkc: Vec<_> = pair.nfkc().collect();
+            let expected = if nfkc.len() == 1 { Some(nfkc[0]) } else { None };
+            assert_eq!(nfkc_composition(ch, second), expected, "{}", pair);
+        }
+    }
+}
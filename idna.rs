@@ -0,0 +1,117 @@
+//! Width mappings for domain names.
+
+use std::error::Error;
+use std::fmt;
+
+use super::fold_width;
+
+/// Maps the width of `s` as UTS #46 maps it for domain name processing.
+///
+/// Characters are folded like `fold_width`, the full stops `。`, `｡` and `．`
+/// become the label separator `.`, and ASCII letters are lowercased. Other
+/// UTS #46 mappings, such as case folding outside ASCII, are not applied.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// assert_eq!(unicode_hfwidth::idna_map("ＥＸＡＭＰＬＥ。com"), "example.com");
+/// assert_eq!(unicode_hfwidth::idna_map("ﾃｽﾄ．jp"), "テスト.jp");
+/// # }
+/// ```
+pub fn idna_map<S: AsRef<str>>(s: S) -> String {
+    let s = s.as_ref();
+    fold_width(s).chars()
+        .map(|ch| match ch {
+            '\u{3002}' => '.',
+            _          => ch.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+/// The error returned by `normalize_hostname` for text that is not a valid
+/// hostname after mapping.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HostnameError {
+    /// The hostname or one of its labels is empty.
+    EmptyLabel,
+    /// The hostname contains a character not allowed in hostnames, such as
+    /// a space or ASCII punctuation other than `-`.
+    InvalidCharacter(char),
+    /// A label starts or ends with `-`.
+    Hyphen,
+    /// An ASCII label is longer than 63 bytes, or an ASCII hostname longer
+    /// than 253 bytes.
+    TooLong,
+}
+
+impl fmt::Display for HostnameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HostnameError::EmptyLabel          => f.write_str("empty label in hostname"),
+            HostnameError::InvalidCharacter(c) => write!(f, "invalid character {:?} in hostname", c),
+            HostnameError::Hyphen              => f.write_str("label starts or ends with '-'"),
+            HostnameError::TooLong             => f.write_str("hostname too long"),
+        }
+    }
+}
+
+impl Error for HostnameError {}
+
+/// Maps the width of `host` with `idna_map` and checks that the result is a
+/// valid hostname, so that `ｅｘａｍｐｌｅ.com` and `example.com` are treated as
+/// the same host.
+///
+/// A trailing `.` is kept. Labels with characters outside ASCII are returned
+/// as Unicode and only checked for spaces and ASCII punctuation; they are to
+/// be passed on to an IDNA `ToASCII` implementation. To reject hostnames that
+/// are not already in canonical width instead, compare the result with `host`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// use unicode_hfwidth::HostnameError;
+///
+/// assert_eq!(unicode_hfwidth::normalize_hostname("ｅｘａｍｐｌｅ．com").unwrap(), "example.com");
+/// assert_eq!(unicode_hfwidth::normalize_hostname("ｅｘ＿ａｍｐｌｅ.com"),
+///            Err(HostnameError::InvalidCharacter('_')));
+/// # }
+/// ```
+pub fn normalize_hostname<S: AsRef<str>>(host: S) -> Result<String, HostnameError> {
+    let host = host.as_ref();
+    let mapped = idna_map(host);
+    let labels = mapped.strip_suffix('.').unwrap_or(&mapped);
+    for label in labels.split('.') {
+        if label.is_empty() {
+            return Err(HostnameError::EmptyLabel);
+        }
+        let invalid = label.chars().find(|&ch| {
+            ch.is_whitespace() || ch.is_control()
+                || ch.is_ascii() && !(ch.is_ascii_alphanumeric() || ch == '-')
+        });
+        if let Some(ch) = invalid {
+            return Err(HostnameError::InvalidCharacter(ch));
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err(HostnameError::Hyphen);
+        }
+        if label.is_ascii() && label.len() > 63 {
+            return Err(HostnameError::TooLong);
+        }
+    }
+    if labels.is_ascii() && labels.len() > 253 {
+        return Err(HostnameError::TooLong);
+    }
+    Ok(mapped)
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_normalize_hostname() {
+    assert_eq!(normalize_hostname("ＷＷＷ｡ｴｸﾞｻﾞﾝﾌﾟﾙ。ＪＰ．").unwrap(), "www.エグザンプル.jp.");
+    assert_eq!(normalize_hostname("a．．b"), Err(HostnameError::EmptyLabel));
+    assert_eq!(normalize_hostname(""), Err(HostnameError::EmptyLabel));
+    assert_eq!(normalize_hostname("ｅｘ　ample.com"), Err(HostnameError::InvalidCharacter(' ')));
+    assert_eq!(normalize_hostname("－ex.com"), Err(HostnameError::Hyphen));
+    assert_eq!(normalize_hostname("ａ".repeat(64)), Err(HostnameError::TooLong));
+    assert_eq!(normalize_hostname(["ａ"; 128].join(".")), Err(HostnameError::TooLong));
+}
@@ -0,0 +1,137 @@
+#ifndef UNICODE_HFWIDTH_H
+#define UNICODE_HFWIDTH_H
+
+/* Generated with cbindgen from ffi.rs. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Returned by the string functions when the input is not valid UTF-8.
+ */
+#define HFWIDTH_INVALID_UTF8 SIZE_MAX
+
+/**
+ * Selects conversion to standard width in `hfwidth_stream_create`.
+ */
+#define HFWIDTH_STANDARD 0
+
+/**
+ * Selects conversion to half-width in `hfwidth_stream_create`.
+ */
+#define HFWIDTH_HALFWIDTH 1
+
+/**
+ * Selects conversion to full-width in `hfwidth_stream_create`.
+ */
+#define HFWIDTH_FULLWIDTH 2
+
+/**
+ * An incremental converter for input fed in chunks.
+ */
+typedef struct HfwidthStream HfwidthStream;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Checks if the code point `ch` is in the Unicode "Halfwidth and Fullwidth
+ * Forms" block.
+ */
+bool hfwidth_is_nonstandard_width(uint32_t ch);
+
+/**
+ * Returns the standard-width form of the code point `ch`, or 0 if there is
+ * none.
+ */
+uint32_t hfwidth_to_standard_width(uint32_t ch);
+
+/**
+ * Returns the half-width form of the code point `ch`, or 0 if there is none.
+ */
+uint32_t hfwidth_to_halfwidth(uint32_t ch);
+
+/**
+ * Returns the full-width form of the code point `ch`, or 0 if there is none.
+ */
+uint32_t hfwidth_to_fullwidth(uint32_t ch);
+
+/**
+ * Converts the UTF-8 string `input` of `input_len` bytes to standard width,
+ * writing the result to `output`. The output is not NUL-terminated.
+ *
+ * Returns the length of the converted string in bytes. If that is greater
+ * than `output_cap`, nothing is written and the call can be repeated with a
+ * large enough buffer. Returns `HFWIDTH_INVALID_UTF8` if `input` is not valid
+ * UTF-8.
+ */
+size_t hfwidth_to_standard_width_str(const uint8_t *input,
+                                     size_t input_len,
+                                     uint8_t *output,
+                                     size_t output_cap);
+
+/**
+ * Like `hfwidth_to_standard_width_str`, but converts to half-width.
+ */
+size_t hfwidth_to_halfwidth_str(const uint8_t *input,
+                                size_t input_len,
+                                uint8_t *output,
+                                size_t output_cap);
+
+/**
+ * Like `hfwidth_to_standard_width_str`, but converts to full-width.
+ */
+size_t hfwidth_to_fullwidth_str(const uint8_t *input,
+                                size_t input_len,
+                                uint8_t *output,
+                                size_t output_cap);
+
+/**
+ * Creates a streaming converter to the form selected by `target`, one of
+ * `HFWIDTH_STANDARD`, `HFWIDTH_HALFWIDTH` and `HFWIDTH_FULLWIDTH`. Returns
+ * null if `target` is not one of them. The converter must be freed with
+ * `hfwidth_stream_destroy`.
+ */
+HfwidthStream *hfwidth_stream_create(uint32_t target);
+
+/**
+ * Converts the next `input_len` bytes of UTF-8 input. Chunks may split
+ * characters anywhere; a kana at the end of a chunk is held back until it is
+ * known whether a voiced sound mark follows.
+ *
+ * Returns the length of the output ready so far. If that is greater than
+ * `output_cap`, nothing is written and the output stays buffered; call again
+ * with more room, passing no input to only collect it. Besides output left
+ * uncollected by earlier calls, a call makes at most `3 * input_len + 4`
+ * bytes ready: each input byte converts to at most 3 bytes, a character
+ * split across calls adds at most 1 byte, and the kana held back by the
+ * previous call adds at most 3 bytes. Returns `HFWIDTH_INVALID_UTF8` if the
+ * input is not valid UTF-8, skipping the rest of the chunk; the converter
+ * can be fed further input afterwards.
+ */
+size_t hfwidth_stream_feed(HfwidthStream *stream,
+                           const uint8_t *input,
+                           size_t input_len,
+                           uint8_t *output,
+                           size_t output_cap);
+
+/**
+ * Ends the input, returning the remaining output like `hfwidth_stream_feed`.
+ * Returns `HFWIDTH_INVALID_UTF8` if the input ended inside a character.
+ * Afterwards the converter can be reused for new input.
+ */
+size_t hfwidth_stream_finish(HfwidthStream *stream, uint8_t *output, size_t output_cap);
+
+/**
+ * Frees a converter created by `hfwidth_stream_create`. Does nothing if
+ * `stream` is null.
+ */
+void hfwidth_stream_destroy(HfwidthStream *stream);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* UNICODE_HFWIDTH_H */
@@ -0,0 +1,212 @@
+//! Conversion of buffers in place.
+
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+use super::policy::{Context, PieceSink};
+use super::WidthPolicy;
+
+impl WidthPolicy {
+    /// Converts each character of `chars` on its own according to the
+    /// policy, like `convert_char`, for buffers with one character per cell
+    /// such as terminal grids. Voiced sound marks are not composed with the
+    /// kana before them, as the number of cells cannot change. Returns the
+    /// indices of the characters that changed, in order.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(all(feature = "kana", feature = "latin"))] {
+    /// use unicode_hfwidth::WidthPolicy;
+    ///
+    /// let mut cells: Vec<char> = "ＩＤ: ｶﾅ".chars().collect();
+    /// let changed = WidthPolicy::default().convert_chars_in_place(&mut cells);
+    /// assert_eq!(cells.iter().collect::<String>(), "ID: カナ");
+    /// assert_eq!(changed, [0, 1, 4, 5]);
+    /// # }
+    /// ```
+    pub fn convert_chars_in_place(&self, chars: &mut [char]) -> Vec<usize> {
+        let mut changed = Vec::new();
+        for (i, ch) in chars.iter_mut().enumerate() {
+            let converted = self.convert_char(*ch);
+            if converted != *ch {
+                *ch = converted;
+                changed.push(i);
+            }
+        }
+        changed
+    }
+}
+
+/// The error returned by `convert_in_place_exact` when converting a
+/// character would change the length of the string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotLengthPreserving {
+    /// Byte index of the character in the input.
+    pub index: usize,
+    /// The character.
+    pub ch: char,
+}
+
+impl fmt::Display for NotLengthPreserving {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "converting character {:?} at index {} changes the length", self.ch, self.index)
+    }
+}
+
+impl Error for NotLengthPreserving {}
+
+/// Checks that each piece of a conversion is as long as the input it came
+/// from, keeping the first that is not.
+struct LengthCheck<'a> {
+    s: &'a str,
+    error: Option<NotLengthPreserving>,
+}
+
+impl PieceSink for LengthCheck<'_> {
+    fn push_piece(&mut self, ch: char, range: Range<usize>) {
+        if self.error.is_none() && ch.len_utf8() != range.len() {
+            /* The last character of the range is where the length changes */
+            let original = self.s[..range.end].chars().next_back().unwrap();
+            self.error = Some(NotLengthPreserving { index: range.end - original.len_utf8(),
+                                                    ch: original });
+        }
+    }
+}
+
+/// Writes each piece of a conversion over the input it came from, which must
+/// be as long.
+struct Overwrite<'a>(&'a mut [u8]);
+
+impl PieceSink for Overwrite<'_> {
+    fn push_piece(&mut self, ch: char, range: Range<usize>) {
+        ch.encode_utf8(&mut self.0[range]);
+    }
+}
+
+/// Returns the first character of `s` whose conversion according to
+/// `policy` changes the length of `s`, as `convert_in_place_exact` does.
+fn check_length_preserving(s: &str, policy: &WidthPolicy) -> Result<(), NotLengthPreserving> {
+    let mut check = LengthCheck { s, error: None };
+    let Ok(()) = policy.push_except(s, &[], |_| None, &mut check, |_| Ok::<_, Infallible>(()));
+    check.error.map_or(Ok(()), Err)
+}
+
+/// Returns `true` if converting `s` according to `policy` keeps its length,
+/// so `convert_in_place_exact` can convert it without allocating.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::WidthPolicy;
+///
+/// let policy = WidthPolicy::default();
+/// assert!(unicode_hfwidth::is_length_preserving("ｶﾀｶﾅ", &policy));
+/// assert!(!unicode_hfwidth::is_length_preserving("ｶﾞ", &policy));
+/// assert!(!unicode_hfwidth::is_length_preserving("ＡＢ", &policy));
+/// # }
+/// ```
+pub fn is_length_preserving(s: &str, policy: &WidthPolicy) -> bool {
+    check_length_preserving(s, policy).is_ok()
+}
+
+/// Returns `true` if `policy` converts `ch` to a character of the same
+/// length in UTF-8. The characters around `ch` are not taken into account,
+/// so a voiced sound mark composed with the kana before it in a string
+/// still counts as length preserving.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// use unicode_hfwidth::WidthPolicy;
+///
+/// let policy = WidthPolicy::default();
+/// assert!(unicode_hfwidth::is_char_length_preserving('ｶ', &policy));
+/// assert!(unicode_hfwidth::is_char_length_preserving('a', &policy));
+/// assert!(!unicode_hfwidth::is_char_length_preserving('Ａ', &policy));
+/// # }
+/// ```
+pub fn is_char_length_preserving(ch: char, policy: &WidthPolicy) -> bool {
+    policy.expansion(ch).is_none() && policy.convert_char(ch).len_utf8() == ch.len_utf8()
+}
+
+/// Converts `s` according to `policy` by rewriting its bytes, without
+/// allocating. Conversions between half-width and full-width katakana, for
+/// example, keep the length of the string. If any character would be
+/// converted to one of another length in UTF-8, such as full-width ASCII,
+/// or composed with the character before it, such as a voiced sound mark,
+/// returns an error for the first one and leaves `s` unchanged.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::{NotLengthPreserving, WidthPolicy};
+///
+/// let mut s = String::from("ｶﾀｶﾅ");
+/// unicode_hfwidth::convert_in_place_exact(&mut s, &WidthPolicy::default()).unwrap();
+/// assert_eq!(s, "カタカナ");
+/// let mut s = String::from("ｶﾅ ＡＢ");
+/// assert_eq!(unicode_hfwidth::convert_in_place_exact(&mut s, &WidthPolicy::default()),
+///            Err(NotLengthPreserving { index: 7, ch: 'Ａ' }));
+/// assert_eq!(s, "ｶﾅ ＡＢ");
+/// # }
+/// ```
+pub fn convert_in_place_exact(s: &mut str, policy: &WidthPolicy)
+                              -> Result<(), NotLengthPreserving> {
+    check_length_preserving(s, policy)?;
+    let mut cx = Context::default();
+    let mut i = 0;
+    while i < s.len() {
+        let ch = s[i..].chars().next().unwrap();
+        let range = i..i + ch.len_utf8();
+        i = range.end;
+        /* Pieces replace input already read with characters of the same length */
+        policy.push_piece(ch, range, &mut cx, &mut Overwrite(unsafe { s.as_bytes_mut() }));
+    }
+    cx.flush(&mut Overwrite(unsafe { s.as_bytes_mut() }));
+    Ok(())
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_convert_chars_in_place() {
+    let policy = WidthPolicy::builder().all(super::WidthTarget::Fullwidth).build();
+    let mut cells = ['a', 'ｶ', 'ﾞ', '漢', 'Ｂ', ' '];
+    assert_eq!(policy.convert_chars_in_place(&mut cells), [0, 1, 2]);
+    assert_eq!(cells, ['ａ', 'カ', '\u{3099}', '漢', 'Ｂ', ' ']);
+    assert!(policy.convert_chars_in_place(&mut cells).is_empty());
+    assert!(policy.convert_chars_in_place(&mut []).is_empty());
+}
+
+#[test]
+fn test_convert_in_place_exact() {
+    let policies = [
+        WidthPolicy::default(),
+        WidthPolicy::builder().all(super::WidthTarget::Halfwidth).build(),
+        WidthPolicy::builder().minus(super::MinusMapping::MinusSign).compose_hangul(true).build(),
+    ];
+    for s in ["ｶﾀｶﾅ ｰ1 ﾊﾟ", "カタカナー2", "ﾊﾝｸﾞﾙ ﾡﾢ", "ﾡ ﾢ", "ＡＢ", "ab", ""] {
+        for policy in &policies {
+            let mut converted = s.to_owned();
+            match convert_in_place_exact(&mut converted, policy) {
+                Ok(()) => assert_eq!(converted, policy.convert(s), "{}", s),
+                Err(error) => {
+                    assert_eq!(converted, s);
+                    assert!(policy.convert(&s[..error.index + error.ch.len_utf8()]).len()
+                            != error.index + error.ch.len_utf8(), "{} {:?}", s, error);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_is_length_preserving() {
+    let policy = WidthPolicy::builder().signs(super::SignMapping::Ascii).build();
+    for s in ["ｶﾀｶﾅ", "ｶﾞ", "a￩b", "a￫b", "￥", "カナ　", ""] {
+        assert_eq!(is_length_preserving(s, &policy), policy.convert(s).len() == s.len(), "{}", s);
+        let chars = s.chars().all(|ch| is_char_length_preserving(ch, &policy));
+        assert!(chars || !is_length_preserving(s, &policy), "{}", s);
+    }
+}
@@ -0,0 +1,95 @@
+//! Conversion of every string in a JSON document.
+
+use serde_json::Value;
+
+use super::WidthPolicy;
+
+/// The number of strings changed by `convert_json`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct JsonChanges {
+    /// Changed string values.
+    pub values: usize,
+    /// Changed object keys.
+    pub keys: usize,
+}
+
+/// Converts every string value in `value` according to `policy`, and object
+/// keys too if `keys` is set. Returns the number of strings changed.
+///
+/// If a converted key equals another key of the same object, the entry that
+/// comes later in the iteration order of the object replaces the other. That
+/// is the order of the original keys, or the order they were inserted in with
+/// the `preserve_order` feature of `serde_json`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::WidthPolicy;
+///
+/// let mut value = serde_json::json!({ "ｶﾅ": ["ＡＢＣ", 1, { "x": "ｶﾞ" }] });
+/// let changes = unicode_hfwidth::convert_json(&mut value, &WidthPolicy::default(), true);
+/// assert_eq!(value, serde_json::json!({ "カナ": ["ABC", 1, { "x": "ガ" }] }));
+/// assert_eq!((changes.values, changes.keys), (2, 1));
+/// # }
+/// ```
+pub fn convert_json(value: &mut Value, policy: &WidthPolicy, keys: bool) -> JsonChanges {
+    convert_json_with(value, &|s| policy.convert(s), keys)
+}
+
+/// Converts the strings in `value` with `convert`, like `convert_json`.
+pub(crate) fn convert_json_with(value: &mut Value, convert: &dyn Fn(&str) -> String, keys: bool)
+                                -> JsonChanges {
+    let mut changes = JsonChanges::default();
+    walk(value, convert, keys, &mut changes);
+    changes
+}
+
+fn walk(value: &mut Value, convert: &dyn Fn(&str) -> String, keys: bool,
+        changes: &mut JsonChanges) {
+    match value {
+        Value::String(s) => {
+            let converted = convert(s);
+            if converted != *s {
+                *s = converted;
+                changes.values += 1;
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                walk(value, convert, keys, changes);
+            }
+        }
+        Value::Object(map) if keys => {
+            for (key, mut value) in std::mem::take(map) {
+                walk(&mut value, convert, keys, changes);
+                let converted = convert(&key);
+                if converted != key {
+                    changes.keys += 1;
+                }
+                map.insert(converted, value);
+            }
+        }
+        Value::Object(map) => {
+            for value in map.values_mut() {
+                walk(value, convert, keys, changes);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_convert_json_values_only() {
+    let mut value = serde_json::json!({ "ｷｰ": "ﾊﾞﾘｭｰ", "n": null, "s": "same" });
+    let changes = convert_json(&mut value, &WidthPolicy::default(), false);
+    assert_eq!(value, serde_json::json!({ "ｷｰ": "バリュー", "n": null, "s": "same" }));
+    assert_eq!(changes, JsonChanges { values: 1, keys: 0 });
+    let mut map = serde_json::Map::new();
+    map.insert("ａ".to_owned(), Value::from(1));
+    map.insert("a".to_owned(), Value::from(2));
+    let mut value = Value::Object(map);
+    convert_json(&mut value, &WidthPolicy::default(), true);
+    /* "ａ" sorts after "a", so its entry wins */
+    assert_eq!(value, serde_json::json!({ "a": 1 }));
+}
@@ -0,0 +1,94 @@
+//! Fixed-length fields in legacy encodings such as Shift_JIS.
+
+use std::error::Error;
+use std::fmt;
+
+use encoding_rs::{Encoding, ISO_2022_JP};
+
+use super::table::Align;
+
+/// Error returned when a character cannot be represented in the target
+/// encoding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnmappableChar {
+    /// Byte index of the character in the input.
+    pub index: usize,
+    /// The character.
+    pub ch: char,
+}
+
+impl fmt::Display for UnmappableChar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "character {:?} at index {} cannot be encoded", self.ch, self.index)
+    }
+}
+
+impl Error for UnmappableChar {}
+
+/// Encodes `s` in `encoding` as a field of exactly `len` bytes, padding with
+/// `fill` or truncating as needed. Truncation never splits a multi-byte
+/// character; the bytes left over are padded instead. `align` decides which
+/// side the padding goes on.
+///
+/// In Shift_JIS, half-width katakana take one byte and full-width characters
+/// two, which is what fixed-length formats such as Zengin bank transfer files
+/// rely on.
+///
+/// # Panics
+/// Panics if `encoding` is stateful, that is, ISO-2022-JP, whose escape
+/// sequences make the length of a character depend on the ones before it.
+///
+/// # Example
+/// ```rust
+/// use unicode_hfwidth::Align;
+///
+/// let field = unicode_hfwidth::encode_fixed("ﾔﾏﾀﾞ ﾀﾛｳ", 10, encoding_rs::SHIFT_JIS, b' ', Align::Left);
+/// assert_eq!(field.unwrap(), b"\xd4\xcf\xc0\xde \xc0\xdb\xb3  ");
+///
+/// let field = unicode_hfwidth::encode_fixed("123", 6, encoding_rs::SHIFT_JIS, b'0', Align::Right);
+/// assert_eq!(field.unwrap(), b"000123");
+/// ```
+pub fn encode_fixed<S: AsRef<str>>(s: S, len: usize, encoding: &'static Encoding, fill: u8,
+                                   align: Align) -> Result<Vec<u8>, UnmappableChar> {
+    assert!(encoding.output_encoding() != ISO_2022_JP,
+            "fixed-length fields cannot be encoded in {}", encoding.name());
+    let s = s.as_ref();
+    let mut body = Vec::with_capacity(len);
+    let mut buf = [0; 4];
+    for (index, ch) in s.char_indices() {
+        let (bytes, _, had_errors) = encoding.encode(ch.encode_utf8(&mut buf));
+        if had_errors {
+            return Err(UnmappableChar { index, ch });
+        }
+        if body.len() + bytes.len() > len {
+            break;
+        }
+        body.extend_from_slice(&bytes);
+    }
+    let missing = len - body.len();
+    let left = match align {
+        Align::Left   => 0,
+        Align::Right  => missing,
+        Align::Center => missing / 2,
+    };
+    let mut field = vec![fill; left];
+    field.extend_from_slice(&body);
+    field.resize(len, fill);
+    Ok(field)
+}
+
+#[test]
+fn test_encode_fixed() {
+    let sjis = encoding_rs::SHIFT_JIS;
+    assert_eq!(encode_fixed("カナ", 3, sjis, b' ', Align::Left).unwrap(), b"\x83J ");
+    assert_eq!(encode_fixed("ｶﾅ", 3, sjis, b' ', Align::Left).unwrap(), b"\xb6\xc5 ");
+    assert_eq!(encode_fixed("abc", 2, sjis, b' ', Align::Right).unwrap(), b"ab");
+    assert_eq!(encode_fixed("a😀", 4, sjis, b' ', Align::Left),
+               Err(UnmappableChar { index: 1, ch: '😀' }));
+}
+
+#[test]
+#[should_panic]
+fn test_encode_fixed_stateful() {
+    let _ = encode_fixed("ｶﾅ", 10, ISO_2022_JP, b' ', Align::Left);
+}
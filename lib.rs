@@ -1,6 +1,150 @@
 //! Utilities for handling characters in the Unicode "Halfwidth and Fullwidth Forms" block.
 
-use std::mem::transmute;
+mod analysis;
+mod converted;
+mod converter;
+mod cp932;
+mod display;
+mod edit;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod filename;
+mod fold;
+mod idna;
+mod inplace;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "encoding")]
+mod legacy;
+pub mod lint;
+mod markdown;
+#[cfg(feature = "xml")]
+mod markup;
+#[cfg(feature = "mobile")]
+pub mod mobile;
+#[cfg(feature = "unicode-normalization")]
+mod normalization;
+mod normalized;
+mod numeric;
+mod offsets;
+mod parse;
+mod policy;
+mod pretokenize;
+mod preset;
+#[cfg(feature = "provider")]
+mod provider;
+#[cfg(any(feature = "toml", feature = "yaml"))]
+mod profile;
+#[cfg(feature = "python")]
+mod python;
+mod recognize;
+#[cfg(feature = "csv")]
+mod records;
+mod registry;
+mod report;
+mod restore;
+mod reuse;
+#[cfg(feature = "sqlx")]
+mod sql;
+mod scan;
+mod script;
+mod search;
+mod sink;
+#[cfg(any(feature = "compact_str", feature = "smol_str"))]
+mod smallstr;
+mod spoof;
+mod stream;
+mod strict;
+mod table;
+#[cfg(feature = "testgen")]
+mod testgen;
+#[cfg(feature = "tantivy")]
+mod tokenizer;
+mod trace;
+mod translit;
+mod url;
+mod utf16;
+mod variants;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "axum")]
+mod web;
+
+pub use analysis::{analyze, converted_len_utf8, detect_dominant_form, normalize_to_dominant,
+                   standard_form_width, WidthForm, WidthStats};
+pub use converted::{fullwidth_chars, halfwidth_chars, Converted};
+pub use converter::{CancellationToken, Cancelled, Emails, Placeholders, Protector, Quotes, Urls,
+                    WidthConverter};
+pub use cp932::{from_cp932_compatible, to_cp932_compatible};
+pub use display::{byte_at_column, center, char_width, column_at_byte, cursor_column,
+                  expand_to_width, pad_left, pad_right, shrink_to_width, str_width,
+                  str_width_ansi, strip_ansi, truncate_to_width, wrap_to_width, Ellipsis,
+                  Fill, Fitted, Kinsoku, WrapToWidth};
+pub use edit::{diff_normalize, Edit};
+pub use filename::{sanitize_filename, FilenameError, Lookalikes};
+pub use fold::{fold_width, nfkc_composition, nfkc_decomposition};
+pub use idna::{idna_map, normalize_hostname, HostnameError};
+pub use inplace::{convert_in_place_exact, is_char_length_preserving, is_length_preserving,
+                  NotLengthPreserving};
+#[cfg(feature = "json")]
+pub use json::{convert_json, JsonChanges};
+#[cfg(feature = "encoding")]
+pub use legacy::{encode_fixed, UnmappableChar};
+pub use markdown::convert_markdown;
+#[cfg(feature = "xml")]
+pub use markup::convert_markup;
+#[cfg(feature = "unicode-normalization")]
+pub use normalization::{WidthFold, WidthNormalization};
+pub use normalized::WidthNormalized;
+pub use numeric::{lint_numeric_field, sanitize_numeric_field, NumericDiagnostic};
+pub use offsets::{remap_matches, OffsetMap};
+pub use parse::{FromStrWidth, WidthLenient};
+pub use policy::{filter_map_width, CurrencyMapping, CurrencyPolicy, CurrencySymbol, JamoForm,
+                 MiddleDotMapping, MinusMapping, QuoteMapping, SignMapping, UnknownTarget,
+                 WaveDashMapping, WidthPolicy, WidthPolicyBuilder, WidthTarget};
+pub use pretokenize::Pretokenized;
+pub use preset::{normalize_ja_standard, precis_username_casemapped, precis_width_map, Preset};
+#[cfg(feature = "provider")]
+pub use provider::{BakedWidthProvider, MissingWidthData, WidthDataProvider, WidthMappingKey,
+                   WidthMappingsV1};
+#[cfg(any(feature = "toml", feature = "yaml"))]
+pub use profile::{Profile, ProfileError};
+pub use recognize::{take_digits_any_width, take_fullwidth_digits, take_fullwidth_latin,
+                    take_halfwidth_hangul, take_halfwidth_katakana};
+#[cfg(feature = "csv")]
+pub use records::CsvTransformer;
+pub use registry::PolicyRegistry;
+pub use report::ConversionReport;
+pub use restore::{normalize_reversible, RestoreMap};
+pub use reuse::{normalize_arc, to_standard_width_str_reuse};
+pub use scan::{all_nonstandard, contains_nonstandard_width, first_nonstandard, has_fullwidth_ascii,
+               has_halfwidth_hangul, has_halfwidth_katakana, nonstandard_chars, AllNonstandard};
+pub use script::{is_fullwidth_digit, is_fullwidth_katakana_compatible, is_fullwidth_latin,
+                 is_halfwidth_jamo, is_halfwidth_katakana};
+pub use search::WidthFilter;
+pub use sink::{FullwidthChars, FullwidthOf, FullwidthString, HalfwidthOf, HalfwidthString};
+#[cfg(feature = "compact_str")]
+pub use smallstr::to_standard_width_compact;
+#[cfg(feature = "smol_str")]
+pub use smallstr::to_standard_width_smol;
+pub use spoof::{check_width_spoof, WidthSpoof};
+pub use stream::StreamConverter;
+pub use strict::{try_to_fullwidth, try_to_halfwidth, TryConvertError};
+pub use table::{Align, Table};
+#[cfg(feature = "testgen")]
+pub use testgen::MixedWidthGenerator;
+#[cfg(feature = "tantivy")]
+pub use tokenizer::{WidthFilterTokenStream, WidthFilterTokenizer};
+pub use translit::{Transliterator, UnknownTransform};
+pub use url::{normalize_url_host, normalize_url_path, normalize_url_query_value};
+pub use utf16::{str_width_utf16, to_fullwidth_utf16, to_halfwidth_utf16,
+                to_standard_width_utf16, Utf16Position};
+pub use variants::{width_variants, WidthVariants};
+#[cfg(feature = "axum")]
+pub use web::normalize_request;
+
+#[cfg(feature = "mobile")]
+uniffi::setup_scaffolding!();
 
 /// Checks if `ch` is in the Unicode "Halfwidth and Fullwidth Forms" block.
 ///
@@ -10,27 +154,141 @@ use std::mem::transmute;
 /// assert_eq!(unicode_hfwidth::is_nonstandard_width('ｶ'), true);
 /// ```
 pub fn is_nonstandard_width(ch: char) -> bool {
+    is_nonstandard_width_u32(ch as u32)
+}
+
+/// Checks if the code point `ch` is in the Unicode "Halfwidth and Fullwidth
+/// Forms" block, like `is_nonstandard_width`.
+pub fn is_nonstandard_width_u32(ch: u32) -> bool {
+    matches!(ch, 0xff00..=0xffee)
+}
+
+/// The sub-blocks of the Unicode "Halfwidth and Fullwidth Forms" block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+           serde(rename_all = "snake_case"))]
+pub enum FormsCategory {
+    /// Full-width variants of ASCII characters, U+FF01 to U+FF5E.
+    FullwidthAscii,
+    /// Full-width white parentheses, U+FF5F to U+FF60.
+    FullwidthBrackets,
+    /// Half-width CJK punctuation, U+FF61 to U+FF64.
+    HalfwidthPunctuation,
+    /// Half-width katakana, U+FF65 to U+FF9F.
+    HalfwidthKatakana,
+    /// Half-width Hangul compatibility jamo, U+FFA0 to U+FFDC.
+    HalfwidthHangul,
+    /// Full-width symbol variants, U+FFE0 to U+FFE6.
+    FullwidthSymbols,
+    /// Half-width symbol variants, U+FFE8 to U+FFEE.
+    HalfwidthSymbols,
+}
+
+impl FormsCategory {
+    /// All categories, in code point order.
+    pub const ALL: [FormsCategory; 7] = [
+        FormsCategory::FullwidthAscii,
+        FormsCategory::FullwidthBrackets,
+        FormsCategory::HalfwidthPunctuation,
+        FormsCategory::HalfwidthKatakana,
+        FormsCategory::HalfwidthHangul,
+        FormsCategory::FullwidthSymbols,
+        FormsCategory::HalfwidthSymbols,
+    ];
+}
+
+/// Returns the sub-block `ch` belongs to. If `ch` is not an assigned character
+/// in the Unicode "Halfwidth and Fullwidth Forms" block, returns `None`.
+///
+/// # Example
+/// ```rust
+/// use unicode_hfwidth::FormsCategory;
+///
+/// assert_eq!(unicode_hfwidth::forms_category('ｶ'), Some(FormsCategory::HalfwidthKatakana));
+/// assert_eq!(unicode_hfwidth::forms_category('ａ'), Some(FormsCategory::FullwidthAscii));
+/// assert_eq!(unicode_hfwidth::forms_category('カ'), None);
+/// ```
+pub fn forms_category(ch: char) -> Option<FormsCategory> {
     match ch as u32 {
-        0xff00...0xffee => true,
-        _               => false,
+        0xff01..=0xff5e => Some(FormsCategory::FullwidthAscii),
+        0xff5f..=0xff60 => Some(FormsCategory::FullwidthBrackets),
+        0xff61..=0xff64 => Some(FormsCategory::HalfwidthPunctuation),
+        0xff65..=0xff9f => Some(FormsCategory::HalfwidthKatakana),
+        0xffa0..=0xffdc => match ch as u32 {
+            0xffbf..=0xffc1 | 0xffc8..=0xffc9 | 0xffd0..=0xffd1 | 0xffd8..=0xffd9 => None,
+            _ => Some(FormsCategory::HalfwidthHangul),
+        },
+        0xffe0..=0xffe6 => Some(FormsCategory::FullwidthSymbols),
+        0xffe8..=0xffee => Some(FormsCategory::HalfwidthSymbols),
+        _               => None,
     }
 }
 
+/// Returns the sub-block of the width variant pair `ch` is part of, whether
+/// `ch` itself is the block character or its standard counterpart.
+pub(crate) fn pair_category(ch: char) -> Option<FormsCategory> {
+    forms_category(ch)
+        .or_else(|| to_halfwidth(ch).and_then(forms_category))
+        .or_else(|| to_fullwidth(ch).and_then(forms_category))
+}
+
+/// Returns the precomposed kana for `base` followed by the combining voiced
+/// (U+3099) or semi-voiced (U+309A) sound mark `mark`, if there is one.
+pub(crate) fn compose_kana(base: char, mark: char) -> Option<char> {
+    let b = base as u32;
+    /* Hiragana compose like the katakana 0x60 above them */
+    let k = if matches!(b, 0x3041..=0x309f) { b + 0x60 } else { b };
+    let ka_to = matches!(k, 0x30ab..=0x30c1) && !k.is_multiple_of(2)
+        || matches!(k, 0x30c4..=0x30c8) && k.is_multiple_of(2);
+    let ha_ho = matches!(k, 0x30cf..=0x30db) && (k - 0x30cf).is_multiple_of(3);
+    let composed = match (mark, b) {
+        ('\u{3099}', _) if ka_to || ha_ho => b + 1,
+        ('\u{309a}', _) if ha_ho          => b + 2,
+        ('\u{3099}', 0x3046 | 0x30a6)     => b + 0x4e,
+        ('\u{3099}', 0x30ef..=0x30f2)     => b + 8,
+        ('\u{3099}', 0x309d | 0x30fd)     => b + 1,
+        _                                 => return None,
+    };
+    char::from_u32(composed)
+}
+
 /// Returns the standard-width form for `ch`. If `ch` is not in the Unicode
 /// "Halfwidth and Fullwidth forms" block, returns `None`.
 ///
 /// # Example
 /// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
 /// assert_eq!(unicode_hfwidth::to_standard_width('カ'), None);
 /// assert_eq!(unicode_hfwidth::to_standard_width('ｶ'), Some('カ'));
 /// assert_eq!(unicode_hfwidth::to_standard_width('ａ'), Some('a'));
+/// # }
 /// ```
 pub fn to_standard_width(ch: char) -> Option<char> {
     match ch as u32 {
-        0xff01...0xff60 => to_halfwidth(ch),
-        0xff61...0xffdc => to_fullwidth(ch),
-        0xffe0...0xffe6 => to_halfwidth(ch),
-        0xffe8...0xffee => to_fullwidth(ch),
+        0xff01..=0xff60 => to_halfwidth(ch),
+        0xff61..=0xffdc => to_fullwidth(ch),
+        0xffe0..=0xffe6 => to_halfwidth(ch),
+        0xffe8..=0xffee => to_fullwidth(ch),
+        _               => None
+    }
+}
+
+/// Returns the standard-width form for the code point `ch`, like
+/// `to_standard_width`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "kana")] {
+/// assert_eq!(unicode_hfwidth::to_standard_width_u32(0xff76), Some(0x30ab));
+/// assert_eq!(unicode_hfwidth::to_standard_width_u32(0x30ab), None);
+/// # }
+/// ```
+pub fn to_standard_width_u32(ch: u32) -> Option<u32> {
+    match ch {
+        0xff01..=0xff60 => to_halfwidth_u32(ch),
+        0xff61..=0xffdc => to_fullwidth_u32(ch),
+        0xffe0..=0xffe6 => to_halfwidth_u32(ch),
+        0xffe8..=0xffee => to_fullwidth_u32(ch),
         _               => None
     }
 }
@@ -38,305 +296,376 @@ pub fn to_standard_width(ch: char) -> Option<char> {
 /// Returns the half-width form for `ch`. If no half-width form for `ch` exists,
 /// or `ch` is already in half-width form, returns `None`.
 ///
+/// Characters of the table subsets disabled by turning off the default
+/// `kana`, `hangul`, `symbols` or `latin` features have no form.
+///
 /// # Example
 /// ```rust
+/// # #[cfg(feature = "kana")] {
 /// assert_eq!(unicode_hfwidth::to_halfwidth('カ'), Some('ｶ'));
 /// assert_eq!(unicode_hfwidth::to_halfwidth('a'), None);
+/// # }
 /// ```
 pub fn to_halfwidth(ch: char) -> Option<char> {
-    let ch = ch as u32;
-    unsafe {
-        match ch {
-            /* Full-width variant characters */
-            0xff01...0xff5e => Some(transmute(ch - 0xff01 + 0x0021)),
-            0xff5f...0xff60 => Some(transmute(ch - 0xff5f + 0x2985)),
-            0xffe0...0xffe1 => Some(transmute(ch - 0xffe0 + 0x00a2)),
-            0xffe2          => Some(transmute(0x00acu32)),
-            0xffe3          => Some(transmute(0x00afu32)),
-            0xffe4          => Some(transmute(0x00a6u32)),
-            0xffe5          => Some(transmute(0x00a5u32)),
-            0xffe6          => Some(transmute(0x20a9u32)),
+    /* The tables only contain valid code points */
+    to_halfwidth_u32(ch as u32).map(|ch| unsafe { char::from_u32_unchecked(ch) })
+}
 
-            /* Natural full-width characters */
-            0x3002 => Some(transmute(0xff61u32)),
-            0x300c => Some(transmute(0xff62u32)),
-            0x300d => Some(transmute(0xff63u32)),
-            0x3001 => Some(transmute(0xff64u32)),
-            0x30fb => Some(transmute(0xff65u32)),
-            0x30f2 => Some(transmute(0xff66u32)),
-            0x30a1 => Some(transmute(0xff67u32)),
-            0x30a3 => Some(transmute(0xff68u32)),
-            0x30a5 => Some(transmute(0xff69u32)),
-            0x30a7 => Some(transmute(0xff6au32)),
-            0x30a9 => Some(transmute(0xff6bu32)),
-            0x30e3 => Some(transmute(0xff6cu32)),
-            0x30e5 => Some(transmute(0xff6du32)),
-            0x30e7 => Some(transmute(0xff6eu32)),
-            0x30c3 => Some(transmute(0xff6fu32)),
-            0x30fc => Some(transmute(0xff70u32)),
-            0x30a2 => Some(transmute(0xff71u32)),
-            0x30a4 => Some(transmute(0xff72u32)),
-            0x30a6 => Some(transmute(0xff73u32)),
-            0x30a8 => Some(transmute(0xff74u32)),
-            0x30aa => Some(transmute(0xff75u32)),
-            0x30ab => Some(transmute(0xff76u32)),
-            0x30ad => Some(transmute(0xff77u32)),
-            0x30af => Some(transmute(0xff78u32)),
-            0x30b1 => Some(transmute(0xff79u32)),
-            0x30b3 => Some(transmute(0xff7au32)),
-            0x30b5 => Some(transmute(0xff7bu32)),
-            0x30b7 => Some(transmute(0xff7cu32)),
-            0x30b9 => Some(transmute(0xff7du32)),
-            0x30bb => Some(transmute(0xff7eu32)),
-            0x30bd => Some(transmute(0xff7fu32)),
-            0x30bf => Some(transmute(0xff80u32)),
-            0x30c1 => Some(transmute(0xff81u32)),
-            0x30c4 => Some(transmute(0xff82u32)),
-            0x30c6 => Some(transmute(0xff83u32)),
-            0x30c8 => Some(transmute(0xff84u32)),
-            0x30ca => Some(transmute(0xff85u32)),
-            0x30cb => Some(transmute(0xff86u32)),
-            0x30cc => Some(transmute(0xff87u32)),
-            0x30cd => Some(transmute(0xff88u32)),
-            0x30ce => Some(transmute(0xff89u32)),
-            0x30cf => Some(transmute(0xff8au32)),
-            0x30d2 => Some(transmute(0xff8bu32)),
-            0x30d5 => Some(transmute(0xff8cu32)),
-            0x30d8 => Some(transmute(0xff8du32)),
-            0x30db => Some(transmute(0xff8eu32)),
-            0x30de => Some(transmute(0xff8fu32)),
-            0x30df => Some(transmute(0xff90u32)),
-            0x30e0 => Some(transmute(0xff91u32)),
-            0x30e1 => Some(transmute(0xff92u32)),
-            0x30e2 => Some(transmute(0xff93u32)),
-            0x30e4 => Some(transmute(0xff94u32)),
-            0x30e6 => Some(transmute(0xff95u32)),
-            0x30e8 => Some(transmute(0xff96u32)),
-            0x30e9 => Some(transmute(0xff97u32)),
-            0x30ea => Some(transmute(0xff98u32)),
-            0x30eb => Some(transmute(0xff99u32)),
-            0x30ec => Some(transmute(0xff9au32)),
-            0x30ed => Some(transmute(0xff9bu32)),
-            0x30ef => Some(transmute(0xff9cu32)),
-            0x30f3 => Some(transmute(0xff9du32)),
-            0x3099 => Some(transmute(0xff9eu32)),
-            0x309a => Some(transmute(0xff9fu32)),
-            0x3164 => Some(transmute(0xffa0u32)),
-            0x3131 => Some(transmute(0xffa1u32)),
-            0x3132 => Some(transmute(0xffa2u32)),
-            0x3133 => Some(transmute(0xffa3u32)),
-            0x3134 => Some(transmute(0xffa4u32)),
-            0x3135 => Some(transmute(0xffa5u32)),
-            0x3136 => Some(transmute(0xffa6u32)),
-            0x3137 => Some(transmute(0xffa7u32)),
-            0x3138 => Some(transmute(0xffa8u32)),
-            0x3139 => Some(transmute(0xffa9u32)),
-            0x313a => Some(transmute(0xffaau32)),
-            0x313b => Some(transmute(0xffabu32)),
-            0x313c => Some(transmute(0xffacu32)),
-            0x313d => Some(transmute(0xffadu32)),
-            0x313e => Some(transmute(0xffaeu32)),
-            0x313f => Some(transmute(0xffafu32)),
-            0x3140 => Some(transmute(0xffb0u32)),
-            0x3141 => Some(transmute(0xffb1u32)),
-            0x3142 => Some(transmute(0xffb2u32)),
-            0x3143 => Some(transmute(0xffb3u32)),
-            0x3144 => Some(transmute(0xffb4u32)),
-            0x3145 => Some(transmute(0xffb5u32)),
-            0x3146 => Some(transmute(0xffb6u32)),
-            0x3147 => Some(transmute(0xffb7u32)),
-            0x3148 => Some(transmute(0xffb8u32)),
-            0x3149 => Some(transmute(0xffb9u32)),
-            0x314a => Some(transmute(0xffbau32)),
-            0x314b => Some(transmute(0xffbbu32)),
-            0x314c => Some(transmute(0xffbcu32)),
-            0x314d => Some(transmute(0xffbdu32)),
-            0x314e => Some(transmute(0xffbeu32)),
-            0x314f => Some(transmute(0xffc2u32)),
-            0x3150 => Some(transmute(0xffc3u32)),
-            0x3151 => Some(transmute(0xffc4u32)),
-            0x3152 => Some(transmute(0xffc5u32)),
-            0x3153 => Some(transmute(0xffc6u32)),
-            0x3154 => Some(transmute(0xffc7u32)),
-            0x3155 => Some(transmute(0xffcau32)),
-            0x3156 => Some(transmute(0xffcbu32)),
-            0x3157 => Some(transmute(0xffccu32)),
-            0x3158 => Some(transmute(0xffcdu32)),
-            0x3159 => Some(transmute(0xffceu32)),
-            0x315a => Some(transmute(0xffcfu32)),
-            0x315b => Some(transmute(0xffd2u32)),
-            0x315c => Some(transmute(0xffd3u32)),
-            0x315d => Some(transmute(0xffd4u32)),
-            0x315e => Some(transmute(0xffd5u32)),
-            0x315f => Some(transmute(0xffd6u32)),
-            0x3160 => Some(transmute(0xffd7u32)),
-            0x3161 => Some(transmute(0xffdau32)),
-            0x3162 => Some(transmute(0xffdbu32)),
-            0x3163 => Some(transmute(0xffdcu32)),
-            0x2502 => Some(transmute(0xffe8u32)),
-            0x2190 => Some(transmute(0xffe9u32)),
-            0x2191 => Some(transmute(0xffeau32)),
-            0x2192 => Some(transmute(0xffebu32)),
-            0x2193 => Some(transmute(0xffecu32)),
-            0x25a0 => Some(transmute(0xffedu32)),
-            0x25cb => Some(transmute(0xffeeu32)),
+/// Returns the half-width form for the code point `ch`, like `to_halfwidth`.
+/// Surrogates and values beyond U+10FFFF have no form.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "kana")] {
+/// assert_eq!(unicode_hfwidth::to_halfwidth_u32(0x30ab), Some(0xff76));
+/// assert_eq!(unicode_hfwidth::to_halfwidth_u32(0xd800), None);
+/// # }
+/// ```
+pub fn to_halfwidth_u32(ch: u32) -> Option<u32> {
+    match ch {
+        /* Full-width variant characters */
+        #[cfg(feature = "latin")]
+        0xff01..=0xff5e => Some(ch - 0xff01 + 0x0021),
+        #[cfg(feature = "latin")]
+        0xff5f..=0xff60 => Some(ch - 0xff5f + 0x2985),
+        #[cfg(feature = "symbols")]
+        0xffe0..=0xffe6 => match ch {
+            0xffe0..=0xffe1 => Some(ch - 0xffe0 + 0x00a2),
+            0xffe2          => Some(0x00ac),
+            0xffe3          => Some(0x00af),
+            0xffe4          => Some(0x00a6),
+            0xffe5          => Some(0x00a5),
+            0xffe6          => Some(0x20a9),
+            _               => None,
+        },
 
-            _ => None
-        }
+        /* Natural full-width characters */
+        #[cfg(feature = "kana")]
+        0x3001..=0x30fc => match ch {
+            0x3002 => Some(0xff61),
+            0x300c => Some(0xff62),
+            0x300d => Some(0xff63),
+            0x3001 => Some(0xff64),
+            0x30fb => Some(0xff65),
+            0x30f2 => Some(0xff66),
+            0x30a1 => Some(0xff67),
+            0x30a3 => Some(0xff68),
+            0x30a5 => Some(0xff69),
+            0x30a7 => Some(0xff6a),
+            0x30a9 => Some(0xff6b),
+            0x30e3 => Some(0xff6c),
+            0x30e5 => Some(0xff6d),
+            0x30e7 => Some(0xff6e),
+            0x30c3 => Some(0xff6f),
+            0x30fc => Some(0xff70),
+            0x30a2 => Some(0xff71),
+            0x30a4 => Some(0xff72),
+            0x30a6 => Some(0xff73),
+            0x30a8 => Some(0xff74),
+            0x30aa => Some(0xff75),
+            0x30ab => Some(0xff76),
+            0x30ad => Some(0xff77),
+            0x30af => Some(0xff78),
+            0x30b1 => Some(0xff79),
+            0x30b3 => Some(0xff7a),
+            0x30b5 => Some(0xff7b),
+            0x30b7 => Some(0xff7c),
+            0x30b9 => Some(0xff7d),
+            0x30bb => Some(0xff7e),
+            0x30bd => Some(0xff7f),
+            0x30bf => Some(0xff80),
+            0x30c1 => Some(0xff81),
+            0x30c4 => Some(0xff82),
+            0x30c6 => Some(0xff83),
+            0x30c8 => Some(0xff84),
+            0x30ca => Some(0xff85),
+            0x30cb => Some(0xff86),
+            0x30cc => Some(0xff87),
+            0x30cd => Some(0xff88),
+            0x30ce => Some(0xff89),
+            0x30cf => Some(0xff8a),
+            0x30d2 => Some(0xff8b),
+            0x30d5 => Some(0xff8c),
+            0x30d8 => Some(0xff8d),
+            0x30db => Some(0xff8e),
+            0x30de => Some(0xff8f),
+            0x30df => Some(0xff90),
+            0x30e0 => Some(0xff91),
+            0x30e1 => Some(0xff92),
+            0x30e2 => Some(0xff93),
+            0x30e4 => Some(0xff94),
+            0x30e6 => Some(0xff95),
+            0x30e8 => Some(0xff96),
+            0x30e9 => Some(0xff97),
+            0x30ea => Some(0xff98),
+            0x30eb => Some(0xff99),
+            0x30ec => Some(0xff9a),
+            0x30ed => Some(0xff9b),
+            0x30ef => Some(0xff9c),
+            0x30f3 => Some(0xff9d),
+            0x3099 => Some(0xff9e),
+            0x309a => Some(0xff9f),
+            _ => None,
+        },
+        #[cfg(feature = "hangul")]
+        0x3131..=0x3164 => match ch {
+            0x3164 => Some(0xffa0),
+            0x3131 => Some(0xffa1),
+            0x3132 => Some(0xffa2),
+            0x3133 => Some(0xffa3),
+            0x3134 => Some(0xffa4),
+            0x3135 => Some(0xffa5),
+            0x3136 => Some(0xffa6),
+            0x3137 => Some(0xffa7),
+            0x3138 => Some(0xffa8),
+            0x3139 => Some(0xffa9),
+            0x313a => Some(0xffaa),
+            0x313b => Some(0xffab),
+            0x313c => Some(0xffac),
+            0x313d => Some(0xffad),
+            0x313e => Some(0xffae),
+            0x313f => Some(0xffaf),
+            0x3140 => Some(0xffb0),
+            0x3141 => Some(0xffb1),
+            0x3142 => Some(0xffb2),
+            0x3143 => Some(0xffb3),
+            0x3144 => Some(0xffb4),
+            0x3145 => Some(0xffb5),
+            0x3146 => Some(0xffb6),
+            0x3147 => Some(0xffb7),
+            0x3148 => Some(0xffb8),
+            0x3149 => Some(0xffb9),
+            0x314a => Some(0xffba),
+            0x314b => Some(0xffbb),
+            0x314c => Some(0xffbc),
+            0x314d => Some(0xffbd),
+            0x314e => Some(0xffbe),
+            0x314f => Some(0xffc2),
+            0x3150 => Some(0xffc3),
+            0x3151 => Some(0xffc4),
+            0x3152 => Some(0xffc5),
+            0x3153 => Some(0xffc6),
+            0x3154 => Some(0xffc7),
+            0x3155 => Some(0xffca),
+            0x3156 => Some(0xffcb),
+            0x3157 => Some(0xffcc),
+            0x3158 => Some(0xffcd),
+            0x3159 => Some(0xffce),
+            0x315a => Some(0xffcf),
+            0x315b => Some(0xffd2),
+            0x315c => Some(0xffd3),
+            0x315d => Some(0xffd4),
+            0x315e => Some(0xffd5),
+            0x315f => Some(0xffd6),
+            0x3160 => Some(0xffd7),
+            0x3161 => Some(0xffda),
+            0x3162 => Some(0xffdb),
+            0x3163 => Some(0xffdc),
+            _ => None,
+        },
+        #[cfg(feature = "symbols")]
+        0x2190..=0x25cb => match ch {
+            0x2502 => Some(0xffe8),
+            0x2190 => Some(0xffe9),
+            0x2191 => Some(0xffea),
+            0x2192 => Some(0xffeb),
+            0x2193 => Some(0xffec),
+            0x25a0 => Some(0xffed),
+            0x25cb => Some(0xffee),
+            _ => None,
+        },
+
+        _ => None
     }
 }
 
 /// Returns the full-width form for `ch`. If no full-width form for `ch` exists,
 /// or `ch` is already in full-width form, returns `None`.
 ///
+/// Characters of the table subsets disabled by turning off the default
+/// `kana`, `hangul`, `symbols` or `latin` features have no form.
+///
 /// # Example
 /// ```rust
+/// # #[cfg(feature = "latin")] {
 /// assert_eq!(unicode_hfwidth::to_fullwidth('a'), Some('ａ'));
 /// assert_eq!(unicode_hfwidth::to_fullwidth('カ'), None);
+/// # }
 /// ```
 pub fn to_fullwidth(ch: char) -> Option<char> {
-    let ch = ch as u32;
-    unsafe {
-        match ch {
-            /* Half-width variant characters */
-            0xff61 => Some(transmute(0x3002u32)),
-            0xff62 => Some(transmute(0x300cu32)),
-            0xff63 => Some(transmute(0x300du32)),
-            0xff64 => Some(transmute(0x3001u32)),
-            0xff65 => Some(transmute(0x30fbu32)),
-            0xff66 => Some(transmute(0x30f2u32)),
-            0xff67 => Some(transmute(0x30a1u32)),
-            0xff68 => Some(transmute(0x30a3u32)),
-            0xff69 => Some(transmute(0x30a5u32)),
-            0xff6a => Some(transmute(0x30a7u32)),
-            0xff6b => Some(transmute(0x30a9u32)),
-            0xff6c => Some(transmute(0x30e3u32)),
-            0xff6d => Some(transmute(0x30e5u32)),
-            0xff6e => Some(transmute(0x30e7u32)),
-            0xff6f => Some(transmute(0x30c3u32)),
-            0xff70 => Some(transmute(0x30fcu32)),
-            0xff71 => Some(transmute(0x30a2u32)),
-            0xff72 => Some(transmute(0x30a4u32)),
-            0xff73 => Some(transmute(0x30a6u32)),
-            0xff74 => Some(transmute(0x30a8u32)),
-            0xff75 => Some(transmute(0x30aau32)),
-            0xff76 => Some(transmute(0x30abu32)),
-            0xff77 => Some(transmute(0x30adu32)),
-            0xff78 => Some(transmute(0x30afu32)),
-            0xff79 => Some(transmute(0x30b1u32)),
-            0xff7a => Some(transmute(0x30b3u32)),
-            0xff7b => Some(transmute(0x30b5u32)),
-            0xff7c => Some(transmute(0x30b7u32)),
-            0xff7d => Some(transmute(0x30b9u32)),
-            0xff7e => Some(transmute(0x30bbu32)),
-            0xff7f => Some(transmute(0x30bdu32)),
-            0xff80 => Some(transmute(0x30bfu32)),
-            0xff81 => Some(transmute(0x30c1u32)),
-            0xff82 => Some(transmute(0x30c4u32)),
-            0xff83 => Some(transmute(0x30c6u32)),
-            0xff84 => Some(transmute(0x30c8u32)),
-            0xff85 => Some(transmute(0x30cau32)),
-            0xff86 => Some(transmute(0x30cbu32)),
-            0xff87 => Some(transmute(0x30ccu32)),
-            0xff88 => Some(transmute(0x30cdu32)),
-            0xff89 => Some(transmute(0x30ceu32)),
-            0xff8a => Some(transmute(0x30cfu32)),
-            0xff8b => Some(transmute(0x30d2u32)),
-            0xff8c => Some(transmute(0x30d5u32)),
-            0xff8d => Some(transmute(0x30d8u32)),
-            0xff8e => Some(transmute(0x30dbu32)),
-            0xff8f => Some(transmute(0x30deu32)),
-            0xff90 => Some(transmute(0x30dfu32)),
-            0xff91 => Some(transmute(0x30e0u32)),
-            0xff92 => Some(transmute(0x30e1u32)),
-            0xff93 => Some(transmute(0x30e2u32)),
-            0xff94 => Some(transmute(0x30e4u32)),
-            0xff95 => Some(transmute(0x30e6u32)),
-            0xff96 => Some(transmute(0x30e8u32)),
-            0xff97 => Some(transmute(0x30e9u32)),
-            0xff98 => Some(transmute(0x30eau32)),
-            0xff99 => Some(transmute(0x30ebu32)),
-            0xff9a => Some(transmute(0x30ecu32)),
-            0xff9b => Some(transmute(0x30edu32)),
-            0xff9c => Some(transmute(0x30efu32)),
-            0xff9d => Some(transmute(0x30f3u32)),
-            0xff9e => Some(transmute(0x3099u32)),
-            0xff9f => Some(transmute(0x309au32)),
-            0xffa0 => Some(transmute(0x3164u32)),
-            0xffa1 => Some(transmute(0x3131u32)),
-            0xffa2 => Some(transmute(0x3132u32)),
-            0xffa3 => Some(transmute(0x3133u32)),
-            0xffa4 => Some(transmute(0x3134u32)),
-            0xffa5 => Some(transmute(0x3135u32)),
-            0xffa6 => Some(transmute(0x3136u32)),
-            0xffa7 => Some(transmute(0x3137u32)),
-            0xffa8 => Some(transmute(0x3138u32)),
-            0xffa9 => Some(transmute(0x3139u32)),
-            0xffaa => Some(transmute(0x313au32)),
-            0xffab => Some(transmute(0x313bu32)),
-            0xffac => Some(transmute(0x313cu32)),
-            0xffad => Some(transmute(0x313du32)),
-            0xffae => Some(transmute(0x313eu32)),
-            0xffaf => Some(transmute(0x313fu32)),
-            0xffb0 => Some(transmute(0x3140u32)),
-            0xffb1 => Some(transmute(0x3141u32)),
-            0xffb2 => Some(transmute(0x3142u32)),
-            0xffb3 => Some(transmute(0x3143u32)),
-            0xffb4 => Some(transmute(0x3144u32)),
-            0xffb5 => Some(transmute(0x3145u32)),
-            0xffb6 => Some(transmute(0x3146u32)),
-            0xffb7 => Some(transmute(0x3147u32)),
-            0xffb8 => Some(transmute(0x3148u32)),
-            0xffb9 => Some(transmute(0x3149u32)),
-            0xffba => Some(transmute(0x314au32)),
-            0xffbb => Some(transmute(0x314bu32)),
-            0xffbc => Some(transmute(0x314cu32)),
-            0xffbd => Some(transmute(0x314du32)),
-            0xffbe => Some(transmute(0x314eu32)),
-            0xffc2 => Some(transmute(0x314fu32)),
-            0xffc3 => Some(transmute(0x3150u32)),
-            0xffc4 => Some(transmute(0x3151u32)),
-            0xffc5 => Some(transmute(0x3152u32)),
-            0xffc6 => Some(transmute(0x3153u32)),
-            0xffc7 => Some(transmute(0x3154u32)),
-            0xffca => Some(transmute(0x3155u32)),
-            0xffcb => Some(transmute(0x3156u32)),
-            0xffcc => Some(transmute(0x3157u32)),
-            0xffcd => Some(transmute(0x3158u32)),
-            0xffce => Some(transmute(0x3159u32)),
-            0xffcf => Some(transmute(0x315au32)),
-            0xffd2 => Some(transmute(0x315bu32)),
-            0xffd3 => Some(transmute(0x315cu32)),
-            0xffd4 => Some(transmute(0x315du32)),
-            0xffd5 => Some(transmute(0x315eu32)),
-            0xffd6 => Some(transmute(0x315fu32)),
-            0xffd7 => Some(transmute(0x3160u32)),
-            0xffda => Some(transmute(0x3161u32)),
-            0xffdb => Some(transmute(0x3162u32)),
-            0xffdc => Some(transmute(0x3163u32)),
-            0xffe8 => Some(transmute(0x2502u32)),
-            0xffe9 => Some(transmute(0x2190u32)),
-            0xffea => Some(transmute(0x2191u32)),
-            0xffeb => Some(transmute(0x2192u32)),
-            0xffec => Some(transmute(0x2193u32)),
-            0xffed => Some(transmute(0x25a0u32)),
-            0xffee => Some(transmute(0x25cbu32)),
-
-            /* Natural half-width characters */
-            0x0021...0x007e => Some(transmute(ch - 0x0021 + 0xff01)),
-            0x2985...0x2986 => Some(transmute(ch - 0x2985 + 0xff5f)),
-            0x00a2...0x00a3 => Some(transmute(ch - 0x00a2 + 0xffe0)),
-            0x00ac          => Some(transmute(0xffe2u32)),
-            0x00af          => Some(transmute(0xffe3u32)),
-            0x00a6          => Some(transmute(0xffe4u32)),
-            0x00a5          => Some(transmute(0xffe5u32)),
-            0x20a9          => Some(transmute(0xffe6u32)),
+    /* The tables only contain valid code points */
+    to_fullwidth_u32(ch as u32).map(|ch| unsafe { char::from_u32_unchecked(ch) })
+}
 
+/// Returns the full-width form for the code point `ch`, like `to_fullwidth`.
+/// Surrogates and values beyond U+10FFFF have no form.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// assert_eq!(unicode_hfwidth::to_fullwidth_u32(0x61), Some(0xff41));
+/// assert_eq!(unicode_hfwidth::to_fullwidth_u32(0x110061), None);
+/// # }
+/// ```
+pub fn to_fullwidth_u32(ch: u32) -> Option<u32> {
+    match ch {
+        /* Half-width variant characters */
+        #[cfg(feature = "kana")]
+        0xff61..=0xff9f => match ch {
+            0xff61 => Some(0x3002),
+            0xff62 => Some(0x300c),
+            0xff63 => Some(0x300d),
+            0xff64 => Some(0x3001),
+            0xff65 => Some(0x30fb),
+            0xff66 => Some(0x30f2),
+            0xff67 => Some(0x30a1),
+            0xff68 => Some(0x30a3),
+            0xff69 => Some(0x30a5),
+            0xff6a => Some(0x30a7),
+            0xff6b => Some(0x30a9),
+            0xff6c => Some(0x30e3),
+            0xff6d => Some(0x30e5),
+            0xff6e => Some(0x30e7),
+            0xff6f => Some(0x30c3),
+            0xff70 => Some(0x30fc),
+            0xff71 => Some(0x30a2),
+            0xff72 => Some(0x30a4),
+            0xff73 => Some(0x30a6),
+            0xff74 => Some(0x30a8),
+            0xff75 => Some(0x30aa),
+            0xff76 => Some(0x30ab),
+            0xff77 => Some(0x30ad),
+            0xff78 => Some(0x30af),
+            0xff79 => Some(0x30b1),
+            0xff7a => Some(0x30b3),
+            0xff7b => Some(0x30b5),
+            0xff7c => Some(0x30b7),
+            0xff7d => Some(0x30b9),
+            0xff7e => Some(0x30bb),
+            0xff7f => Some(0x30bd),
+            0xff80 => Some(0x30bf),
+            0xff81 => Some(0x30c1),
+            0xff82 => Some(0x30c4),
+            0xff83 => Some(0x30c6),
+            0xff84 => Some(0x30c8),
+            0xff85 => Some(0x30ca),
+            0xff86 => Some(0x30cb),
+            0xff87 => Some(0x30cc),
+            0xff88 => Some(0x30cd),
+            0xff89 => Some(0x30ce),
+            0xff8a => Some(0x30cf),
+            0xff8b => Some(0x30d2),
+            0xff8c => Some(0x30d5),
+            0xff8d => Some(0x30d8),
+            0xff8e => Some(0x30db),
+            0xff8f => Some(0x30de),
+            0xff90 => Some(0x30df),
+            0xff91 => Some(0x30e0),
+            0xff92 => Some(0x30e1),
+            0xff93 => Some(0x30e2),
+            0xff94 => Some(0x30e4),
+            0xff95 => Some(0x30e6),
+            0xff96 => Some(0x30e8),
+            0xff97 => Some(0x30e9),
+            0xff98 => Some(0x30ea),
+            0xff99 => Some(0x30eb),
+            0xff9a => Some(0x30ec),
+            0xff9b => Some(0x30ed),
+            0xff9c => Some(0x30ef),
+            0xff9d => Some(0x30f3),
+            0xff9e => Some(0x3099),
+            0xff9f => Some(0x309a),
             _ => None,
-        }
+        },
+        #[cfg(feature = "hangul")]
+        0xffa0..=0xffdc => match ch {
+            0xffa0 => Some(0x3164),
+            0xffa1 => Some(0x3131),
+            0xffa2 => Some(0x3132),
+            0xffa3 => Some(0x3133),
+            0xffa4 => Some(0x3134),
+            0xffa5 => Some(0x3135),
+            0xffa6 => Some(0x3136),
+            0xffa7 => Some(0x3137),
+            0xffa8 => Some(0x3138),
+            0xffa9 => Some(0x3139),
+            0xffaa => Some(0x313a),
+            0xffab => Some(0x313b),
+            0xffac => Some(0x313c),
+            0xffad => Some(0x313d),
+            0xffae => Some(0x313e),
+            0xffaf => Some(0x313f),
+            0xffb0 => Some(0x3140),
+            0xffb1 => Some(0x3141),
+            0xffb2 => Some(0x3142),
+            0xffb3 => Some(0x3143),
+            0xffb4 => Some(0x3144),
+            0xffb5 => Some(0x3145),
+            0xffb6 => Some(0x3146),
+            0xffb7 => Some(0x3147),
+            0xffb8 => Some(0x3148),
+            0xffb9 => Some(0x3149),
+            0xffba => Some(0x314a),
+            0xffbb => Some(0x314b),
+            0xffbc => Some(0x314c),
+            0xffbd => Some(0x314d),
+            0xffbe => Some(0x314e),
+            0xffc2 => Some(0x314f),
+            0xffc3 => Some(0x3150),
+            0xffc4 => Some(0x3151),
+            0xffc5 => Some(0x3152),
+            0xffc6 => Some(0x3153),
+            0xffc7 => Some(0x3154),
+            0xffca => Some(0x3155),
+            0xffcb => Some(0x3156),
+            0xffcc => Some(0x3157),
+            0xffcd => Some(0x3158),
+            0xffce => Some(0x3159),
+            0xffcf => Some(0x315a),
+            0xffd2 => Some(0x315b),
+            0xffd3 => Some(0x315c),
+            0xffd4 => Some(0x315d),
+            0xffd5 => Some(0x315e),
+            0xffd6 => Some(0x315f),
+            0xffd7 => Some(0x3160),
+            0xffda => Some(0x3161),
+            0xffdb => Some(0x3162),
+            0xffdc => Some(0x3163),
+            _ => None,
+        },
+        #[cfg(feature = "symbols")]
+        0xffe8..=0xffee => match ch {
+            0xffe8 => Some(0x2502),
+            0xffe9 => Some(0x2190),
+            0xffea => Some(0x2191),
+            0xffeb => Some(0x2192),
+            0xffec => Some(0x2193),
+            0xffed => Some(0x25a0),
+            0xffee => Some(0x25cb),
+            _ => None,
+        },
+
+        /* Natural half-width characters */
+        #[cfg(feature = "latin")]
+        0x0021..=0x007e => Some(ch - 0x0021 + 0xff01),
+        #[cfg(feature = "latin")]
+        0x2985..=0x2986 => Some(ch - 0x2985 + 0xff5f),
+        #[cfg(feature = "symbols")]
+        0x00a2..=0x20a9 => match ch {
+            0x00a2..=0x00a3 => Some(ch - 0x00a2 + 0xffe0),
+            0x00ac          => Some(0xffe2),
+            0x00af          => Some(0xffe3),
+            0x00a6          => Some(0xffe4),
+            0x00a5          => Some(0xffe5),
+            0x20a9          => Some(0xffe6),
+            _               => None,
+        },
+
+        _ => None,
     }
 }
 
+#[cfg(feature = "kana")]
 #[test]
 fn test_katakana() {
     let full = "アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン";
@@ -346,6 +675,7 @@ fn test_katakana() {
     }
 }
 
+#[cfg(feature = "kana")]
 #[test]
 fn test_katakana_rev() {
     let full = "アイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン";
@@ -355,6 +685,20 @@ fn test_katakana_rev() {
     }
 }
 
+#[test]
+fn test_u32() {
+    /* The character functions rely on the tables only returning valid code points */
+    for code in 0..=0x10ffff {
+        for form in [to_halfwidth_u32(code), to_fullwidth_u32(code), to_standard_width_u32(code)] {
+            assert!(form.is_none_or(|form| char::from_u32(form).is_some()), "U+{:04X}", code);
+        }
+    }
+    for code in (0xd800..=0xdfff).chain([0x110000, u32::MAX]) {
+        assert_eq!(to_halfwidth_u32(code).or(to_fullwidth_u32(code)), None);
+    }
+}
+
+#[cfg(feature = "latin")]
 #[test]
 fn test_a() {
     assert_eq!(to_fullwidth('a').unwrap(), 'ａ');
@@ -0,0 +1,208 @@
+//! Width lints.
+//!
+//! `check` reports characters of non-standard width as diagnostics with a
+//! suggested fix, for tools that surface problems instead of rewriting text.
+//!
+//! # Example
+//! ```rust
+//! # #[cfg(all(feature = "kana", feature = "latin"))] {
+//! use unicode_hfwidth::lint::{self, Rule};
+//!
+//! let diagnostics = lint::check("ﾒｰﾙ: ＡBC");
+//! assert_eq!(diagnostics[0].rule, Rule::HalfwidthKatakana);
+//! assert_eq!(diagnostics[0].fix.replacement, "メール");
+//! assert_eq!(diagnostics[1].rule.id(), "mixed-width-word");
+//! assert_eq!(diagnostics[1].message, "word 'ＡBC' mixes widths, should be 'ABC'");
+//! # }
+//! ```
+
+use std::fmt;
+use std::ops::Range;
+
+use super::{is_nonstandard_width, pair_category, Edit, FormsCategory, WidthPolicy};
+
+/// The kind of problem a diagnostic reports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// Full-width ASCII letters, punctuation or white parentheses.
+    FullwidthAscii,
+    /// A number written with full-width digits and signs.
+    FullwidthNumber,
+    /// Half-width katakana or CJK punctuation.
+    HalfwidthKatakana,
+    /// Half-width Hangul jamo.
+    HalfwidthHangul,
+    /// Full-width or half-width variants of symbols.
+    SymbolVariant,
+    /// A word mixing full-width and half-width forms of the same script.
+    MixedWidthWord,
+}
+
+impl Rule {
+    /// Returns the identifier of the rule, such as `halfwidth-katakana`.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Rule::FullwidthAscii    => "fullwidth-ascii",
+            Rule::FullwidthNumber   => "fullwidth-number",
+            Rule::HalfwidthKatakana => "halfwidth-katakana",
+            Rule::HalfwidthHangul   => "halfwidth-hangul",
+            Rule::SymbolVariant     => "symbol-variant",
+            Rule::MixedWidthWord    => "mixed-width-word",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Rule::FullwidthAscii    => "full-width ASCII",
+            Rule::FullwidthNumber   => "full-width number",
+            Rule::HalfwidthKatakana => "half-width katakana",
+            Rule::HalfwidthHangul   => "half-width Hangul",
+            Rule::SymbolVariant     => "symbol variant",
+            Rule::MixedWidthWord    => "word",
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// A problem found by `check`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The kind of problem.
+    pub rule: Rule,
+    /// A description of the problem.
+    pub message: String,
+    /// The byte range of the offending text.
+    pub span: Range<usize>,
+    /// The edit converting the offending text to standard width.
+    pub fix: Edit,
+}
+
+/// Returns the rule for characters of `category`.
+fn category_rule(category: FormsCategory) -> Rule {
+    match category {
+        FormsCategory::FullwidthAscii
+            | FormsCategory::FullwidthBrackets    => Rule::FullwidthAscii,
+        FormsCategory::HalfwidthPunctuation
+            | FormsCategory::HalfwidthKatakana    => Rule::HalfwidthKatakana,
+        FormsCategory::HalfwidthHangul            => Rule::HalfwidthHangul,
+        FormsCategory::FullwidthSymbols
+            | FormsCategory::HalfwidthSymbols     => Rule::SymbolVariant,
+    }
+}
+
+/// Returns the rule for `ch` if it is a character of non-standard width.
+fn char_rule(ch: char) -> Option<Rule> {
+    let converted = WidthPolicy::default().convert_char(ch);
+    if converted == ch && !is_nonstandard_width(ch) {
+        return None;
+    }
+    pair_category(ch).map(category_rule)
+}
+
+fn is_word_char(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, 'ー' | 'ﾞ' | 'ﾟ')
+}
+
+/// Returns `true` if `word` contains both non-standard characters and
+/// standard characters of the same rule.
+fn is_mixed(word: &str) -> bool {
+    let nonstandard: Vec<_> = word.chars().filter_map(char_rule).collect();
+    word.chars()
+        .filter(|&ch| char_rule(ch).is_none() && !matches!(ch, '\u{3099}' | '\u{309a}'))
+        .filter_map(|ch| pair_category(ch).map(category_rule))
+        .any(|rule| nonstandard.contains(&rule))
+}
+
+fn diagnostic(s: &str, rule: Rule, span: Range<usize>) -> Diagnostic {
+    let text = &s[span.clone()];
+    let replacement = WidthPolicy::default().convert(text);
+    Diagnostic {
+        rule,
+        message: format!("{} '{}' {}, should be '{}'", rule.description(), text,
+                         if rule == Rule::MixedWidthWord { "mixes widths" } else { "found" },
+                         replacement),
+        fix: Edit { range: span.clone(), replacement },
+        span,
+    }
+}
+
+/// Checks `s` for characters of non-standard width, returning diagnostics
+/// sorted by position.
+///
+/// Each run of non-standard characters of one rule is reported once. Words
+/// mixing both widths of one script, such as `ＡBC` or `ｶタカナ`, are
+/// reported as a whole instead, and runs of full-width digits and signs as
+/// numbers. The fixes do not overlap and convert to standard width.
+pub fn check<S: AsRef<str>>(s: S) -> Vec<Diagnostic> {
+    let s = s.as_ref();
+    let mut diagnostics = Vec::new();
+    let mut run: Option<(Rule, Range<usize>)> = None;
+    let mut word_start = None;
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, ch)) = chars.next() {
+        let end = i + ch.len_utf8();
+        if is_word_char(ch) && word_start.is_none() {
+            word_start = Some(i);
+        }
+        let word_end = !chars.peek().is_some_and(|&(_, next)| is_word_char(next));
+        let rule = char_rule(ch);
+        match (&mut run, rule) {
+            (Some((current, span)), Some(rule)) if *current == rule && span.end == i => {
+                span.end = end;
+            }
+            (_, Some(rule)) => {
+                diagnostics.extend(run.take().map(|(rule, span)| diagnostic(s, rule, span)));
+                run = Some((rule, i..end));
+            }
+            (_, None) => {
+                diagnostics.extend(run.take().map(|(rule, span)| diagnostic(s, rule, span)));
+            }
+        }
+        if let (Some(start), true) = (word_start, word_end) {
+            word_start = None;
+            if is_mixed(&s[start..end]) {
+                diagnostics.extend(run.take().map(|(rule, span)| diagnostic(s, rule, span)));
+                let overlapping = diagnostics.iter().position(|d| d.span.end > start);
+                for d in diagnostics.split_off(overlapping.unwrap_or(diagnostics.len())) {
+                    if d.span.start < start {
+                        diagnostics.push(diagnostic(s, d.rule, d.span.start..start));
+                    }
+                }
+                diagnostics.push(diagnostic(s, Rule::MixedWidthWord, start..end));
+            }
+        }
+    }
+    diagnostics.extend(run.map(|(rule, span)| diagnostic(s, rule, span)));
+    for d in &mut diagnostics {
+        let text = &s[d.span.clone()];
+        let number = text.chars().any(|ch| matches!(ch, '０'..='９'))
+            && text.chars().all(|ch| matches!(ch, '０'..='９' | '＋' | '－' | '．' | '，'));
+        if d.rule == Rule::FullwidthAscii && number {
+            *d = diagnostic(s, Rule::FullwidthNumber, d.span.clone());
+        }
+    }
+    diagnostics
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_check() {
+    let s = "ｶタカナ and ＡＢＣ, ｶﾞｲﾄﾞ ￦ １２．５ x１ ．Ｂc";
+    let found: Vec<_> = check(s).into_iter().map(|d| (d.rule, &s[d.span])).collect();
+    assert_eq!(found, [
+        (Rule::MixedWidthWord, "ｶタカナ"),
+        (Rule::FullwidthAscii, "ＡＢＣ"),
+        (Rule::HalfwidthKatakana, "ｶﾞｲﾄﾞ"),
+        (Rule::SymbolVariant, "￦"),
+        (Rule::FullwidthNumber, "１２．５"),
+        (Rule::MixedWidthWord, "x１"),
+        (Rule::FullwidthAscii, "．"),
+        (Rule::MixedWidthWord, "Ｂc"),
+    ]);
+    assert!(check("ABC カタカナ").is_empty());
+}
@@ -0,0 +1,158 @@
+//! Conversion of Markdown prose.
+
+use std::ops::Range;
+
+use super::WidthPolicy;
+
+/// Returns the character and length of the code fence `line` opens or
+/// closes, if it is one.
+fn fence_marker(line: &str) -> Option<(u8, usize)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let ch = *trimmed.as_bytes().first()?;
+    let len = trimmed.bytes().take_while(|&b| b == ch).count();
+    match ch {
+        b'`' if len >= 3 && !trimmed[len..].contains('`') => Some((ch, len)),
+        b'~' if len >= 3                                 => Some((ch, len)),
+        _                                                => None,
+    }
+}
+
+/// Returns the span of the destination of the link reference definition
+/// starting at `start`, if `line` is one.
+fn definition_url(line: &str, start: usize) -> Option<Range<usize>> {
+    let trimmed = line.trim_start_matches(' ');
+    let indent = line.len() - trimmed.len();
+    if indent > 3 || !trimmed.starts_with('[') {
+        return None;
+    }
+    let label_end = trimmed.find("]:")?;
+    let rest = &trimmed[label_end + 2..];
+    let url = rest.trim_start();
+    let url_start = start + indent + label_end + 2 + (rest.len() - url.len());
+    let url_len = url.find(char::is_whitespace).unwrap_or(url.len());
+    (url_len > 0).then(|| url_start..url_start + url_len)
+}
+
+/// Appends the spans of code spans, link destinations and autolinks in the
+/// prose `range` of `s` to `spans`.
+fn inline_spans(s: &str, range: Range<usize>, spans: &mut Vec<Range<usize>>) {
+    let bytes = &s.as_bytes()[..range.end];
+    let run = |i: usize| bytes[i..].iter().take_while(|&&b| b == b'`').count();
+    let mut i = range.start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'`' => {
+                let len = run(i);
+                let mut j = i + len;
+                while j < bytes.len() && !(bytes[j] == b'`' && run(j) == len) {
+                    j += if bytes[j] == b'`' { run(j) } else { 1 };
+                }
+                if j < bytes.len() {
+                    spans.push(i..j + len);
+                    i = j + len;
+                } else {
+                    i += len;
+                }
+            }
+            b']' if bytes.get(i + 1) == Some(&b'(') => {
+                let mut depth = 0;
+                let mut j = i + 2;
+                while j < bytes.len() && bytes[j] != b'\n' && !(bytes[j] == b')' && depth == 0) {
+                    match bytes[j] {
+                        b'(' => depth += 1,
+                        b')' => depth -= 1,
+                        _    => {}
+                    }
+                    j += 1;
+                }
+                if j < bytes.len() && bytes[j] == b')' {
+                    spans.push(i + 2..j);
+                }
+                i = j;
+            }
+            b'<' => {
+                let end = bytes[i..].iter()
+                    .position(|&b| b == b'>' || b.is_ascii_whitespace())
+                    .map(|len| i + len);
+                match end {
+                    Some(end) if bytes[end] == b'>'
+                        && bytes[i + 1..end].iter().any(|&b| b == b':' || b == b'@') => {
+                        spans.push(i + 1..end);
+                        i = end;
+                    }
+                    _ => i += 1,
+                }
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+/// Converts the prose of the Markdown document `s` according to `policy`,
+/// leaving fenced code blocks, code spans, link destinations, autolinks and
+/// link reference definitions unchanged.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::WidthPolicy;
+///
+/// let md = "ＡＢ `ＣＤ` [ﾘﾝｸ](https://example.com/ｶﾅ)\n```\nＥＦ\n```\n";
+/// assert_eq!(unicode_hfwidth::convert_markdown(md, &WidthPolicy::default()),
+///            "AB `ＣＤ` [リンク](https://example.com/ｶﾅ)\n```\nＥＦ\n```\n");
+/// # }
+/// ```
+pub fn convert_markdown<S: AsRef<str>>(s: S, policy: &WidthPolicy) -> String {
+    let s = s.as_ref();
+    let mut spans = Vec::new();
+    let mut fence = None;
+    let mut prose_start = 0;
+    let mut offset = 0;
+    for line in s.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        match (fence, fence_marker(line)) {
+            (Some((ch, len)), Some((close, close_len)))
+                if close == ch && close_len >= len
+                    && line.trim_start_matches(' ')[close_len..].trim().is_empty() => {
+                fence = None;
+            }
+            (Some(_), _) => {}
+            (None, Some(marker)) => {
+                inline_spans(s, prose_start..start, &mut spans);
+                fence = Some(marker);
+            }
+            (None, None) => {
+                spans.extend(definition_url(line, start));
+                continue;
+            }
+        }
+        spans.push(start..offset);
+        prose_start = offset;
+    }
+    if fence.is_none() {
+        inline_spans(s, prose_start..s.len(), &mut spans);
+    }
+    spans.sort_by_key(|span| span.start);
+    policy.convert_except(s, &spans)
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_convert_markdown() {
+    let policy = WidthPolicy::default();
+    let md = "# ﾀｲﾄﾙ\n\n\
+              ``ｺｰﾄﾞ`ｽﾊﾟﾝ`` ｶﾅ \\`ｶﾅ` <https://ｴ.example> <ｶﾅ>\n\
+              [ﾘﾝｸ]: https://example.com/ｶﾅ \"ﾀｲﾄﾙ\"\n\
+              ~~~~ ﾃｷｽﾄ\nＡ\n~~~\nＢ\n~~~~\nＣ\n";
+    assert_eq!(convert_markdown(md, &policy),
+               "# タイトル\n\n\
+                ``ｺｰﾄﾞ`ｽﾊﾟﾝ`` カナ \\`カナ` <https://ｴ.example> <カナ>\n\
+                [リンク]: https://example.com/ｶﾅ \"タイトル\"\n\
+                ~~~~ ﾃｷｽﾄ\nＡ\n~~~\nＢ\n~~~~\nC\n");
+    assert_eq!(convert_markdown("```\nＡ", &policy), "```\nＡ");
+}
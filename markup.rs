@@ -0,0 +1,136 @@
+//! Conversion of text in HTML and XML documents.
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use super::WidthPolicy;
+
+/// Returns the length of the character or entity reference at the start of
+/// `s`, which starts with `&`, or 0 if it is a bare ampersand.
+fn reference_len(s: &str) -> usize {
+    let name = s[1..].find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '#'));
+    match name {
+        Some(len) if len > 0 && s[1 + len..].starts_with(';') => len + 2,
+        _                                                     => 0,
+    }
+}
+
+/// Converts the escaped text `raw`, copying references such as `&amp;` as
+/// they are and escaping `<`, `&` and `quote` where conversion produced them.
+fn convert_escaped(raw: &str, policy: &WidthPolicy, quote: Option<char>, out: &mut String) {
+    let mut rest = raw;
+    while !rest.is_empty() {
+        let end = rest.find('&').unwrap_or(rest.len());
+        for ch in policy.convert(&rest[..end]).chars() {
+            match ch {
+                '<'                       => out.push_str("&lt;"),
+                '&'                       => out.push_str("&amp;"),
+                '"' if quote == Some(ch)  => out.push_str("&quot;"),
+                '\'' if quote == Some(ch) => out.push_str("&#39;"),
+                _                         => out.push(ch),
+            }
+        }
+        rest = &rest[end..];
+        if !rest.is_empty() {
+            let len = reference_len(rest).max(1);
+            out.push_str(&rest[..len]);
+            rest = &rest[len..];
+        }
+    }
+}
+
+/// Copies the start tag `tag`, whose name is `name_len` bytes long, converting
+/// quoted attribute values.
+fn convert_tag(tag: &str, name_len: usize, policy: &WidthPolicy, out: &mut String) {
+    let mut rest = &tag[1 + name_len..];
+    out.push_str(&tag[..1 + name_len]);
+    while let Some(start) = rest.find(['"', '\'']) {
+        let quote = rest[start..].chars().next().unwrap();
+        let end = match rest[start + 1..].find(quote) {
+            Some(end) => start + 1 + end,
+            None      => break,
+        };
+        out.push_str(&rest[..start + 1]);
+        convert_escaped(&rest[start + 1..end], policy, Some(quote), out);
+        out.push(quote);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+}
+
+/// Converts the text of an HTML or XML document according to `policy`, and
+/// the values of quoted attributes if `attributes` is set.
+///
+/// Everything else is copied byte for byte: markup, entity and character
+/// references, comments, CDATA sections, and the content of `script` and
+/// `style` elements. Characters that conversion turns into markup
+/// characters, such as `＜` into `<`, are escaped. The document does not need
+/// to be well-formed, but must not contain a bare `<` in text.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::WidthPolicy;
+///
+/// let html = r#"<p title="ｶﾅ">ＡＢ&amp;ＣＤ＜</p>"#;
+/// let converted = unicode_hfwidth::convert_markup(html, &WidthPolicy::default(), true);
+/// assert_eq!(converted.unwrap(), r#"<p title="カナ">AB&amp;CD&lt;</p>"#);
+/// # }
+/// ```
+pub fn convert_markup<S: AsRef<str>>(input: S, policy: &WidthPolicy, attributes: bool)
+                                     -> Result<String, quick_xml::Error> {
+    let input = input.as_ref();
+    let mut reader = Reader::from_str(input);
+    reader.config_mut().check_end_names = false;
+    reader.config_mut().allow_unmatched_ends = true;
+    let mut out = String::with_capacity(input.len());
+    let mut raw_text: Option<Vec<u8>> = None;
+    let mut start = 0;
+    loop {
+        let event = reader.read_event()?;
+        let end = reader.buffer_position() as usize;
+        let span = &input[start..end];
+        match event {
+            Event::Eof => break,
+            Event::Text(_) if raw_text.is_none() => convert_escaped(span, policy, None, &mut out),
+            Event::Start(ref tag) | Event::Empty(ref tag) => {
+                let name = tag.name();
+                if matches!(event, Event::Start(_)) && raw_text.is_none()
+                    && (name.as_ref().eq_ignore_ascii_case(b"script")
+                        || name.as_ref().eq_ignore_ascii_case(b"style")) {
+                    raw_text = Some(name.as_ref().to_ascii_lowercase());
+                }
+                if attributes {
+                    convert_tag(span, name.as_ref().len(), policy, &mut out);
+                } else {
+                    out.push_str(span);
+                }
+            }
+            Event::End(ref tag) => {
+                if raw_text.as_deref()
+                    .is_some_and(|name| tag.name().as_ref().eq_ignore_ascii_case(name)) {
+                    raw_text = None;
+                }
+                out.push_str(span);
+            }
+            _ => out.push_str(span),
+        }
+        start = end;
+    }
+    out.push_str(&input[start..]);
+    Ok(out)
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_convert_markup() {
+    let policy = WidthPolicy::default();
+    let html = "<!-- ＡＢ --><div class='ｶﾅ' data-x=\"ｱ＂\">Ｔ＆Ｔ &#x41; ＆amp;\
+                <style>.ａ{}</style><![CDATA[ＡＢ]]><br/>ｶﾞ</div>";
+    assert_eq!(convert_markup(html, &policy, false).unwrap(),
+               "<!-- ＡＢ --><div class='ｶﾅ' data-x=\"ｱ＂\">T&amp;T &#x41; &amp;amp;\
+                <style>.ａ{}</style><![CDATA[ＡＢ]]><br/>ガ</div>");
+    assert_eq!(convert_markup(html, &policy, true).unwrap(),
+               "<!-- ＡＢ --><div class='カナ' data-x=\"ア&quot;\">T&amp;T &#x41; &amp;amp;\
+                <style>.ａ{}</style><![CDATA[ＡＢ]]><br/>ガ</div>");
+}
@@ -0,0 +1,65 @@
+//! Swift and Kotlin bindings.
+//!
+//! Enabled by the `mobile` feature, which exports the functions below with
+//! UniFFI. Build the library with
+//! `cargo rustc --release --lib --features mobile --crate-type cdylib` and
+//! generate bindings from it with `uniffi-bindgen generate --library`.
+
+use super::{WidthPolicy, WidthTarget};
+
+/// Targets per category for `convert`. Unset categories are converted to
+/// standard width.
+#[derive(uniffi::Record)]
+pub struct ConvertOptions {
+    pub ascii: Option<WidthTarget>,
+    pub kana: Option<WidthTarget>,
+    pub hangul: Option<WidthTarget>,
+    pub symbols: Option<WidthTarget>,
+    pub space: Option<WidthTarget>,
+}
+
+/// Converts `s` to standard width.
+#[uniffi::export]
+pub fn to_standard_width(s: &str) -> String {
+    WidthPolicy::default().convert(s)
+}
+
+/// Converts `s` to half-width wherever a half-width form exists.
+#[uniffi::export]
+pub fn to_halfwidth(s: &str) -> String {
+    WidthPolicy::builder().all(WidthTarget::Halfwidth).build().convert(s)
+}
+
+/// Converts `s` to full-width wherever a full-width form exists.
+#[uniffi::export]
+pub fn to_fullwidth(s: &str) -> String {
+    WidthPolicy::builder().all(WidthTarget::Fullwidth).build().convert(s)
+}
+
+/// Converts `s` according to `options`.
+#[uniffi::export]
+pub fn convert(s: &str, options: ConvertOptions) -> String {
+    let mut policy = WidthPolicy::builder();
+    if let Some(target) = options.ascii {
+        policy = policy.ascii(target);
+    }
+    if let Some(target) = options.kana {
+        policy = policy.kana(target);
+    }
+    if let Some(target) = options.hangul {
+        policy = policy.hangul(target);
+    }
+    if let Some(target) = options.symbols {
+        policy = policy.symbols(target);
+    }
+    if let Some(target) = options.space {
+        policy = policy.space(target);
+    }
+    policy.build().convert(s)
+}
+
+/// Returns the number of terminal columns `s` occupies.
+#[uniffi::export]
+pub fn str_width(s: &str) -> u64 {
+    super::str_width(s) as u64
+}
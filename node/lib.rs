@@ -0,0 +1,74 @@
+//! Node.js bindings for `unicode_hfwidth`.
+//!
+//! Build the Node-API addon with `cargo build --release -p unicode_hfwidth_node`
+//! and load the library renamed to `unicode_hfwidth.node`.
+
+use napi::{Error, Result};
+use napi_derive::napi;
+
+use unicode_hfwidth::{WidthPolicy, WidthTarget};
+
+/// Targets per category for `convert`. Each takes `"keep"`, `"standard"`,
+/// `"half"` or `"full"`, or `"halfwidth"` or `"fullwidth"`; unset categories
+/// are converted to standard width.
+#[napi(object)]
+pub struct ConvertOptions {
+    pub ascii: Option<String>,
+    pub kana: Option<String>,
+    pub hangul: Option<String>,
+    pub symbols: Option<String>,
+    pub space: Option<String>,
+}
+
+fn parse_target(name: &str, value: &str) -> Result<WidthTarget> {
+    value.parse()
+        .map_err(|_| Error::from_reason(format!("invalid value '{}' for {}", value, name)))
+}
+
+/// Converts `s` to standard width.
+#[napi(js_name = "toStandardWidth")]
+pub fn to_standard_width(s: String) -> String {
+    WidthPolicy::default().convert(&s)
+}
+
+/// Converts `s` to half-width wherever a half-width form exists.
+#[napi(js_name = "toHalfwidth")]
+pub fn to_halfwidth(s: String) -> String {
+    WidthPolicy::builder().all(WidthTarget::Halfwidth).build().convert(&s)
+}
+
+/// Converts `s` to full-width wherever a full-width form exists.
+#[napi(js_name = "toFullwidth")]
+pub fn to_fullwidth(s: String) -> String {
+    WidthPolicy::builder().all(WidthTarget::Fullwidth).build().convert(&s)
+}
+
+/// Converts `s` according to `options`.
+#[napi]
+pub fn convert(s: String, options: Option<ConvertOptions>) -> Result<String> {
+    let mut policy = WidthPolicy::builder();
+    if let Some(options) = options {
+        if let Some(value) = options.ascii {
+            policy = policy.ascii(parse_target("ascii", &value)?);
+        }
+        if let Some(value) = options.kana {
+            policy = policy.kana(parse_target("kana", &value)?);
+        }
+        if let Some(value) = options.hangul {
+            policy = policy.hangul(parse_target("hangul", &value)?);
+        }
+        if let Some(value) = options.symbols {
+            policy = policy.symbols(parse_target("symbols", &value)?);
+        }
+        if let Some(value) = options.space {
+            policy = policy.space(parse_target("space", &value)?);
+        }
+    }
+    Ok(policy.build().convert(&s))
+}
+
+/// Returns the number of terminal columns `s` occupies.
+#[napi(js_name = "strWidth")]
+pub fn str_width(s: String) -> u32 {
+    unicode_hfwidth::str_width(&s) as u32
+}
@@ -0,0 +1,96 @@
+//! Adapters composing width folding with the `unicode-normalization` crate.
+
+use std::str::Chars;
+
+use unicode_normalization::{Recompositions, UnicodeNormalization};
+
+use super::nfkc_decomposition;
+
+/// Iterator returned by `WidthNormalization::width_fold`.
+#[derive(Clone, Debug)]
+pub struct WidthFold<I> {
+    iter: I,
+    pending: Option<char>,
+}
+
+impl<I: Iterator<Item = char>> Iterator for WidthFold<I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        if let Some(ch) = self.pending.take() {
+            return Some(ch);
+        }
+        let ch = self.iter.next()?;
+        match nfkc_decomposition(ch) {
+            Some(decomposition) => {
+                self.pending = decomposition.get(1).copied();
+                Some(decomposition[0])
+            }
+            None => Some(ch),
+        }
+    }
+}
+
+/// Width folding methods for strings and character iterators, in the style of
+/// `unicode_normalization::UnicodeNormalization`, so they chain with its
+/// adapters in either order.
+///
+/// Requires the `unicode-normalization` feature.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin", feature = "unicode-normalization"))] {
+/// use unicode_hfwidth::WidthNormalization;
+/// use unicode_normalization::UnicodeNormalization;
+///
+/// assert_eq!("ｶﾞｲﾄﾞ ＡＢ".width_fold_nfc().collect::<String>(), "ガイド AB");
+/// assert_eq!("ﾊﾟ".nfd().width_fold().nfc().collect::<String>(), "パ");
+/// # }
+/// ```
+pub trait WidthNormalization<I: Iterator<Item = char>> {
+    /// Returns an iterator over the characters with the width decompositions
+    /// of `nfkc_decomposition`, leaving other characters unchanged. Voiced
+    /// sound marks and Hangul jamo stay decomposed.
+    fn width_fold(self) -> WidthFold<I>;
+
+    /// Returns an iterator over the characters folded with `width_fold` and
+    /// normalized to NFC, composing voiced kana and Hangul syllables like
+    /// `fold_width` does and the rest of the text canonically.
+    fn width_fold_nfc(self) -> Recompositions<WidthFold<I>>;
+}
+
+impl<I: Iterator<Item = char>> WidthNormalization<I> for I {
+    fn width_fold(self) -> WidthFold<I> {
+        WidthFold { iter: self, pending: None }
+    }
+
+    fn width_fold_nfc(self) -> Recompositions<WidthFold<I>> {
+        self.width_fold().nfc()
+    }
+}
+
+impl<'a> WidthNormalization<Chars<'a>> for &'a str {
+    fn width_fold(self) -> WidthFold<Chars<'a>> {
+        self.chars().width_fold()
+    }
+
+    fn width_fold_nfc(self) -> Recompositions<WidthFold<Chars<'a>>> {
+        self.chars().width_fold_nfc()
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_width_fold_nfc() {
+    use super::{fold_width, HalfwidthOf};
+
+    for s in ["ＡＢＣ　ｶﾞｷﾞ", "ﾡￂﾤ ￦￡", "ｶ\u{3099}", "ABC ￣", "e\u{301} ﾊﾟ"] {
+        let expected: String = fold_width(s).nfc().collect();
+        assert_eq!(s.width_fold_nfc().collect::<String>(), expected, "{}", s);
+        assert_eq!(s.nfd().width_fold_nfc().collect::<String>(), expected, "{}", s);
+    }
+    let nfkc: String = "ＡＢＣ　ｶﾞｷﾞ".nfkc().collect();
+    assert_eq!("ＡＢＣ　ｶﾞｷﾞ".width_fold_nfc().collect::<String>(), nfkc);
+    let half: String = HalfwidthOf("ガ".nfc()).into_iter().collect();
+    assert_eq!(half, "ｶﾞ");
+}
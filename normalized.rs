@@ -0,0 +1,101 @@
+//! Strings guaranteed to be in standard width.
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+
+use super::WidthPolicy;
+
+/// A string that is always in standard width.
+///
+/// The value is converted with the default `WidthPolicy` when the wrapper is
+/// created. With the `sqlx` feature, the wrapper can be bound to queries and
+/// read from rows wherever `String` can, and is converted in both directions,
+/// so values are width-canonical at the database boundary.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::WidthNormalized;
+///
+/// let name = WidthNormalized::new("ＡＢＣｶﾀｶﾅ".to_owned());
+/// assert_eq!(name.as_str(), "ABCカタカナ");
+/// assert_eq!(name.into_inner(), "ABCカタカナ");
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WidthNormalized<T = String>(pub(crate) T);
+
+impl<T: AsRef<str> + From<String>> WidthNormalized<T> {
+    /// Converts `value` to standard width and wraps it.
+    pub fn new(value: T) -> WidthNormalized<T> {
+        let s = value.as_ref();
+        if super::contains_nonstandard_width(s) {
+            WidthNormalized(T::from(WidthPolicy::default().convert(s)))
+        } else {
+            WidthNormalized(value)
+        }
+    }
+}
+
+impl<T> WidthNormalized<T> {
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: AsRef<str>> WidthNormalized<T> {
+    /// Returns the value as a string slice.
+    pub fn as_str(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+impl<T: AsRef<str> + From<String>> From<T> for WidthNormalized<T> {
+    fn from(value: T) -> WidthNormalized<T> {
+        WidthNormalized::new(value)
+    }
+}
+
+impl From<&str> for WidthNormalized<String> {
+    fn from(s: &str) -> WidthNormalized<String> {
+        WidthNormalized(WidthPolicy::default().convert(s))
+    }
+}
+
+impl<T: AsRef<str>> Deref for WidthNormalized<T> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<T: AsRef<str>> AsRef<str> for WidthNormalized<T> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<T: AsRef<str>> Borrow<str> for WidthNormalized<T> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<T: AsRef<str>> fmt::Display for WidthNormalized<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_width_normalized() {
+    let value = WidthNormalized::from("ｶﾞｲﾄﾞ １");
+    assert_eq!(value.as_str(), "ガイド 1");
+    assert_eq!(value, WidthNormalized::new("ガイド 1".to_owned()));
+    assert_eq!(value.len(), "ガイド 1".len());
+    assert_eq!(value.to_string(), "ガイド 1");
+}
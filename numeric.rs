@@ -0,0 +1,86 @@
+//! Helpers for numeric form fields such as phone numbers and postal codes.
+
+/// Returns the ASCII form of the full-width ASCII variant `ch`, without the
+/// conversion tables, which may be left out of the build.
+fn fullwidth_to_ascii(ch: char) -> char {
+    char::from_u32(ch as u32 - 0xfee0).unwrap()
+}
+
+/// Returns `true` if `ch` is commonly typed in place of an ASCII hyphen in
+/// numeric fields.
+fn is_hyphen_like(ch: char) -> bool {
+    matches!(ch, '-' | '－' | 'ー' | 'ｰ' | '‐' | '‑' | '‒' | '–' | '—' | '―' | '−')
+}
+
+/// Sanitizes a numeric form field such as a phone number or postal code.
+///
+/// Full-width digits are converted to ASCII, hyphen lookalikes (including `ー`
+/// and `－`) are converted to `-`, and leading and trailing whitespace,
+/// including ideographic spaces, is trimmed. All other characters are left
+/// untouched.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::sanitize_numeric_field("　０３ー１２３４－５６７８ "), "03-1234-5678");
+/// assert_eq!(unicode_hfwidth::sanitize_numeric_field("〒１００"), "〒100");
+/// ```
+pub fn sanitize_numeric_field<S: AsRef<str>>(s: S) -> String {
+    let s = s.as_ref();
+    s.trim()
+        .chars()
+        .map(|ch| match ch {
+            '０'..='９' => fullwidth_to_ascii(ch),
+            _ if is_hyphen_like(ch) => '-',
+            _ => ch,
+        })
+        .collect()
+}
+
+/// A full-width character found in a numeric field by `lint_numeric_field`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NumericDiagnostic {
+    /// Byte index of the character.
+    pub index: usize,
+    /// The offending character.
+    pub ch: char,
+    /// The suggested ASCII replacement.
+    pub replacement: char,
+}
+
+/// Scans a numeric field for full-width digits and signs (`＋`, `－`, `．` and
+/// `，`), returning a diagnostic with a suggested replacement for each.
+///
+/// Unlike `sanitize_numeric_field`, nothing is converted, so validation layers
+/// can choose between rejecting and fixing the input.
+///
+/// # Example
+/// ```rust
+/// let diagnostics = unicode_hfwidth::lint_numeric_field("1２-3");
+/// assert_eq!(diagnostics.len(), 1);
+/// assert_eq!(diagnostics[0].index, 1);
+/// assert_eq!(diagnostics[0].ch, '２');
+/// assert_eq!(diagnostics[0].replacement, '2');
+/// ```
+pub fn lint_numeric_field<S: AsRef<str>>(s: S) -> Vec<NumericDiagnostic> {
+    let s = s.as_ref();
+    s.char_indices()
+        .filter(|&(_, ch)| matches!(ch, '０'..='９' | '＋' | '－' | '．' | '，'))
+        .map(|(index, ch)| NumericDiagnostic { index, ch, replacement: fullwidth_to_ascii(ch) })
+        .collect()
+}
+
+#[test]
+fn test_sanitize_numeric_field() {
+    assert_eq!(sanitize_numeric_field("１５０－０００１"), "150-0001");
+    assert_eq!(sanitize_numeric_field("\u{3000}090ｰ1234ー5678\u{3000}"), "090-1234-5678");
+    assert_eq!(sanitize_numeric_field("１２ ３４"), "12 34");
+    assert_eq!(sanitize_numeric_field("ａｂｃ"), "ａｂｃ");
+}
+
+#[test]
+fn test_lint_numeric_field() {
+    let diagnostics = lint_numeric_field("－１,000．5");
+    let found: Vec<_> = diagnostics.iter().map(|d| (d.index, d.ch, d.replacement)).collect();
+    assert_eq!(found, [(0, '－', '-'), (3, '１', '1'), (10, '．', '.')]);
+    assert!(lint_numeric_field("03-1234-5678").is_empty());
+}
@@ -0,0 +1,366 @@
+//! Mapping of byte offsets between converted text and its original.
+
+use std::ops::Range;
+
+use super::pretokenize::width_pieces;
+use super::{Edit, WidthPolicy};
+
+/// Maps byte offsets in converted text back to the original text, and the
+/// other way around.
+///
+/// The map stores checkpoints where the two texts fall out of step, and
+/// offsets between checkpoints are interpolated. Where characters were
+/// removed, an offset maps to the end of the removed text as the start of a
+/// range, and to its start as the end of a range.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OffsetMap {
+    /// `(original, converted)` offset pairs, sorted by both.
+    checkpoints: Vec<(usize, usize)>,
+    original_len: usize,
+    len: usize,
+}
+
+impl Default for OffsetMap {
+    fn default() -> OffsetMap {
+        OffsetMap { checkpoints: vec![(0, 0)], original_len: 0, len: 0 }
+    }
+}
+
+impl OffsetMap {
+    /// Creates a map from its checkpoints, which start with `(0, 0)`.
+    pub(crate) fn from_checkpoints(checkpoints: Vec<(usize, usize)>, original_len: usize,
+                                   len: usize) -> OffsetMap {
+        OffsetMap { checkpoints, original_len, len }
+    }
+
+    /// Appends converted text of `len` bytes produced from `original`.
+    pub(crate) fn push(&mut self, original: Range<usize>, len: usize) {
+        let (o, n) = *self.checkpoints.last().unwrap();
+        let expected = o + (self.len - n);
+        if expected != original.start {
+            if n != self.len {
+                self.checkpoints.push((expected, self.len));
+            }
+            self.checkpoints.push((original.start, self.len));
+        }
+        self.len += len;
+        if original.len() != len {
+            self.checkpoints.push((original.end, self.len));
+        }
+        self.original_len = original.end;
+    }
+
+    /// Ends the map at the end of the original text of `original_len` bytes.
+    pub(crate) fn finish(&mut self, original_len: usize) {
+        let (o, n) = *self.checkpoints.last().unwrap();
+        let expected = o + (self.len - n);
+        if expected != original_len {
+            if n != self.len {
+                self.checkpoints.push((expected, self.len));
+            }
+            self.checkpoints.push((original_len, self.len));
+        }
+        self.original_len = original_len;
+    }
+
+    /// Interpolates `offset` from the last checkpoint before it.
+    fn interpolate(&self, index: usize, offset: usize) -> usize {
+        let (o, n) = self.checkpoints[index - 1];
+        let limit = self.checkpoints.get(index).map_or(self.original_len, |&(o, _)| o);
+        (o + (offset - n)).min(limit)
+    }
+
+    /// Returns the `(original, converted)` offset pairs the map interpolates
+    /// between, sorted by both offsets. The first pair is `(0, 0)`.
+    pub fn checkpoints(&self) -> &[(usize, usize)] {
+        &self.checkpoints
+    }
+
+    /// Returns the length of the original text.
+    pub fn original_len(&self) -> usize {
+        self.original_len
+    }
+
+    /// Returns the length of the converted text.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the converted text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the converted offset of the original `offset`. Offsets inside
+    /// a character that was composed with the next one map to the end of the
+    /// composed character.
+    pub fn to_converted(&self, offset: usize) -> usize {
+        let index = self.checkpoints.partition_point(|&(o, _)| o <= offset);
+        let (o, n) = self.checkpoints[index - 1];
+        let limit = self.checkpoints.get(index).map_or(self.len, |&(_, n)| n);
+        (n + (offset - o)).min(limit)
+    }
+
+    /// Returns the original offset of the converted `offset`, taken as the
+    /// start of a range.
+    pub fn to_original(&self, offset: usize) -> usize {
+        let lo = self.checkpoints.partition_point(|&(_, n)| n < offset);
+        let hi = self.checkpoints.partition_point(|&(_, n)| n <= offset);
+        if hi > lo { self.checkpoints[hi - 1].0 } else { self.interpolate(lo, offset) }
+    }
+
+    /// Returns the original byte range of the converted `range`.
+    pub fn original_range(&self, range: Range<usize>) -> Range<usize> {
+        let lo = self.checkpoints.partition_point(|&(_, n)| n < range.end);
+        let hi = self.checkpoints.partition_point(|&(_, n)| n <= range.end);
+        let end = if hi > lo { self.checkpoints[lo].0 } else { self.interpolate(lo, range.end) };
+        self.to_original(range.start).min(end)..end
+    }
+
+    /// Returns the stretches of the map as original ranges and the length of
+    /// the converted text they became, alternating between stretches in step
+    /// with the original and stretches where the lengths differ.
+    fn segments(&self) -> Vec<(Range<usize>, usize)> {
+        let ends = self.checkpoints[1..].iter().copied()
+            .chain(Some((self.original_len, self.len)));
+        let mut segments = Vec::new();
+        for (&(o, n), (end, len)) in self.checkpoints.iter().zip(ends) {
+            let step = (end - o).min(len - n);
+            if step > 0 {
+                segments.push((o..o + step, step));
+            }
+            if end - o != len - n {
+                segments.push((o + step..end, len - n - step));
+            }
+        }
+        segments
+    }
+
+    /// Returns the original byte ranges of the converted `ranges`, such as
+    /// the matches of a search in the converted text.
+    pub fn original_ranges<I: IntoIterator<Item = Range<usize>>>(&self, ranges: I)
+                                                                -> Vec<Range<usize>> {
+        ranges.into_iter().map(|range| self.original_range(range)).collect()
+    }
+}
+
+/// Maps `matches`, byte ranges found in `original` converted according to
+/// `policy`, back to byte ranges of `original`, for highlighting the matches
+/// in the text as it was entered.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// use unicode_hfwidth::WidthPolicy;
+///
+/// let original = "ﾃﾞｰﾀ and ＤＡＴＡ";
+/// let policy = WidthPolicy::default();
+/// let normalized = policy.convert(original);
+/// let matches = normalized.match_indices("DATA").map(|(i, m)| i..i + m.len());
+/// let found = unicode_hfwidth::remap_matches(original, &policy, matches);
+/// assert_eq!(found, [17..29]);
+/// assert_eq!(&original[found[0].clone()], "ＤＡＴＡ");
+/// # }
+/// ```
+pub fn remap_matches<I>(original: &str, policy: &WidthPolicy, matches: I) -> Vec<Range<usize>>
+    where I: IntoIterator<Item = Range<usize>> {
+    policy.convert_with_offsets(original).1.original_ranges(matches)
+}
+
+impl WidthPolicy {
+    /// Converts `s` according to the policy, like `convert`, and returns a
+    /// map between offsets in `s` and in the result.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(all(feature = "kana", feature = "latin"))] {
+    /// use unicode_hfwidth::WidthPolicy;
+    ///
+    /// let s = "ｶﾞｲﾄﾞ: ＡＢ";
+    /// let (converted, offsets) = WidthPolicy::default().convert_with_offsets(s);
+    /// assert_eq!(converted, "ガイド: AB");
+    /// let found = converted.find("AB").unwrap();
+    /// assert_eq!(&s[offsets.original_range(found..found + 2)], "ＡＢ");
+    /// assert_eq!(offsets.to_converted(s.find('Ａ').unwrap()), found);
+    /// # }
+    /// ```
+    pub fn convert_with_offsets<S: AsRef<str>>(&self, s: S) -> (String, OffsetMap) {
+        let s = s.as_ref();
+        let mut out = String::with_capacity(self.capacity_for(s));
+        let mut offsets = OffsetMap::default();
+        for (ch, range) in width_pieces(s, self) {
+            out.push(ch);
+            offsets.push(range, ch.len_utf8());
+        }
+        offsets.finish(s.len());
+        (out, offsets)
+    }
+
+    /// Applies `edit` to `original`, and updates `normalized` and `offsets`,
+    /// its conversion according to the policy and their offset map, by
+    /// converting only the text between the whitespace around the edit
+    /// instead of all of `original`. Returns the edit made to `normalized`,
+    /// for keeping other copies of it in step, such as an editor's buffer.
+    ///
+    /// Panics if the range of `edit` is out of bounds or not on character
+    /// boundaries of `original`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(all(feature = "kana", feature = "latin"))] {
+    /// use unicode_hfwidth::{Edit, WidthPolicy};
+    ///
+    /// let policy = WidthPolicy::default();
+    /// let mut original = "ｶﾞｲﾄﾞ ＡＢ ｶﾀﾛｸﾞ".to_owned();
+    /// let (mut normalized, mut offsets) = policy.convert_with_offsets(&original);
+    /// let edit = Edit { range: 19..22, replacement: "Ｃ".to_owned() };
+    /// let changed = policy.renormalize_edit(&mut original, &mut normalized, &mut offsets, &edit);
+    /// assert_eq!(original, "ｶﾞｲﾄﾞ ＡＣ ｶﾀﾛｸﾞ");
+    /// assert_eq!(normalized, "ガイド AC カタログ");
+    /// assert_eq!(changed, Edit { range: 10..12, replacement: "AC".to_owned() });
+    /// assert_eq!(offsets, policy.convert_with_offsets(&original).1);
+    /// # }
+    /// ```
+    pub fn renormalize_edit(&self, original: &mut String, normalized: &mut String,
+                            offsets: &mut OffsetMap, edit: &Edit) -> Edit {
+        /* Conversion never looks across whitespace, which is converted on its own */
+        let start = original[..edit.range.start].char_indices()
+            .rfind(|&(_, ch)| ch.is_whitespace())
+            .map_or(0, |(i, ch)| i + ch.len_utf8());
+        let end = original[edit.range.end..].find(char::is_whitespace)
+            .map_or(original.len(), |i| edit.range.end + i);
+        original.replace_range(edit.range.clone(), &edit.replacement);
+        let new_end = end - edit.range.len() + edit.replacement.len();
+
+        let segments = offsets.segments();
+        let mut map = OffsetMap::default();
+        for (range, len) in clip(&segments, 0..start) {
+            map.push(range, len);
+        }
+        let converted_start = map.len();
+        let mut replacement = String::new();
+        for (ch, range) in width_pieces(&original[start..new_end], self) {
+            replacement.push(ch);
+            map.push(start + range.start..start + range.end, ch.len_utf8());
+        }
+        let converted_end = converted_start
+            + clip(&segments, start..end).map(|(_, len)| len).sum::<usize>();
+        for (range, len) in clip(&segments, end..usize::MAX) {
+            map.push(range.start + new_end - end..range.end + new_end - end, len);
+        }
+        map.finish(original.len());
+        normalized.replace_range(converted_start..converted_end, &replacement);
+        *offsets = map;
+        Edit { range: converted_start..converted_end, replacement }
+    }
+}
+
+/// Returns the parts of `segments` from `OffsetMap::segments` within the
+/// original `window`. Segments of removed text belong to the text before.
+fn clip(segments: &[(Range<usize>, usize)], window: Range<usize>)
+        -> impl Iterator<Item = (Range<usize>, usize)> + '_ {
+    segments.iter().filter_map(move |(range, len)| {
+        let inside = if range.is_empty() {
+            window.start < range.start && range.start <= window.end
+        } else {
+            range.end > window.start && range.start < window.end
+        };
+        if !inside || range.is_empty() || range.len() != *len {
+            return Some((range.clone(), *len)).filter(|_| inside);
+        }
+        /* Only stretches in step cross the boundaries of a window */
+        let clipped = range.start.max(window.start)..range.end.min(window.end);
+        Some((clipped.clone(), clipped.len()))
+    })
+}
+
+#[test]
+fn test_offset_map() {
+    /* "aｶﾞ~b" becomes "aガb", removing the tilde */
+    let mut map = OffsetMap::default();
+    map.push(0..1, 1);
+    map.push(1..7, 3);
+    map.push(8..9, 1);
+    map.finish(9);
+    assert_eq!(map.checkpoints, [(0, 0), (7, 4), (8, 4)]);
+    assert_eq!(map.to_original(0), 0);
+    assert_eq!(map.to_original(1), 1);
+    assert_eq!(map.to_original(4), 8);
+    assert_eq!(map.original_range(1..4), 1..7);
+    assert_eq!(map.original_range(4..5), 8..9);
+    assert_eq!(map.original_range(0..5), 0..9);
+    assert_eq!(map.to_converted(7), 4);
+    assert_eq!(map.to_converted(8), 4);
+    assert_eq!(map.to_converted(9), 5);
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_convert_with_offsets() {
+    let s = "aＡ\u{ff76}\u{ff9e}b\u{3000}";
+    let (converted, offsets) = WidthPolicy::default().convert_with_offsets(s);
+    assert_eq!(converted, WidthPolicy::default().convert(s));
+    assert_eq!(offsets.checkpoints(), [(0, 0), (4, 2), (10, 5)]);
+    assert_eq!((offsets.original_len(), offsets.len()), (s.len(), converted.len()));
+    for (i, _) in converted.char_indices() {
+        let original = offsets.to_original(i);
+        assert!(s.is_char_boundary(original));
+        assert_eq!(offsets.to_converted(original), i);
+    }
+}
+
+#[cfg(feature = "kana")]
+#[test]
+fn test_offsets_expansion() {
+    let policy = WidthPolicy::builder().signs(super::SignMapping::Ascii).build();
+    let s = "a￩ｶﾞ";
+    let (converted, offsets) = policy.convert_with_offsets(s);
+    assert_eq!(converted, policy.convert(s));
+    assert_eq!(converted, "a<-ガ");
+    assert_eq!(offsets.original_range(1..3), 1..4);
+    assert_eq!(offsets.original_range(3..6), 4..10);
+    assert_eq!(super::diff_normalize(s, &policy)[0].replacement, "<-ガ");
+}
+
+#[cfg(feature = "kana")]
+#[test]
+fn test_remap_matches() {
+    let original = "ｶﾞｶﾞ ｶﾞｯｶﾘ";
+    let policy = WidthPolicy::default();
+    let normalized = policy.convert(original);
+    let matches: Vec<_> = normalized.match_indices("ガ").map(|(i, m)| i..i + m.len()).collect();
+    let found = remap_matches(original, &policy, matches);
+    let texts: Vec<_> = found.into_iter().map(|range| &original[range]).collect();
+    assert_eq!(texts, ["ｶﾞ", "ｶﾞ", "ｶﾞ"]);
+}
+
+#[test]
+fn test_renormalize_edit() {
+    let policies = [
+        WidthPolicy::default(),
+        WidthPolicy::from(super::Preset::SearchFold),
+        WidthPolicy::from(super::Preset::LegacyBank),
+    ];
+    let s = "ｶﾞｲﾄﾞ ｰ1 ﾊﾝｸﾞﾙ ﾡﾢ　'ＡＢ' ￩x\n\"ｶﾀ\"";
+    for policy in &policies {
+        for (start, _) in s.char_indices() {
+            for (end, _) in s[start..].char_indices().take(3) {
+                for replacement in ["", "ﾞ", " ", "ｰ2ＣＤ"] {
+                    let mut original = s.to_owned();
+                    let (mut normalized, mut offsets) = policy.convert_with_offsets(s);
+                    let edit = Edit { range: start..start + end, replacement: replacement.into() };
+                    let changed = policy.renormalize_edit(&mut original, &mut normalized,
+                                                          &mut offsets, &edit);
+                    let mut expected = s.to_owned();
+                    expected.replace_range(edit.range.clone(), replacement);
+                    assert_eq!(original, expected);
+                    assert_eq!((normalized.clone(), offsets),
+                               policy.convert_with_offsets(&expected), "{:?}", edit);
+                    let mut patched = policy.convert(s);
+                    patched.replace_range(changed.range, &changed.replacement);
+                    assert_eq!(patched, normalized);
+                }
+            }
+        }
+    }
+}
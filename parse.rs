@@ -0,0 +1,84 @@
+//! Width-lenient parsing of `FromStr` types.
+
+use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
+
+use super::WidthPolicy;
+
+/// Parsing that folds character widths before delegating to `FromStr`.
+///
+/// Implemented for every `FromStr` type.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// use unicode_hfwidth::FromStrWidth;
+///
+/// assert_eq!(u32::from_str_width("１２３"), Ok(123));
+/// assert_eq!(f64::from_str_width("－１．５"), Ok(-1.5));
+/// # }
+/// ```
+pub trait FromStrWidth: FromStr {
+    /// Parses `s` after converting it to standard width.
+    fn from_str_width(s: &str) -> Result<Self, Self::Err>;
+}
+
+impl<T: FromStr> FromStrWidth for T {
+    fn from_str_width(s: &str) -> Result<T, T::Err> {
+        WidthPolicy::default().convert(s).parse()
+    }
+}
+
+/// Wrapper whose `FromStr` implementation accepts non-standard width input.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// use std::str::FromStr;
+/// use unicode_hfwidth::WidthLenient;
+///
+/// assert_eq!(WidthLenient::<u32>::from_str("１２３").unwrap().into_inner(), 123);
+/// let WidthLenient(value): WidthLenient<i64> = "－４２".parse().unwrap();
+/// assert_eq!(value, -42);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WidthLenient<T>(pub T);
+
+impl<T> WidthLenient<T> {
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: FromStr> FromStr for WidthLenient<T> {
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<WidthLenient<T>, T::Err> {
+        T::from_str_width(s).map(WidthLenient)
+    }
+}
+
+impl<T> Deref for WidthLenient<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for WidthLenient<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "latin")]
+#[test]
+fn test_width_lenient() {
+    assert_eq!("１２３".parse::<WidthLenient<u8>>().map(WidthLenient::into_inner), Ok(123));
+    assert_eq!("123".parse::<WidthLenient<u8>>().map(WidthLenient::into_inner), Ok(123));
+    assert!("１２３４".parse::<WidthLenient<u8>>().is_err());
+    assert_eq!(bool::from_str_width("ｔｒｕｅ"), Ok(true));
+}
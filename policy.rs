@@ -0,0 +1,1278 @@
+//! Configurable string conversion.
+
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+use std::iter;
+use std::ops::Range;
+use std::str::FromStr;
+
+use super::converted::Converted;
+use super::fold::{compose_hangul, conjoining_jamo, final_form, is_vowel, split_final};
+use super::trace::traced;
+use super::{compose_kana, converted_len_utf8, is_fullwidth_digit, pair_category, to_fullwidth,
+            to_halfwidth, to_standard_width, FormsCategory, WidthForm};
+
+/// The form characters of a category are converted to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "mobile", derive(uniffi::Enum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+           serde(rename_all = "lowercase"))]
+pub enum WidthTarget {
+    /// Leave characters as they are.
+    Keep,
+    /// Convert characters to their standard-width form.
+    #[default]
+    Standard,
+    /// Convert characters to their half-width form.
+    #[cfg_attr(feature = "serde", serde(alias = "half"))]
+    Halfwidth,
+    /// Convert characters to their full-width form.
+    #[cfg_attr(feature = "serde", serde(alias = "full"))]
+    Fullwidth,
+}
+
+impl WidthTarget {
+    fn apply(self, ch: char) -> char {
+        match self {
+            WidthTarget::Keep      => None,
+            WidthTarget::Standard  => to_standard_width(ch),
+            WidthTarget::Halfwidth => to_halfwidth(ch),
+            WidthTarget::Fullwidth => to_fullwidth(ch),
+        }.unwrap_or(ch)
+    }
+}
+
+impl FromStr for WidthTarget {
+    type Err = UnknownTarget;
+
+    /// Parses the names `WidthTarget` is serialized with: `keep`, `standard`,
+    /// `halfwidth` or `half`, and `fullwidth` or `full`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use unicode_hfwidth::WidthTarget;
+    ///
+    /// assert_eq!("half".parse(), Ok(WidthTarget::Halfwidth));
+    /// assert_eq!("fullwidth".parse(), Ok(WidthTarget::Fullwidth));
+    /// assert!("wide".parse::<WidthTarget>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<WidthTarget, UnknownTarget> {
+        match s {
+            "keep"               => Ok(WidthTarget::Keep),
+            "standard"           => Ok(WidthTarget::Standard),
+            "halfwidth" | "half" => Ok(WidthTarget::Halfwidth),
+            "fullwidth" | "full" => Ok(WidthTarget::Fullwidth),
+            _                    => Err(UnknownTarget(s.to_owned())),
+        }
+    }
+}
+
+/// The error returned when parsing a `WidthTarget` from an unknown name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownTarget(pub String);
+
+impl fmt::Display for UnknownTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown width target {:?}", self.0)
+    }
+}
+
+impl Error for UnknownTarget {}
+
+/// The currency and related symbols covered by a `CurrencyPolicy`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+           serde(rename_all = "snake_case"))]
+pub enum CurrencySymbol {
+    /// `￥` and `¥`.
+    Yen,
+    /// `￦` and `₩`.
+    Won,
+    /// `￠` and `¢`.
+    Cent,
+    /// `￡` and `£`.
+    Pound,
+    /// `￤` and `¦`. Not a currency symbol, but treated alike by the same file
+    /// formats.
+    BrokenBar,
+}
+
+impl CurrencySymbol {
+    /// All covered symbols.
+    pub const ALL: [CurrencySymbol; 5] = [
+        CurrencySymbol::Yen,
+        CurrencySymbol::Won,
+        CurrencySymbol::Cent,
+        CurrencySymbol::Pound,
+        CurrencySymbol::BrokenBar,
+    ];
+
+    /// Returns the symbol `ch` is a form of, if any.
+    pub fn from_char(ch: char) -> Option<CurrencySymbol> {
+        match ch {
+            '￥' | '¥' => Some(CurrencySymbol::Yen),
+            '￦' | '₩' => Some(CurrencySymbol::Won),
+            '￠' | '¢' => Some(CurrencySymbol::Cent),
+            '￡' | '£' => Some(CurrencySymbol::Pound),
+            '￤' | '¦' => Some(CurrencySymbol::BrokenBar),
+            _ => None,
+        }
+    }
+
+    /// Returns the standard sign, e.g. `¥` for `Yen`.
+    pub fn sign(self) -> char {
+        match self {
+            CurrencySymbol::Yen       => '¥',
+            CurrencySymbol::Won       => '₩',
+            CurrencySymbol::Cent      => '¢',
+            CurrencySymbol::Pound     => '£',
+            CurrencySymbol::BrokenBar => '¦',
+        }
+    }
+
+    /// Returns the ASCII substitute, e.g. `\` for `Yen` as in JIS X 0201.
+    pub fn ascii(self) -> char {
+        match self {
+            CurrencySymbol::Yen       => '\\',
+            CurrencySymbol::Won       => 'W',
+            CurrencySymbol::Cent      => 'c',
+            CurrencySymbol::Pound     => 'L',
+            CurrencySymbol::BrokenBar => '|',
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// How a currency symbol is converted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+           serde(rename_all = "lowercase"))]
+pub enum CurrencyMapping {
+    /// Leave the symbol as it is.
+    Keep,
+    /// Map both forms to the standard sign, e.g. `￥` to `¥`.
+    Sign,
+    /// Map both forms to an ASCII substitute, e.g. `￥` to `\`.
+    Ascii,
+}
+
+/// Per-symbol conversion choices for `￥`, `￦`, `￠`, `￡` and `￤`.
+///
+/// Symbols without a choice follow the `FormsCategory::FullwidthSymbols`
+/// target of the `WidthPolicy`.
+///
+/// # Example
+/// ```rust
+/// use unicode_hfwidth::{CurrencyMapping, CurrencyPolicy, CurrencySymbol};
+///
+/// let policy = CurrencyPolicy::new().set(CurrencySymbol::Yen, CurrencyMapping::Ascii);
+/// assert_eq!(policy.apply('￥'), Some('\\'));
+/// assert_eq!(policy.apply('￡'), None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct CurrencyPolicy {
+    mappings: [Option<CurrencyMapping>; 5],
+}
+
+impl CurrencyPolicy {
+    /// Creates a policy with no choices made.
+    pub fn new() -> CurrencyPolicy {
+        CurrencyPolicy::default()
+    }
+
+    /// Creates a policy applying `mapping` to every symbol.
+    pub fn all(mapping: CurrencyMapping) -> CurrencyPolicy {
+        CurrencyPolicy { mappings: [Some(mapping); 5] }
+    }
+
+    /// Sets the mapping for `symbol`.
+    pub fn set(mut self, symbol: CurrencySymbol, mapping: CurrencyMapping) -> CurrencyPolicy {
+        self.mappings[symbol.index()] = Some(mapping);
+        self
+    }
+
+    /// Returns the mapping chosen for `symbol`, if any.
+    pub fn get(&self, symbol: CurrencySymbol) -> Option<CurrencyMapping> {
+        self.mappings[symbol.index()]
+    }
+
+    /// Returns the conversion of `ch`. If `ch` is not a covered symbol or no
+    /// choice was made for it, returns `None`.
+    pub fn apply(&self, ch: char) -> Option<char> {
+        let symbol = CurrencySymbol::from_char(ch)?;
+        match self.get(symbol)? {
+            CurrencyMapping::Keep  => Some(ch),
+            CurrencyMapping::Sign  => Some(symbol.sign()),
+            CurrencyMapping::Ascii => Some(symbol.ascii()),
+        }
+    }
+}
+
+/// How the half-width forms of signs, U+FFE8 to U+FFEE such as `￩`, are
+/// converted. Their standard counterparts, such as `←`, are left to the
+/// `FormsCategory::HalfwidthSymbols` target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+           serde(rename_all = "lowercase"))]
+pub enum SignMapping {
+    /// Leave the signs as they are.
+    Keep,
+    /// Map them to ASCII stand-ins: `|` for bars, `<-`, `^`, `->` and
+    /// `v` for arrows, `#` for squares and `o` for circles.
+    Ascii,
+}
+
+/// The jamo half-width Hangul is converted to when its target is
+/// `WidthTarget::Standard` or `WidthTarget::Fullwidth`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+           serde(rename_all = "lowercase"))]
+pub enum JamoForm {
+    /// Hangul compatibility jamo, U+3130 to U+318F, such as `ㄱ`.
+    #[default]
+    Compatibility,
+    /// Conjoining jamo, U+1100 to U+11FF, as NFKC decomposes half-width
+    /// Hangul to. Consonants that cannot start a syllable, such as `ﾣ`,
+    /// become final consonants.
+    Conjoining,
+}
+
+/// Which character the wave dash `〜` and the full-width tilde `～` are both
+/// converted to. Decoders of Japanese encodings disagree on which of the two
+/// one character is, so text from different systems has either.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+           serde(rename_all = "snake_case"))]
+pub enum WaveDashMapping {
+    /// The wave dash `〜`, U+301C, as JIS-based decoders produce.
+    WaveDash,
+    /// The full-width tilde `～`, U+FF5E, as Windows code page 932 produces.
+    FullwidthTilde,
+}
+
+impl WaveDashMapping {
+    /// Returns the character both are converted to.
+    pub fn char(self) -> char {
+        match self {
+            WaveDashMapping::WaveDash       => '\u{301c}',
+            WaveDashMapping::FullwidthTilde => '\u{ff5e}',
+        }
+    }
+}
+
+/// The character minus signs and hyphens typed in place of each other are
+/// converted to, which depends on the field: phone numbers and postal codes
+/// want hyphens, and signed amounts minus signs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+           serde(rename_all = "snake_case"))]
+pub enum MinusMapping {
+    /// The ASCII hyphen-minus `-`.
+    HyphenMinus,
+    /// The minus sign `−`, U+2212.
+    MinusSign,
+}
+
+impl MinusMapping {
+    /// Returns the character minus signs and hyphens are converted to.
+    pub fn char(self) -> char {
+        match self {
+            MinusMapping::HyphenMinus => '-',
+            MinusMapping::MinusSign   => '\u{2212}',
+        }
+    }
+}
+
+/// How the katakana middle dot `・` and its half-width form `･`, which
+/// separate the parts of foreign names, are converted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+           serde(rename_all = "lowercase"))]
+pub enum MiddleDotMapping {
+    /// Leave both forms as they are.
+    Keep,
+    /// Convert both to the half-width `･`.
+    Halfwidth,
+    /// Convert both to the full-width `・`.
+    Fullwidth,
+    /// Replace both with `/`.
+    Slash,
+    /// Replace both with `-`.
+    Hyphen,
+}
+
+/// The quotation marks the full-width quotation mark `＂` and apostrophe `＇`
+/// become when converted to half width.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+           serde(rename_all = "lowercase"))]
+pub enum QuoteMapping {
+    /// The ASCII quotation mark `"` and apostrophe `'`.
+    #[default]
+    Ascii,
+    /// Curly quotation marks: `“` and `‘` where a quotation opens, at the
+    /// start or after a space or an opening bracket, and `”` and `’`
+    /// elsewhere, which makes apostrophes within words `’`.
+    Curly,
+}
+
+impl QuoteMapping {
+    /// Returns the quotation mark the half-width `quote` becomes after
+    /// `prev`, the character before it, if any.
+    fn apply(self, quote: char, prev: Option<char>) -> char {
+        let opening = match prev {
+            Some(prev) => prev.is_whitespace() || "([{<（［｛「『“‘".contains(prev),
+            None       => true,
+        };
+        match (self, quote, opening) {
+            (QuoteMapping::Curly, '"', true)   => '“',
+            (QuoteMapping::Curly, '"', false)  => '”',
+            (QuoteMapping::Curly, '\'', true)  => '‘',
+            (QuoteMapping::Curly, '\'', false) => '’',
+            _                                  => quote,
+        }
+    }
+}
+
+/// Returns `true` if `ch` is a prolonged sound mark, which IMEs produce for
+/// a hyphen typed in Japanese input mode.
+pub(crate) fn is_prolonged_mark(ch: char) -> bool {
+    matches!(ch, 'ー' | 'ｰ')
+}
+
+/// Returns `true` if `ch` is kana, which a prolonged sound mark can follow.
+pub(crate) fn is_kana(ch: char) -> bool {
+    matches!(ch, '\u{3041}'..='\u{30ff}' | '\u{ff66}'..='\u{ff9f}')
+}
+
+/// Returns `true` if `ch` is an ASCII or full-width digit.
+pub(crate) fn is_digit(ch: char) -> bool {
+    ch.is_ascii_digit() || is_fullwidth_digit(ch)
+}
+
+/// Returns the ASCII stand-in for a half-width form of sign. Standard-width
+/// arrows and shapes are ordinary text, and have none.
+fn sign_ascii(ch: char) -> Option<&'static str> {
+    match ch {
+        '\u{ffe8}' => Some("|"),
+        '\u{ffe9}' => Some("<-"),
+        '\u{ffea}' => Some("^"),
+        '\u{ffeb}' => Some("->"),
+        '\u{ffec}' => Some("v"),
+        '\u{ffed}' => Some("#"),
+        '\u{ffee}' => Some("o"),
+        _           => None,
+    }
+}
+
+/// Returns the ASCII approximation of the CJK punctuation `ch`, in either
+/// width.
+fn ascii_approximation(ch: char) -> Option<char> {
+    match ch {
+        '。' | '｡'               => Some('.'),
+        '、' | '､'               => Some(','),
+        '「' | '」' | '｢' | '｣'
+            | '『' | '』'        => Some('"'),
+        '・' | '･'               => Some('/'),
+        '【' | '〔'              => Some('['),
+        '】' | '〕'              => Some(']'),
+        '〜'                     => Some('~'),
+        _                        => None,
+    }
+}
+
+/// Describes how each kind of character is converted by the string converter.
+///
+/// The default policy converts everything to its standard-width form.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// use unicode_hfwidth::{FormsCategory, WidthPolicy, WidthTarget};
+///
+/// let policy = WidthPolicy::builder()
+///     .category(FormsCategory::HalfwidthKatakana, WidthTarget::Keep)
+///     .build();
+/// assert_eq!(policy.convert("ＡＢＣｶﾀｶﾅ"), "ABCｶﾀｶﾅ");
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct WidthPolicy {
+    targets: [WidthTarget; 7],
+    space: WidthTarget,
+    currency: CurrencyPolicy,
+    ascii_punctuation: bool,
+    signs: Option<SignMapping>,
+    jamo: JamoForm,
+    compose_hangul: bool,
+    wave_dash: Option<WaveDashMapping>,
+    minus: Option<MinusMapping>,
+    quotes: QuoteMapping,
+    middle_dot: Option<MiddleDotMapping>,
+    contextual_prolonged_mark: bool,
+}
+
+impl WidthPolicy {
+    /// Creates a policy converting everything to its standard-width form.
+    pub fn new() -> WidthPolicy {
+        WidthPolicy::default()
+    }
+
+    /// Returns a builder starting from the default policy.
+    pub fn builder() -> WidthPolicyBuilder {
+        WidthPolicy::default().into_builder()
+    }
+
+    /// Returns a builder starting from the policy.
+    pub(crate) fn into_builder(self) -> WidthPolicyBuilder {
+        WidthPolicyBuilder { policy: self }
+    }
+
+    /// Returns the target for characters of `category`.
+    pub fn target(&self, category: FormsCategory) -> WidthTarget {
+        self.targets[category as usize]
+    }
+
+    /// Returns the target for ASCII and ideographic spaces.
+    pub fn space(&self) -> WidthTarget {
+        self.space
+    }
+
+    /// Returns the currency symbol policy.
+    pub fn currency(&self) -> &CurrencyPolicy {
+        &self.currency
+    }
+
+    /// Returns `true` if CJK punctuation is approximated with ASCII.
+    pub fn ascii_punctuation(&self) -> bool {
+        self.ascii_punctuation
+    }
+
+    /// Returns the mapping chosen for forms of signs, if any. Without one,
+    /// they follow the `FormsCategory::HalfwidthSymbols` target.
+    pub fn signs(&self) -> Option<SignMapping> {
+        self.signs
+    }
+
+    /// Returns the jamo half-width Hangul is converted to.
+    pub fn jamo(&self) -> JamoForm {
+        self.jamo
+    }
+
+    /// Returns `true` if jamo are composed into Hangul syllables.
+    pub fn compose_hangul(&self) -> bool {
+        self.compose_hangul
+    }
+
+    /// Returns the character wave dashes and full-width tildes are unified
+    /// to, if any. Without one, they are converted separately.
+    pub fn wave_dash(&self) -> Option<WaveDashMapping> {
+        self.wave_dash
+    }
+
+    /// Returns the character minus signs and hyphens are folded to, if any.
+    pub fn minus(&self) -> Option<MinusMapping> {
+        self.minus
+    }
+
+    /// Returns the quotation marks full-width quotation marks become.
+    pub fn quotes(&self) -> QuoteMapping {
+        self.quotes
+    }
+
+    /// Returns the mapping chosen for katakana middle dots, if any. Without
+    /// one, they follow the `FormsCategory::HalfwidthKatakana` target.
+    pub fn middle_dot(&self) -> Option<MiddleDotMapping> {
+        self.middle_dot
+    }
+
+    /// Returns `true` if prolonged sound marks not following kana become
+    /// hyphens.
+    pub fn contextual_prolonged_mark(&self) -> bool {
+        self.contextual_prolonged_mark
+    }
+
+    /// Returns `converted`, the conversion of `ch`, with the conversions
+    /// depending on the character before applied: quotation marks, and
+    /// prolonged sound marks used as hyphens. `prev` is the converted
+    /// character before `ch`.
+    pub(crate) fn convert_after(&self, ch: char, converted: char, prev: Option<char>) -> char {
+        match ch {
+            '＂' | '＇' => self.quotes.apply(converted, prev),
+            'ー' | 'ｰ' if self.contextual_prolonged_mark && !prev.is_some_and(is_kana) => {
+                self.minus.map_or('-', MinusMapping::char)
+            }
+            _ => converted,
+        }
+    }
+
+    /// Returns the conversion of `ch` if the policy replaces it with more
+    /// than one character.
+    pub(crate) fn expansion(&self, ch: char) -> Option<&'static str> {
+        match self.signs {
+            Some(SignMapping::Ascii) => sign_ascii(ch).filter(|ascii| ascii.len() > 1),
+            _                        => None,
+        }
+    }
+
+    /// Converts a single character according to the policy. Arrows the policy
+    /// replaces with two characters, such as `￩` becoming `<-`, are converted
+    /// to their arrow head.
+    pub fn convert_char(&self, ch: char) -> char {
+        if self.ascii_punctuation {
+            if let Some(ascii) = ascii_approximation(ch) {
+                return ascii;
+            }
+        }
+        if let Some(mapping) = self.wave_dash {
+            if matches!(ch, '\u{301c}' | '\u{ff5e}') {
+                return mapping.char();
+            }
+        }
+        if let (Some(mapping), '・' | '･') = (self.middle_dot, ch) {
+            return match mapping {
+                MiddleDotMapping::Keep      => ch,
+                MiddleDotMapping::Halfwidth => '･',
+                MiddleDotMapping::Fullwidth => '・',
+                MiddleDotMapping::Slash     => '/',
+                MiddleDotMapping::Hyphen    => '-',
+            };
+        }
+        if let Some(mapping) = self.minus {
+            if matches!(ch, '\u{2212}' | '\u{ff0d}') {
+                return mapping.char();
+            }
+        }
+        match (self.signs, sign_ascii(ch)) {
+            (Some(SignMapping::Keep), Some(_))      => return ch,
+            (Some(SignMapping::Ascii), Some(ascii)) => {
+                return ascii.chars().find(|&head| head != '-').unwrap();
+            }
+            _                                       => {}
+        }
+        if let Some(converted) = self.currency.apply(ch) {
+            return converted;
+        }
+        match (ch, self.space) {
+            ('\u{3000}', WidthTarget::Halfwidth) => return ' ',
+            (' ', WidthTarget::Fullwidth)        => return '\u{3000}',
+            _                                    => {}
+        }
+        let hangul = self.target(FormsCategory::HalfwidthHangul);
+        if (self.jamo == JamoForm::Conjoining || self.compose_hangul)
+            && matches!(hangul, WidthTarget::Standard | WidthTarget::Fullwidth) {
+            if let Some(jamo) = conjoining_jamo(ch) {
+                return jamo;
+            }
+        }
+        match pair_category(ch) {
+            Some(category) => self.target(category).apply(ch),
+            None           => ch,
+        }
+    }
+
+    /// Converts a single character according to the policy, like
+    /// `convert_char`, but returns both characters of the arrows the policy
+    /// replaces with two.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(all(feature = "kana", feature = "latin"))] {
+    /// use unicode_hfwidth::{SignMapping, WidthPolicy};
+    ///
+    /// let policy = WidthPolicy::builder().signs(SignMapping::Ascii).build();
+    /// let s: String = "ｱ￫Ｂ".chars().flat_map(|ch| policy.convert_char_expanded(ch)).collect();
+    /// assert_eq!(s, "ア->B");
+    /// # }
+    /// ```
+    pub fn convert_char_expanded(&self, ch: char) -> Converted {
+        self.expansion(ch)
+            .and_then(Converted::from_short)
+            .unwrap_or_else(|| Converted::one(self.convert_char(ch)))
+    }
+
+    /// Converts `s` according to the policy. A half-width voiced sound mark
+    /// converted to its combining form is composed with the preceding kana,
+    /// e.g. `ｶﾞ` becomes `ガ`.
+    pub fn convert<S: AsRef<str>>(&self, s: S) -> String {
+        let s = s.as_ref();
+        traced("WidthPolicy::convert", s, || self.convert_untraced(s))
+    }
+
+    /// Returns the capacity to convert `s` into: the exact length of its
+    /// conversion if the policy converts everything to one `WidthForm`, or
+    /// else the length of `s`.
+    pub(crate) fn capacity_for(&self, s: &str) -> usize {
+        [WidthForm::Halfwidth, WidthForm::Fullwidth].into_iter()
+            .find(|form| *self == WidthPolicy::builder().all(form.target()).build())
+            .map_or(s.len(), |form| converted_len_utf8(s, form))
+    }
+
+    /// Converts `s` like `convert`, without tracing it.
+    fn convert_untraced(&self, s: &str) -> String {
+        let mut out = String::with_capacity(self.capacity_for(s));
+        let mut cx = Context::default();
+        for ch in s.chars() {
+            self.push_char(ch, &mut cx, &mut out);
+        }
+        cx.flush(&mut out);
+        out
+    }
+
+    /// Converts `s` according to the policy, copying the byte ranges in
+    /// `protected`, sorted by start, unchanged.
+    pub(crate) fn convert_except(&self, s: &str, protected: &[Range<usize>]) -> String {
+        let mut out = String::with_capacity(s.len());
+        let Ok(()) = self.push_except(s, protected, |_| None, &mut out,
+                                      |_| Ok::<_, Infallible>(()));
+        out
+    }
+
+    /// Appends the conversion of `s` to `out` piece by piece, replacing the
+    /// characters `mapping` returns a character for with it, and copying the
+    /// byte ranges in `protected`, sorted by start, unchanged. The context of
+    /// the conversion carries across mapped and protected text, so that every
+    /// way of converting with protected spans gives the same result. Calls
+    /// `check` with the offset of each character before converting it, and
+    /// stops at its first error.
+    pub(crate) fn push_except<S, M, C, E>(&self, s: &str, protected: &[Range<usize>], mapping: M,
+                                          out: &mut S, mut check: C) -> Result<(), E>
+        where S: PieceSink, M: Fn(char) -> Option<char>, C: FnMut(usize) -> Result<(), E> {
+        let mut cx = Context::default();
+        let mut pos = 0;
+        for span in protected.iter().filter(|span| !span.is_empty()).map(Some).chain([None]) {
+            if span.is_some_and(|span| span.end <= pos) {
+                continue;
+            }
+            let start = span.map_or(s.len(), |span| span.start.max(pos));
+            for (i, ch) in s[pos..start].char_indices().map(|(i, ch)| (pos + i, ch)) {
+                check(i)?;
+                let range = i..i + ch.len_utf8();
+                match mapping(ch) {
+                    Some(mapped) => cx.push_verbatim(mapped, range, out),
+                    None         => self.push_piece(ch, range, &mut cx, out),
+                }
+            }
+            if let Some(span) = span {
+                for (i, ch) in s[start..span.end].char_indices().map(|(i, ch)| (start + i, ch)) {
+                    cx.push_verbatim(ch, i..i + ch.len_utf8(), out);
+                }
+                pos = span.end;
+            }
+        }
+        cx.flush(out);
+        Ok(())
+    }
+
+    /// Appends the conversion of `ch` to `out`, like `push_piece`, for output
+    /// where the input ranges are not needed.
+    pub(crate) fn push_char(&self, ch: char, cx: &mut Context, out: &mut String) {
+        self.push_piece(ch, 0..0, cx, out);
+    }
+
+    /// Appends the conversion of `ch`, from `range` of the input, to `out`.
+    /// A converted kana that a following voiced sound mark could compose
+    /// with, or a jamo or syllable a following jamo could compose with, is
+    /// held back in `cx` until the next character is known. Every conversion
+    /// goes through here, so that offsets, edits and in-place conversion
+    /// agree with `convert`.
+    pub(crate) fn push_piece<S: PieceSink>(&self, ch: char, range: Range<usize>, cx: &mut Context,
+                                           out: &mut S) {
+        if let Some(expansion) = self.expansion(ch) {
+            cx.flush(out);
+            /* Characters after the first come from the empty range after ch */
+            let ranges = iter::once(range.clone()).chain(iter::repeat(range.end..range.end));
+            for (ch, range) in expansion.chars().zip(ranges) {
+                cx.emit(ch, range, out);
+            }
+            return;
+        }
+        let converted = self.convert_after(ch, self.convert_char(ch), cx.held().or(cx.last));
+        if let (Some(mapping), Some((mark, _, _))) = (self.minus, &mut cx.held) {
+            if is_prolonged_mark(*mark) && is_digit(ch) {
+                *mark = mapping.char();
+            }
+        }
+        let held = cx.held();
+        if matches!(ch, 'ﾞ' | 'ﾟ') {
+            if let Some(composed) = held.and_then(|base| compose_kana(base, converted)) {
+                let (_, held_range, _) = cx.held.take().unwrap();
+                cx.emit(composed, held_range.start..range.end, out);
+                return;
+            }
+        }
+        if self.compose_hangul {
+            /* A tentative final consonant followed by a vowel starts a syllable instead */
+            let split = held.and_then(split_final).filter(|_| is_vowel(converted));
+            if let Some((syllable, leading)) = split {
+                let (_, held_range, tail) = cx.held.take().unwrap();
+                cx.emit(syllable, held_range.start..tail, out);
+                cx.held = compose_hangul(leading, converted)
+                    .map(|composed| (composed, tail..range.end, range.start));
+                return;
+            }
+            /* Half-width consonants are ambiguous, and end a syllable if they can */
+            let next = match final_form(converted) {
+                Some(final_) if matches!(ch, '\u{ffa1}'..='\u{ffbe}')
+                    && held.is_some_and(|base| compose_hangul(base, final_).is_some()) => final_,
+                _ => converted,
+            };
+            if let Some(composed) = held.and_then(|base| compose_hangul(base, next)) {
+                let tentative = next != converted && split_final(composed).is_some();
+                let (_, held_range, _) = cx.held.take().unwrap();
+                let tail = range.start;
+                let range = held_range.start..range.end;
+                if tentative || compose_hangul(composed, '\u{11a8}').is_some() {
+                    cx.held = Some((composed, range, tail));
+                } else {
+                    cx.emit(composed, range, out);
+                }
+                return;
+            }
+        }
+        cx.flush(out);
+        if compose_kana(converted, '\u{3099}').is_some()
+            || compose_kana(converted, '\u{309a}').is_some()
+            || self.compose_hangul && (compose_hangul(converted, '\u{1161}').is_some()
+                                       || compose_hangul(converted, '\u{11a8}').is_some())
+            || self.minus.is_some() && is_prolonged_mark(converted)
+                && !cx.last.is_some_and(is_kana) {
+            cx.held = Some((converted, range.clone(), range.start));
+        } else {
+            cx.emit(converted, range, out);
+        }
+    }
+}
+
+/// Receives the characters of a conversion with the byte ranges of the input
+/// they came from.
+pub(crate) trait PieceSink {
+    /// Appends `ch`, converted from `range` of the input.
+    fn push_piece(&mut self, ch: char, range: Range<usize>);
+}
+
+impl PieceSink for String {
+    fn push_piece(&mut self, ch: char, _: Range<usize>) {
+        self.push(ch);
+    }
+}
+
+impl PieceSink for Vec<u16> {
+    fn push_piece(&mut self, ch: char, _: Range<usize>) {
+        self.extend_from_slice(ch.encode_utf16(&mut [0; 2]));
+    }
+}
+
+/// The state a conversion carries from one character to the next.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Context {
+    /// A converted character that may compose with the next one, the range of
+    /// the input it came from, and the start of the last input character in
+    /// that range.
+    held: Option<(char, Range<usize>, usize)>,
+    /// The last character output, which the conversion of the next one can
+    /// depend on.
+    last: Option<char>,
+}
+
+impl Context {
+    /// Returns the character held back, if any.
+    fn held(&self) -> Option<char> {
+        self.held.as_ref().map(|&(held, _, _)| held)
+    }
+
+    fn emit<S: PieceSink>(&mut self, ch: char, range: Range<usize>, out: &mut S) {
+        out.push_piece(ch, range);
+        self.last = Some(ch);
+    }
+
+    /// Outputs the character held back, if any.
+    pub(crate) fn flush<S: PieceSink>(&mut self, out: &mut S) {
+        if let Some((ch, range, _)) = self.held.take() {
+            self.emit(ch, range, out);
+        }
+    }
+
+    /// Outputs the character held back, if any, and resets the context for
+    /// new input.
+    pub(crate) fn finish<S: PieceSink>(&mut self, out: &mut S) {
+        self.flush(out);
+        self.last = None;
+    }
+
+    /// Outputs `ch`, from `range` of the input, as it is, after the character
+    /// held back: a mapped character, or one of protected text. Nothing
+    /// composes with it.
+    pub(crate) fn push_verbatim<S: PieceSink>(&mut self, ch: char, range: Range<usize>,
+                                              out: &mut S) {
+        self.flush(out);
+        self.emit(ch, range, out);
+    }
+}
+
+/// Converts `s` according to `policy`, passing each character of `s` through
+/// `filter` first, which returns the character to convert in its place, or
+/// `None` to drop it. Gives the same result as filtering `s` into a new
+/// string and converting that, in a single pass.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::WidthPolicy;
+///
+/// let policy = WidthPolicy::default();
+/// let unvoiced = unicode_hfwidth::filter_map_width("ｶﾞｲﾄﾞ", &policy, |ch| {
+///     (!matches!(ch, 'ﾞ' | 'ﾟ')).then_some(ch)
+/// });
+/// assert_eq!(unvoiced, "カイト");
+/// let compact = unicode_hfwidth::filter_map_width("ＩＤ　１２", &policy, |ch| {
+///     (ch != '\u{3000}').then_some(ch)
+/// });
+/// assert_eq!(compact, "ID12");
+/// # }
+/// ```
+pub fn filter_map_width<F>(s: &str, policy: &WidthPolicy, mut filter: F) -> String
+    where F: FnMut(char) -> Option<char> {
+    let mut out = String::with_capacity(s.len());
+    let mut cx = Context::default();
+    for ch in s.chars().filter_map(&mut filter) {
+        policy.push_char(ch, &mut cx, &mut out);
+    }
+    cx.flush(&mut out);
+    out
+}
+
+/// Builder for `WidthPolicy`.
+#[derive(Clone, Debug)]
+pub struct WidthPolicyBuilder {
+    policy: WidthPolicy,
+}
+
+impl WidthPolicyBuilder {
+    /// Sets the target for characters of `category`.
+    pub fn category(mut self, category: FormsCategory, target: WidthTarget) -> WidthPolicyBuilder {
+        self.policy.targets[category as usize] = target;
+        self
+    }
+
+    /// Sets the target for every category.
+    pub fn all(mut self, target: WidthTarget) -> WidthPolicyBuilder {
+        self.policy.targets = [target; 7];
+        self
+    }
+
+    /// Sets the target for full-width ASCII variants and white parentheses.
+    pub fn ascii(self, target: WidthTarget) -> WidthPolicyBuilder {
+        self.category(FormsCategory::FullwidthAscii, target)
+            .category(FormsCategory::FullwidthBrackets, target)
+    }
+
+    /// Sets the target for half-width katakana and CJK punctuation.
+    pub fn kana(self, target: WidthTarget) -> WidthPolicyBuilder {
+        self.category(FormsCategory::HalfwidthKatakana, target)
+            .category(FormsCategory::HalfwidthPunctuation, target)
+    }
+
+    /// Sets the target for half-width Hangul.
+    pub fn hangul(self, target: WidthTarget) -> WidthPolicyBuilder {
+        self.category(FormsCategory::HalfwidthHangul, target)
+    }
+
+    /// Sets the target for full-width and half-width symbol variants.
+    pub fn symbols(self, target: WidthTarget) -> WidthPolicyBuilder {
+        self.category(FormsCategory::FullwidthSymbols, target)
+            .category(FormsCategory::HalfwidthSymbols, target)
+    }
+
+    /// Sets the target for spaces: `Halfwidth` converts the ideographic space
+    /// `U+3000` to an ASCII space and `Fullwidth` the reverse. Since both are
+    /// standard-width characters, `Standard` leaves them alone like `Keep`.
+    pub fn space(mut self, target: WidthTarget) -> WidthPolicyBuilder {
+        self.policy.space = target;
+        self
+    }
+
+    /// Sets the currency symbol policy.
+    pub fn currency(mut self, currency: CurrencyPolicy) -> WidthPolicyBuilder {
+        self.policy.currency = currency;
+        self
+    }
+
+    /// Sets whether CJK punctuation without a half-width ASCII form is
+    /// approximated with ASCII, for targets that only accept ASCII: `。` and
+    /// `、` become `.` and `,`, corner brackets `"`, `・` becomes `/`, lenticular
+    /// and tortoise shell brackets `[` and `]`, and `〜` becomes `~`. Both widths
+    /// are approximated, whatever the target of their category.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(all(feature = "kana", feature = "latin"))] {
+    /// use unicode_hfwidth::WidthPolicy;
+    ///
+    /// let policy = WidthPolicy::builder().ascii_punctuation(true).build();
+    /// assert_eq!(policy.convert("「ＯＫ」、ｶﾅ｡"), "\"OK\",カナ.");
+    /// # }
+    /// ```
+    pub fn ascii_punctuation(mut self, enabled: bool) -> WidthPolicyBuilder {
+        self.policy.ascii_punctuation = enabled;
+        self
+    }
+
+    /// Sets the mapping for the half-width forms of signs, U+FFE8 to U+FFEE,
+    /// overriding the target of their category. Their standard counterparts,
+    /// such as `←`, still follow the target.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "kana")] {
+    /// use unicode_hfwidth::{SignMapping, WidthPolicy};
+    ///
+    /// let policy = WidthPolicy::builder().signs(SignMapping::Ascii).build();
+    /// assert_eq!(policy.convert("￭ ｶﾅ ￫ ○ →"), "# カナ -> ○ →");
+    /// # }
+    /// ```
+    pub fn signs(mut self, mapping: SignMapping) -> WidthPolicyBuilder {
+        self.policy.signs = Some(mapping);
+        self
+    }
+
+    /// Sets whether jamo are composed into Hangul syllables after conversion:
+    /// a leading consonant and a vowel, optionally followed by a final
+    /// consonant, become a precomposed syllable, as NFC composes them.
+    ///
+    /// Composition works on conjoining jamo, so half-width Hangul is
+    /// converted to conjoining jamo as with `JamoForm::Conjoining`. Since
+    /// half-width consonants do not tell leading and final consonants apart,
+    /// one following a syllable becomes its final consonant unless a vowel
+    /// follows it.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "hangul")] {
+    /// use unicode_hfwidth::WidthPolicy;
+    ///
+    /// let policy = WidthPolicy::builder().compose_hangul(true).build();
+    /// assert_eq!(policy.convert("ﾾￂﾤﾡￓﾡ ﾡￌﾣ"), "한국 곣");
+    /// # }
+    /// ```
+    pub fn compose_hangul(mut self, enabled: bool) -> WidthPolicyBuilder {
+        self.policy.compose_hangul = enabled;
+        self
+    }
+
+    /// Sets the jamo half-width Hangul is converted to.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "hangul")] {
+    /// use unicode_hfwidth::{JamoForm, WidthPolicy};
+    ///
+    /// let policy = WidthPolicy::builder().jamo(JamoForm::Conjoining).build();
+    /// assert_eq!(policy.convert("ﾡￂ"), "\u{1100}\u{1161}");
+    /// assert_eq!(WidthPolicy::default().convert("ﾡￂ"), "ㄱㅏ");
+    /// # }
+    /// ```
+    pub fn jamo(mut self, form: JamoForm) -> WidthPolicyBuilder {
+        self.policy.jamo = form;
+        self
+    }
+
+    /// Sets the character both the wave dash `〜` and the full-width tilde
+    /// `～` are converted to, overriding the target of the full-width tilde.
+    /// With `ascii_punctuation`, the wave dash is still approximated by `~`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "latin")] {
+    /// use unicode_hfwidth::{WaveDashMapping, WidthPolicy};
+    ///
+    /// let policy = WidthPolicy::builder().wave_dash(WaveDashMapping::WaveDash).build();
+    /// assert_eq!(policy.convert("10～20〜30 ~"), "10〜20〜30 ~");
+    /// assert_eq!(WidthPolicy::default().convert("10～20〜30"), "10~20〜30");
+    /// # }
+    /// ```
+    pub fn wave_dash(mut self, mapping: WaveDashMapping) -> WidthPolicyBuilder {
+        self.policy.wave_dash = Some(mapping);
+        self
+    }
+
+    /// Sets the character the minus sign `−` and the full-width hyphen-minus
+    /// `－` are folded to, overriding the target of the latter. Prolonged
+    /// sound marks `ー` and `ｰ` typed as hyphens are folded too: those before
+    /// a digit that don't follow kana, as in `03ー1234` or `ー5`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "latin")] {
+    /// use unicode_hfwidth::{MinusMapping, WidthPolicy};
+    ///
+    /// let policy = WidthPolicy::builder().minus(MinusMapping::HyphenMinus).build();
+    /// assert_eq!(policy.convert("０３ー１２３４−５６７８"), "03-1234-5678");
+    /// assert_eq!(policy.convert("コーヒー1杯"), "コーヒー1杯");
+    /// let policy = WidthPolicy::builder().minus(MinusMapping::MinusSign).build();
+    /// assert_eq!(policy.convert("ー５００"), "−500");
+    /// # }
+    /// ```
+    pub fn minus(mut self, mapping: MinusMapping) -> WidthPolicyBuilder {
+        self.policy.minus = Some(mapping);
+        self
+    }
+
+    /// Sets the quotation marks the full-width quotation mark `＂` and
+    /// apostrophe `＇` become when their category is converted to half
+    /// width. ASCII quotation marks in the input are left as they are.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "latin")] {
+    /// use unicode_hfwidth::{QuoteMapping, WidthPolicy};
+    ///
+    /// let policy = WidthPolicy::builder().quotes(QuoteMapping::Curly).build();
+    /// assert_eq!(policy.convert("＂Ｉｔ＇ｓ＂ ＇ｏｋ＇"), "“It’s” ‘ok’");
+    /// assert_eq!(WidthPolicy::default().convert("＂Ｉｔ＇ｓ＂"), "\"It's\"");
+    /// # }
+    /// ```
+    pub fn quotes(mut self, mapping: QuoteMapping) -> WidthPolicyBuilder {
+        self.policy.quotes = mapping;
+        self
+    }
+
+    /// Sets the mapping for the katakana middle dot `・` and its half-width
+    /// form `･`, overriding the target of their category. With
+    /// `ascii_punctuation`, they are still approximated by `/`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "kana")] {
+    /// use unicode_hfwidth::{MiddleDotMapping, WidthPolicy};
+    ///
+    /// let policy = WidthPolicy::builder().middle_dot(MiddleDotMapping::Hyphen).build();
+    /// assert_eq!(policy.convert("ｼﾞｮﾝ･ｽﾐｽ"), "ジョン-スミス");
+    /// let policy = WidthPolicy::builder().middle_dot(MiddleDotMapping::Keep).build();
+    /// assert_eq!(policy.convert("ｼﾞｮﾝ･ｽﾐｽ"), "ジョン･スミス");
+    /// # }
+    /// ```
+    pub fn middle_dot(mut self, mapping: MiddleDotMapping) -> WidthPolicyBuilder {
+        self.policy.middle_dot = Some(mapping);
+        self
+    }
+
+    /// Sets whether prolonged sound marks `ー` and `ｰ` that don't follow kana
+    /// become hyphens, `-` or the character of the `minus` mapping, so
+    /// product codes such as `ABCｰ123` keep their hyphens while marks after
+    /// kana follow the target of their category.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "kana")] {
+    /// use unicode_hfwidth::WidthPolicy;
+    ///
+    /// let policy = WidthPolicy::builder().contextual_prolonged_mark(true).build();
+    /// assert_eq!(policy.convert("ｺｰﾄﾞ ABCｰ123"), "コード ABC-123");
+    /// assert_eq!(WidthPolicy::default().convert("ABCｰ123"), "ABCー123");
+    /// # }
+    /// ```
+    pub fn contextual_prolonged_mark(mut self, enabled: bool) -> WidthPolicyBuilder {
+        self.policy.contextual_prolonged_mark = enabled;
+        self
+    }
+
+    /// Returns the configured policy.
+    pub fn build(self) -> WidthPolicy {
+        self.policy
+    }
+}
+
+#[cfg(feature = "symbols")]
+#[test]
+fn test_currency_policy() {
+    let policy = WidthPolicy::builder()
+        .currency(CurrencyPolicy::new()
+            .set(CurrencySymbol::Yen, CurrencyMapping::Ascii)
+            .set(CurrencySymbol::Pound, CurrencyMapping::Keep)
+            .set(CurrencySymbol::Won, CurrencyMapping::Sign))
+        .build();
+    assert_eq!(policy.convert("￥¥￡￦￠"), "\\\\￡₩¢");
+}
+
+#[cfg(feature = "symbols")]
+#[test]
+fn test_currency_follows_category() {
+    let policy = WidthPolicy::builder()
+        .category(FormsCategory::FullwidthSymbols, WidthTarget::Fullwidth)
+        .currency(CurrencyPolicy::new().set(CurrencySymbol::Cent, CurrencyMapping::Sign))
+        .build();
+    assert_eq!(policy.convert("¥￠"), "￥¢");
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_category_groups() {
+    let policy = WidthPolicy::builder()
+        .ascii(WidthTarget::Halfwidth)
+        .kana(WidthTarget::Fullwidth)
+        .space(WidthTarget::Halfwidth)
+        .symbols(WidthTarget::Keep)
+        .build();
+    assert_eq!(policy.convert("ＡＢ\u{3000}ｶﾅ｡￥￩"), "AB カナ。￥￩");
+    assert_eq!(WidthPolicy::default().convert("\u{3000} "), "\u{3000} ");
+    let policy = WidthPolicy::builder().space(WidthTarget::Fullwidth).build();
+    assert_eq!(policy.convert("a b"), "a\u{3000}b");
+}
+
+#[cfg(feature = "latin")]
+#[test]
+fn test_ascii_punctuation() {
+    let policy = WidthPolicy::builder().ascii_punctuation(true).kana(WidthTarget::Keep).build();
+    assert_eq!(policy.convert("『ﾃｽﾄ』・【Ａ】〜｢ｶﾞ｣"), "\"ﾃｽﾄ\"/[A]~\"ｶﾞ\"");
+    assert_eq!(WidthPolicy::default().convert("。・「」"), "。・「」");
+}
+
+#[cfg(feature = "kana")]
+#[test]
+fn test_signs() {
+    let policy = WidthPolicy::builder().signs(SignMapping::Ascii).build();
+    assert_eq!(policy.convert("ｶ￩ﾞ￨￪￬"), "カ<-\u{3099}|^v");
+    assert_eq!(policy.convert_char('￫'), '>');
+    assert_eq!(policy.convert("│←↑→↓■○"), "│←↑→↓■○");
+    let policy = WidthPolicy::builder()
+        .all(WidthTarget::Halfwidth)
+        .signs(SignMapping::Keep)
+        .build();
+    assert_eq!(policy.convert("￭￮ カ"), "￭￮ ｶ");
+}
+
+#[cfg(feature = "hangul")]
+#[test]
+fn test_conjoining_jamo() {
+    let policy = WidthPolicy::builder().jamo(JamoForm::Conjoining).build();
+    assert_eq!(policy.convert("ﾠﾣￌㄱ"), "\u{1160}\u{11aa}\u{1169}ㄱ");
+    let policy = WidthPolicy::builder()
+        .jamo(JamoForm::Conjoining)
+        .hangul(WidthTarget::Keep)
+        .build();
+    assert_eq!(policy.convert("ﾡ"), "ﾡ");
+}
+
+#[cfg(all(feature = "hangul", feature = "kana"))]
+#[test]
+fn test_compose_hangul() {
+    let policy = WidthPolicy::builder().compose_hangul(true).build();
+    assert_eq!(policy.convert("ﾡￂﾡￂﾡ"), "가각");
+    assert_eq!(policy.convert("가ﾣ 하ﾤￂ ﾳﾳￂ"), "갃 하나 \u{1108}빠");
+    assert_eq!(policy.convert("\u{1100}ﾂﾡￂﾞ 한ￂ"), "\u{1100}ツ가\u{3099} 한\u{1161}");
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_as_ref_inputs() {
+    use std::borrow::Cow;
+
+    let policy = WidthPolicy::default();
+    assert_eq!(policy.convert(String::from("ＡＢ")), "AB");
+    assert_eq!(policy.convert(Cow::Borrowed("ｶﾅ")), "カナ");
+    assert_eq!(policy.convert(Box::<str>::from("１")), "1");
+    assert_eq!(super::str_width(String::from("ｶﾅ")), 2);
+}
+
+#[cfg(feature = "kana")]
+#[test]
+fn test_kana_composition() {
+    let policy = WidthPolicy::default();
+    assert_eq!(policy.convert("ｶﾞｷﾞﾊﾟﾜﾞｳﾞﾞ"), "ガギパヷヴ\u{3099}");
+    assert_eq!(policy.convert("ｱﾞかﾞﾞ"), "ア\u{3099}が\u{3099}");
+    let policy = WidthPolicy::builder().kana(WidthTarget::Keep).build();
+    assert_eq!(policy.convert("ｶﾞカﾞ"), "ｶﾞカﾞ");
+}
+
+#[test]
+fn test_wave_dash() {
+    let policy = WidthPolicy::builder()
+        .all(WidthTarget::Halfwidth)
+        .wave_dash(WaveDashMapping::FullwidthTilde)
+        .build();
+    assert_eq!(policy.convert("〜～ｰ"), "～～ｰ");
+    assert_eq!(policy.wave_dash(), Some(WaveDashMapping::FullwidthTilde));
+    assert_eq!(WidthPolicy::default().wave_dash(), None);
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_minus() {
+    let policy = WidthPolicy::builder().minus(MinusMapping::HyphenMinus).build();
+    assert_eq!(policy.convert("TEL:０９０ｰ1234ｰ5678"), "TEL:090-1234-5678");
+    assert_eq!(policy.convert("ｰ ｰ1 ﾃﾞｰ2 ｰ"), "ー -1 デー2 ー");
+    assert_eq!(policy.convert("１００ーＡ"), "100ーA");
+    for s in ["０３ー１", "ｰ ｰ1 ﾃﾞｰ2 ｰ"] {
+        assert_eq!(policy.convert_with_offsets(s).0, policy.convert(s));
+    }
+    let mut stream = super::StreamConverter::new(policy);
+    let mut out = String::new();
+    stream.feed(b"03\xe3\x83\xbc", &mut out).unwrap();
+    stream.feed("１".as_bytes(), &mut out).unwrap();
+    stream.finish(&mut out).unwrap();
+    assert_eq!(out, "03-1");
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_quotes() {
+    let policy = WidthPolicy::builder().quotes(QuoteMapping::Curly).build();
+    assert_eq!(policy.convert("(＂ａ＂)「＇ｶ＇」\"b\""), "(“a”)「‘カ’」\"b\"");
+    for s in ["＂ａ＂", "ｶﾞ＇ｓ"] {
+        assert_eq!(policy.convert_with_offsets(s).0, policy.convert(s));
+    }
+    let policy = WidthPolicy::builder()
+        .quotes(QuoteMapping::Curly)
+        .ascii(WidthTarget::Keep)
+        .build();
+    assert_eq!(policy.convert("＂ａ＂"), "＂ａ＂");
+}
+
+#[test]
+fn test_middle_dot() {
+    let policy = WidthPolicy::builder()
+        .kana(WidthTarget::Keep)
+        .middle_dot(MiddleDotMapping::Fullwidth)
+        .build();
+    assert_eq!(policy.convert("ﾒｱﾘｰ･ｼﾞｪｰﾝ・ﾄﾞｩ"), "ﾒｱﾘｰ・ｼﾞｪｰﾝ・ﾄﾞｩ");
+    let policy = WidthPolicy::builder().middle_dot(MiddleDotMapping::Halfwidth).build();
+    assert_eq!(policy.convert("・･"), "･･");
+    let policy = WidthPolicy::builder().middle_dot(MiddleDotMapping::Slash).build();
+    assert_eq!(policy.convert("A・B"), "A/B");
+}
+
+#[cfg(feature = "kana")]
+#[test]
+fn test_contextual_prolonged_mark() {
+    let policy = WidthPolicy::builder()
+        .kana(WidthTarget::Keep)
+        .contextual_prolonged_mark(true)
+        .build();
+    assert_eq!(policy.convert("ｰ1 ｾｰﾙ SALEｰ2 ﾃﾞｰﾀ-ｰ"), "-1 ｾｰﾙ SALE-2 ﾃﾞｰﾀ--");
+    assert_eq!(policy.convert("ﾊﾟｰｸ"), "ﾊﾟｰｸ");
+    for s in ["ｰ1 ｾｰﾙ", "ﾊﾟｰｸ Aー"] {
+        assert_eq!(policy.convert_with_offsets(s).0, policy.convert(s));
+    }
+    let policy = WidthPolicy::builder()
+        .contextual_prolonged_mark(true)
+        .minus(MinusMapping::MinusSign)
+        .build();
+    assert_eq!(policy.convert("ｰ5 ﾏｲﾅｽｰ5"), "−5 マイナスー5");
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_filter_map_width() {
+    let policy = WidthPolicy::builder().minus(MinusMapping::HyphenMinus).build();
+    let s = "ｶﾞｰ1 Ａｰ2\u{3000}ﾊﾟ";
+    let filter = |ch: char| if ch == '\u{3000}' { None } else { Some(ch.to_ascii_lowercase()) };
+    let filtered: String = s.chars().filter_map(filter).collect();
+    assert_eq!(filter_map_width(s, &policy, filter), policy.convert(&filtered));
+    assert_eq!(filter_map_width(s, &policy, filter), "ガー1 A-2パ");
+    assert_eq!(filter_map_width(s, &policy, |_| None), "");
+}
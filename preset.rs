@@ -0,0 +1,183 @@
+//! Ready-made policies for common conventions.
+
+use super::{fold_width, MiddleDotMapping, MinusMapping, WaveDashMapping, WidthPolicy, WidthTarget};
+
+impl WidthPolicy {
+    /// Returns the policy of the common Japanese editorial convention:
+    /// alphanumerics and ASCII symbols in half width, katakana and CJK
+    /// punctuation in full width, and ideographic spaces as ASCII spaces.
+    /// Prolonged sound marks stay marks, becoming `ー` like the katakana
+    /// around them rather than `-`.
+    pub fn ja_standard() -> WidthPolicy {
+        WidthPolicy::builder()
+            .ascii(WidthTarget::Halfwidth)
+            .kana(WidthTarget::Fullwidth)
+            .hangul(WidthTarget::Standard)
+            .symbols(WidthTarget::Standard)
+            .space(WidthTarget::Halfwidth)
+            .build()
+    }
+}
+
+/// Ready-made policies for common uses, each combining the policy options the
+/// use needs.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::{Preset, WidthPolicy};
+///
+/// let policy = WidthPolicy::from(Preset::WebForm);
+/// assert_eq!(policy.convert("０３ー１２３４　ｶﾞｲﾄﾞ"), "03-1234 ガイド");
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+           serde(rename_all = "snake_case"))]
+pub enum Preset {
+    /// Everything in standard width, including ideographic spaces, which
+    /// become ASCII spaces. Punctuation that has no width forms is left as
+    /// it is.
+    Strict,
+    /// Text typed into web forms: `Strict`, with hyphens typed as minus
+    /// signs, full-width hyphen-minus or prolonged sound marks outside kana
+    /// folded to `-`, and wave dashes and full-width tildes to `〜`.
+    WebForm,
+    /// Legacy banking and batch systems accepting only half-width
+    /// characters: everything in half width, including spaces, with hyphen
+    /// lookalikes and katakana middle dots folded to `-`.
+    LegacyBank,
+    /// Keys for search and matching: `WebForm`, with middle dots unified to
+    /// `・` and jamo composed into Hangul syllables, so that text typed in
+    /// different ways compares equal.
+    SearchFold,
+}
+
+impl Preset {
+    /// All presets.
+    pub const ALL: [Preset; 4] = [Preset::Strict, Preset::WebForm, Preset::LegacyBank,
+                                  Preset::SearchFold];
+
+    /// Returns the name of the preset in snake_case, such as `web_form`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Strict     => "strict",
+            Preset::WebForm    => "web_form",
+            Preset::LegacyBank => "legacy_bank",
+            Preset::SearchFold => "search_fold",
+        }
+    }
+
+    /// Returns the policy of the preset.
+    pub fn policy(self) -> WidthPolicy {
+        let strict = WidthPolicy::builder().space(WidthTarget::Halfwidth);
+        let web_form = strict.clone()
+            .minus(MinusMapping::HyphenMinus)
+            .contextual_prolonged_mark(true)
+            .wave_dash(WaveDashMapping::WaveDash);
+        match self {
+            Preset::Strict     => strict.build(),
+            Preset::WebForm    => web_form.build(),
+            Preset::LegacyBank => {
+                WidthPolicy::builder()
+                    .all(WidthTarget::Halfwidth)
+                    .space(WidthTarget::Halfwidth)
+                    .minus(MinusMapping::HyphenMinus)
+                    .contextual_prolonged_mark(true)
+                    .middle_dot(MiddleDotMapping::Hyphen)
+                    .build()
+            }
+            Preset::SearchFold => {
+                web_form.middle_dot(MiddleDotMapping::Fullwidth).compose_hangul(true).build()
+            }
+        }
+    }
+}
+
+impl From<Preset> for WidthPolicy {
+    fn from(preset: Preset) -> WidthPolicy {
+        preset.policy()
+    }
+}
+
+/// Converts `s` with `WidthPolicy::ja_standard`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// assert_eq!(unicode_hfwidth::normalize_ja_standard("ﾃﾞｰﾀ　ＡＢＣ－１２３（ｺﾋﾟｰ）"),
+///            "データ ABC-123(コピー)");
+/// # }
+/// ```
+pub fn normalize_ja_standard<S: AsRef<str>>(s: S) -> String {
+    WidthPolicy::ja_standard().convert(s)
+}
+
+/// Applies the width mapping rule of PRECIS (RFC 8264), mapping full-width
+/// and half-width characters to their decomposition mappings, as the
+/// `UsernameCaseMapped` and `UsernameCasePreserved` profiles of RFC 8265 and
+/// the `Nickname` profile of RFC 8266 require before case mapping and
+/// normalization. Folded kana and jamo are composed as the following NFC
+/// normalization composes them, so the result is the same after NFC.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// assert_eq!(unicode_hfwidth::precis_width_map("ＵＳＥＲ＿ﾀﾞｲｽｹ"), "USER_ダイスケ");
+/// # }
+/// ```
+pub fn precis_width_map<S: AsRef<str>>(s: S) -> String {
+    fold_width(s)
+}
+
+/// Prepares a username the way the `UsernameCaseMapped` profile of RFC 8265
+/// maps it: the width mapping rule, then lowercasing. The result still has to
+/// be normalized to NFC and checked against the `IdentifierClass` by a PRECIS
+/// implementation, but usernames that differ only in width or case already
+/// have the same preparation.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// use unicode_hfwidth::precis_username_casemapped;
+///
+/// assert_eq!(precis_username_casemapped("Ｊｏｈｎ＿Ｓｍｉｔｈ"), precis_username_casemapped("john_smith"));
+/// # }
+/// ```
+pub fn precis_username_casemapped<S: AsRef<str>>(s: S) -> String {
+    precis_width_map(s).to_lowercase()
+}
+
+#[cfg(all(feature = "kana", feature = "latin", feature = "symbols"))]
+#[test]
+fn test_ja_standard() {
+    assert_eq!(normalize_ja_standard("ｶﾀｶﾅ｡ ｢ﾒﾓ｣ ＄１００ ￥５"), "カタカナ。 「メモ」 $100 ¥5");
+    assert_eq!(normalize_ja_standard("ラーメン ﾗｰﾒﾝ ─ │ →"), "ラーメン ラーメン ─ │ →");
+}
+
+#[cfg(all(feature = "hangul", feature = "kana", feature = "latin", feature = "symbols"))]
+#[test]
+fn test_precis() {
+    use unicode_normalization::UnicodeNormalization;
+
+    for s in ["ＡＢＣ　ｶﾞｷﾞ", "ﾡￂﾤ ￦￡", "ｶ\u{3099}", "ABC"] {
+        let nfkc: String = s.nfkc().collect();
+        assert_eq!(precis_width_map(s).nfc().collect::<String>(), nfkc, "{}", s);
+    }
+    assert_eq!(precis_username_casemapped("ＡＢＣ"), "abc");
+}
+
+#[cfg(all(feature = "hangul", feature = "kana", feature = "latin"))]
+#[test]
+fn test_presets() {
+    let input = "ＡＢＣｰ１２３　ｺｰﾋｰ･ｶｯﾌﾟ 10〜20～ ﾊﾝｸﾞﾙ ﾾￂ";
+    let expected = [
+        "ABCー123 コーヒー・カップ 10〜20~ ハングル ㅎㅏ",
+        "ABC-123 コーヒー・カップ 10〜20〜 ハングル ㅎㅏ",
+        "ABC-123 ｺｰﾋｰ-ｶｯﾌﾟ 10〜20~ ﾊﾝｸﾞﾙ ﾾￂ",
+        "ABC-123 コーヒー・カップ 10〜20〜 ハングル 하",
+    ];
+    for (preset, expected) in Preset::ALL.into_iter().zip(expected) {
+        assert_eq!(preset.policy().convert(input), expected, "{:?}", preset);
+    }
+}
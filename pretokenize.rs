@@ -0,0 +1,252 @@
+//! Normalization in front of tokenizers, keeping track of original offsets.
+
+use std::convert::Infallible;
+use std::ops::Range;
+
+use super::policy::PieceSink;
+use super::{compose_kana, to_standard_width, OffsetMap, WidthPolicy};
+
+/// A character of normalized text with the original byte range it came from.
+pub(crate) type Piece = (char, Range<usize>);
+
+impl PieceSink for Vec<Piece> {
+    fn push_piece(&mut self, ch: char, range: Range<usize>) {
+        self.push((ch, range));
+    }
+}
+
+/// Appends `ch` from `range`, composing a voiced sound mark with the kana
+/// before it.
+fn push_composed(pieces: &mut Vec<Piece>, ch: char, range: Range<usize>) {
+    if let Some((base, base_range)) = pieces.last_mut() {
+        if base_range.end == range.start {
+            if let Some(composed) = compose_kana(*base, ch) {
+                *base = composed;
+                base_range.end = range.end;
+                return;
+            }
+        }
+    }
+    pieces.push((ch, range));
+}
+
+/// Converts `s` according to `policy`, returning the characters of the result
+/// with the ranges of `s` they came from.
+pub(crate) fn width_pieces(s: &str, policy: &WidthPolicy) -> Vec<Piece> {
+    let mut pieces = Vec::with_capacity(s.len());
+    let Ok(()) = policy.push_except(s, &[], |_| None, &mut pieces, |_| Ok::<_, Infallible>(()));
+    pieces
+}
+
+/// Replaces each run of characters matching `f` with a single `to`.
+fn collapse<F: Fn(char) -> bool>(pieces: Vec<Piece>, f: F, to: char) -> Vec<Piece> {
+    let mut out: Vec<Piece> = Vec::with_capacity(pieces.len());
+    for (ch, range) in pieces {
+        match out.last_mut() {
+            Some((last, last_range)) if f(ch) && *last == to && f(*last) => {
+                last_range.end = range.end;
+            }
+            _ if f(ch) => out.push((to, range)),
+            _          => out.push((ch, range)),
+        }
+    }
+    out
+}
+
+/// Returns `true` for the blocks NEologd removes spaces next to: CJK
+/// ideographs, kana, CJK symbols and halfwidth and fullwidth forms.
+fn is_neologd_block(ch: char) -> bool {
+    matches!(ch, '\u{4e00}'..='\u{9fff}' | '\u{3000}'..='\u{30ff}' | '\u{ff00}'..='\u{ffef}')
+}
+
+/// Text normalized for a tokenizer, with a map back to the original text.
+///
+/// Tokenizers such as lindera or MeCab are run on `text()`, and the spans of
+/// the tokens they find are translated back with `original_span`, so they
+/// can be reported against the raw input.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "kana")] {
+/// use unicode_hfwidth::Pretokenized;
+///
+/// let input = "ﾊﾟｿｺﾝ　を　ｶｲﾏｼﾀ～！";
+/// let normalized = Pretokenized::neologd(input);
+/// assert_eq!(normalized.text(), "パソコンをカイマシタ!");
+/// let span = normalized.text().find("カイマシタ").unwrap();
+/// let original = normalized.original_span(span..span + "カイマシタ".len());
+/// assert_eq!(&input[original], "ｶｲﾏｼﾀ");
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pretokenized {
+    text: String,
+    offsets: OffsetMap,
+}
+
+impl Pretokenized {
+    fn from_pieces(pieces: &[Piece], original_len: usize) -> Pretokenized {
+        let mut text = String::with_capacity(original_len);
+        let mut offsets = OffsetMap::default();
+        for (ch, range) in pieces {
+            text.push(*ch);
+            offsets.push(range.clone(), ch.len_utf8());
+        }
+        offsets.finish(original_len);
+        Pretokenized { text, offsets }
+    }
+
+    /// Converts `s` according to `policy`, like `WidthPolicy::convert`.
+    pub fn new(s: &str, policy: &WidthPolicy) -> Pretokenized {
+        let (text, offsets) = policy.convert_with_offsets(s);
+        Pretokenized { text, offsets }
+    }
+
+    /// Normalizes `s` the way the mecab-ipadic-NEologd dictionary expects.
+    ///
+    /// Surrounding whitespace is removed, full-width alphanumerics and
+    /// half-width katakana are converted to standard width, hyphens and
+    /// prolonged sound marks are unified, tildes are removed, and ASCII
+    /// punctuation is converted to half-width, except for `=`, which becomes
+    /// `＝`. Runs of spaces become one space, which is removed next to CJK
+    /// text.
+    pub fn neologd(s: &str) -> Pretokenized {
+        let start = s.len() - s.trim_start().len();
+        let mut pieces = Vec::with_capacity(s.len());
+        for (i, ch) in s.trim().char_indices() {
+            let range = start + i..start + i + ch.len_utf8();
+            match ch {
+                '０'..='９' | 'Ａ'..='Ｚ' | 'ａ'..='ｚ' | '\u{ff61}'..='\u{ff9f}' => {
+                    let converted = to_standard_width(ch).unwrap_or(ch);
+                    push_composed(&mut pieces, converted, range);
+                }
+                '－' => pieces.push(('-', range)),
+                _    => pieces.push((ch, range)),
+            }
+        }
+        let hyphen = |ch| matches!(ch, '˗' | '֊' | '‐' | '‑' | '‒' | '–' | '⁃' | '⁻' | '₋' | '−');
+        let pieces = collapse(pieces, hyphen, '-');
+        let prolonged = |ch| matches!(ch, '﹣' | '－' | 'ｰ' | '—' | '―' | '─' | '━' | 'ー');
+        let mut pieces = collapse(pieces, prolonged, 'ー');
+        pieces.retain(|&(ch, _)| !matches!(ch, '~' | '∼' | '∾' | '〜' | '〰' | '～'));
+        for (ch, _) in &mut pieces {
+            *ch = match *ch {
+                '!'..='/' | ':'..='@' | '['..='`' | '{'..='~' if *ch != '\\' => {
+                    match *ch {
+                        '"'  => '”',
+                        '\'' => '’',
+                        _    => char::from_u32(*ch as u32 + 0xfee0).unwrap(),
+                    }
+                }
+                '¥' => '￥',
+                ch  => ch,
+            };
+        }
+        let space = |ch| matches!(ch, ' ' | '\u{3000}');
+        let pieces = collapse(pieces, space, ' ');
+        let mut out: Vec<Piece> = Vec::with_capacity(pieces.len());
+        for (i, (ch, range)) in pieces.iter().enumerate() {
+            if *ch == ' ' {
+                let prev = out.last().map(|(ch, _)| *ch);
+                let next = pieces.get(i + 1).map(|(ch, _)| *ch);
+                if let (Some(prev), Some(next)) = (prev, next) {
+                    let block = is_neologd_block(prev) || is_neologd_block(next);
+                    if block && (prev.is_ascii() || is_neologd_block(prev))
+                        && (next.is_ascii() || is_neologd_block(next)) {
+                        continue;
+                    }
+                }
+            }
+            out.push((*ch, range.clone()));
+        }
+        for (ch, _) in &mut out {
+            *ch = match *ch {
+                '＝' | '\u{ff02}' | '\u{ff07}' | '＼' => *ch,
+                '！'..='～'                          => char::from_u32(*ch as u32 - 0xfee0).unwrap(),
+                '￥'                                 => '¥',
+                '’'                                  => '\'',
+                '”'                                  => '"',
+                ch                                   => ch,
+            };
+        }
+        Pretokenized::from_pieces(&out, s.len())
+    }
+
+    /// Returns the normalized text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the map from offsets in the normalized text to the original.
+    pub fn offsets(&self) -> &OffsetMap {
+        &self.offsets
+    }
+
+    /// Returns the original byte range of `span` in the normalized text.
+    pub fn original_span(&self, span: Range<usize>) -> Range<usize> {
+        self.offsets.original_range(span)
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_neologd() {
+    let cases = [
+        ("０１２３４５６７８９", "0123456789"),
+        ("ＡＢＣＤＥＦＧＨＩＪＫＬＭＮＯＰＱＲＳＴＵＶＷＸＹＺ", "ABCDEFGHIJKLMNOPQRSTUVWXYZ"),
+        ("！”＃＄％＆’（）＊＋，－．／：；＜＞？＠［￥］＾＿｀｛｜｝",
+         "!\"#$%&'()*+,-./:;<>?@[¥]^_`{|}"),
+        ("＝。、・「」", "＝。、・「」"),
+        ("ﾊﾝｶｸ", "ハンカク"),
+        ("o₋o", "o-o"),
+        ("majika━", "majikaー"),
+        ("わ〰い", "わい"),
+        ("スーパーーーー", "スーパー"),
+        ("!#", "!#"),
+        ("ゼンカク　スペース", "ゼンカクスペース"),
+        ("お             お", "おお"),
+        ("      おお", "おお"),
+        ("おお      ", "おお"),
+        ("検索 エンジン 自作 入門 を 買いました!!!", "検索エンジン自作入門を買いました!!!"),
+        ("アルゴリズム C", "アルゴリズムC"),
+        ("　　　ＰＲＭＬ　　副　読　本　　　", "PRML副読本"),
+        ("Coding the Matrix", "Coding the Matrix"),
+        ("南アルプスの　天然水　Ｓｐａｒｋｉｎｇ　Ｌｅｍｏｎ　レモン一絞り",
+         "南アルプスの天然水Sparking Lemonレモン一絞り"),
+        ("南アルプスの　天然水-　Ｓｐａｒｋｉｎｇ*　Ｌｅｍｏｎ+　レモン一絞り",
+         "南アルプスの天然水-Sparking*Lemon+レモン一絞り"),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(Pretokenized::neologd(input).text(), expected, "{}", input);
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_pretokenized_spans() {
+    let input = " ｶﾞｯｺｳ～ｰｰ ＡＢ ";
+    let normalized = Pretokenized::neologd(input);
+    assert_eq!(normalized.text(), "ガッコウーAB");
+    assert_eq!(&input[normalized.original_span(0..3)], "ｶﾞ");
+    assert_eq!(&input[normalized.original_span(12..15)], "ｰｰ");
+    assert_eq!(&input[normalized.original_span(15..17)], "ＡＢ");
+    let converted = Pretokenized::new("ｶﾞﾞＡ", &WidthPolicy::default());
+    assert_eq!(converted.text(), WidthPolicy::default().convert("ｶﾞﾞＡ"));
+    assert_eq!(converted.original_span(6..7), 9..12);
+}
+
+#[cfg(feature = "hangul")]
+#[test]
+fn test_hangul_pieces() {
+    let policy = WidthPolicy::builder().compose_hangul(true).build();
+    for s in ["ﾡￂﾡￂﾡ", "가ﾣ 하ﾤￂ ﾳﾳￂ", "\u{1100}ﾂﾡￂﾞ 한ￂ", "ﾾￂﾤﾡￓﾡ ﾡￌﾣ", "ﾡￂﾰￂ"] {
+        let (converted, offsets) = policy.convert_with_offsets(s);
+        assert_eq!(converted, policy.convert(s));
+        for (i, _) in converted.char_indices() {
+            assert!(s.is_char_boundary(offsets.to_original(i)));
+        }
+    }
+    let pretokenized = Pretokenized::new("ﾾￂﾤﾡￓﾡ", &policy);
+    assert_eq!(pretokenized.text(), "한국");
+    assert_eq!(pretokenized.original_span(3..6), 9..18);
+}
@@ -0,0 +1,322 @@
+//! Named normalization profiles loaded from TOML or YAML.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use super::{CurrencyMapping, CurrencyPolicy, CurrencySymbol, Emails, FormsCategory, JamoForm,
+            MiddleDotMapping, MinusMapping, Placeholders, Preset, QuoteMapping, Quotes,
+            SignMapping, Urls, WaveDashMapping, WidthConverter, WidthPolicy, WidthPolicyBuilder,
+            WidthTarget};
+
+/// Error returned when a profile cannot be loaded.
+#[derive(Debug)]
+pub enum ProfileError {
+    /// The profile file could not be read.
+    Io(io::Error),
+    /// The profile is not valid TOML or does not describe a profile.
+    #[cfg(feature = "toml")]
+    Toml(toml::de::Error),
+    /// The profile is not valid YAML or does not describe a profile.
+    #[cfg(feature = "yaml")]
+    Yaml(serde_yaml::Error),
+    /// The file extension is not one of the enabled formats.
+    UnsupportedFormat(String),
+    /// A protected pattern is not a valid regular expression, or patterns are
+    /// not supported because the `regex` feature is disabled.
+    Pattern(String),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProfileError::Io(e)                => e.fmt(f),
+            #[cfg(feature = "toml")]
+            ProfileError::Toml(e)              => e.fmt(f),
+            #[cfg(feature = "yaml")]
+            ProfileError::Yaml(e)              => e.fmt(f),
+            ProfileError::UnsupportedFormat(e) => write!(f, "unsupported profile format '{}'", e),
+            ProfileError::Pattern(e)           => write!(f, "invalid pattern: {}", e),
+        }
+    }
+}
+
+impl Error for ProfileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ProfileError::Io(e)   => Some(e),
+            #[cfg(feature = "toml")]
+            ProfileError::Toml(e) => Some(e),
+            #[cfg(feature = "yaml")]
+            ProfileError::Yaml(e) => Some(e),
+            _                     => None,
+        }
+    }
+}
+
+/// A built-in protector named in a profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ProtectorName {
+    Urls,
+    Emails,
+    Placeholders,
+    Quotes,
+}
+
+/// The profile file format.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ProfileFile {
+    name: Option<String>,
+    preset: Option<Preset>,
+    all: Option<WidthTarget>,
+    ascii: Option<WidthTarget>,
+    kana: Option<WidthTarget>,
+    hangul: Option<WidthTarget>,
+    symbols: Option<WidthTarget>,
+    space: Option<WidthTarget>,
+    ascii_punctuation: Option<bool>,
+    signs: Option<SignMapping>,
+    jamo: Option<JamoForm>,
+    compose_hangul: Option<bool>,
+    wave_dash: Option<WaveDashMapping>,
+    minus: Option<MinusMapping>,
+    quotes: Option<QuoteMapping>,
+    middle_dot: Option<MiddleDotMapping>,
+    contextual_prolonged_mark: Option<bool>,
+    categories: HashMap<FormsCategory, WidthTarget>,
+    currency: HashMap<CurrencySymbol, CurrencyMapping>,
+    map: HashMap<char, char>,
+    protect: Vec<ProtectorName>,
+    patterns: Vec<String>,
+}
+
+type Group = fn(WidthPolicyBuilder, WidthTarget) -> WidthPolicyBuilder;
+
+impl ProfileFile {
+    fn build(self) -> Result<Profile, ProfileError> {
+        let mut policy = match self.preset {
+            Some(preset) => preset.policy().into_builder(),
+            None         => WidthPolicy::builder(),
+        };
+        if let Some(target) = self.all {
+            policy = policy.all(target);
+        }
+        let groups = [
+            (self.ascii, WidthPolicyBuilder::ascii as Group),
+            (self.kana, WidthPolicyBuilder::kana),
+            (self.hangul, WidthPolicyBuilder::hangul),
+            (self.symbols, WidthPolicyBuilder::symbols),
+            (self.space, WidthPolicyBuilder::space),
+        ];
+        for (target, set) in groups {
+            if let Some(target) = target {
+                policy = set(policy, target);
+            }
+        }
+        for category in FormsCategory::ALL {
+            if let Some(&target) = self.categories.get(&category) {
+                policy = policy.category(category, target);
+            }
+        }
+        if let Some(enabled) = self.ascii_punctuation {
+            policy = policy.ascii_punctuation(enabled);
+        }
+        if let Some(mapping) = self.signs {
+            policy = policy.signs(mapping);
+        }
+        if let Some(form) = self.jamo {
+            policy = policy.jamo(form);
+        }
+        if let Some(enabled) = self.compose_hangul {
+            policy = policy.compose_hangul(enabled);
+        }
+        if let Some(mapping) = self.wave_dash {
+            policy = policy.wave_dash(mapping);
+        }
+        if let Some(mapping) = self.minus {
+            policy = policy.minus(mapping);
+        }
+        if let Some(mapping) = self.quotes {
+            policy = policy.quotes(mapping);
+        }
+        if let Some(mapping) = self.middle_dot {
+            policy = policy.middle_dot(mapping);
+        }
+        if let Some(enabled) = self.contextual_prolonged_mark {
+            policy = policy.contextual_prolonged_mark(enabled);
+        }
+        let mut currency = CurrencyPolicy::new();
+        for symbol in CurrencySymbol::ALL {
+            if let Some(&mapping) = self.currency.get(&symbol) {
+                currency = currency.set(symbol, mapping);
+            }
+        }
+        let mut converter = WidthConverter::new(policy.currency(currency).build())
+            .mappings(self.map);
+        for protector in self.protect {
+            converter = match protector {
+                ProtectorName::Urls         => converter.protect(Urls),
+                ProtectorName::Emails       => converter.protect(Emails),
+                ProtectorName::Placeholders => converter.protect(Placeholders),
+                ProtectorName::Quotes       => converter.protect(Quotes::default()),
+            };
+        }
+        for pattern in self.patterns {
+            converter = protect_pattern(converter, &pattern)?;
+        }
+        Ok(Profile { name: self.name, converter })
+    }
+}
+
+#[cfg(feature = "regex")]
+fn protect_pattern(converter: WidthConverter, pattern: &str)
+                   -> Result<WidthConverter, ProfileError> {
+    let regex = regex::Regex::new(pattern).map_err(|e| ProfileError::Pattern(e.to_string()))?;
+    Ok(converter.protect(regex))
+}
+
+#[cfg(not(feature = "regex"))]
+fn protect_pattern(_: WidthConverter, pattern: &str) -> Result<WidthConverter, ProfileError> {
+    Err(ProfileError::Pattern(format!("'{}' requires the regex feature", pattern)))
+}
+
+/// A normalization configuration that can be kept under version control and
+/// shared between programs and the `hfwidth` tool.
+///
+/// The keys of a profile, all optional, are:
+///
+/// - `name`: a name for the profile.
+/// - `preset`: a `Preset` to start from, by its snake_case name such as
+///   `web_form`. The other keys override the options it sets.
+/// - `all`: the target for every category.
+/// - `ascii`, `kana`, `hangul`, `symbols` and `space`: the target for the
+///   group of the same name in `WidthPolicyBuilder`, overriding `all`.
+/// - `categories`: targets for single categories by their snake_case names,
+///   overriding the groups.
+/// - `ascii_punctuation`: approximates CJK punctuation with ASCII.
+/// - `signs`: maps forms of signs to `keep` or `ascii`.
+/// - `jamo`: chooses `compatibility` or `conjoining` jamo for half-width
+///   Hangul.
+/// - `compose_hangul`: composes jamo into syllables.
+/// - `wave_dash`: unifies wave dashes and full-width tildes to `wave_dash` or
+///   `fullwidth_tilde`.
+/// - `minus`: folds minus signs and hyphens to `hyphen_minus` or
+///   `minus_sign`.
+/// - `quotes`: converts full-width quotation marks to `ascii` or `curly` ones.
+/// - `middle_dot`: maps katakana middle dots to `keep`, `halfwidth`,
+///   `fullwidth`, `slash` or `hyphen`.
+/// - `contextual_prolonged_mark`: turns prolonged sound marks not following
+///   kana into hyphens.
+/// - `currency`: maps currency symbols to `keep`, `sign` or `ascii`.
+/// - `map`: maps single characters to others, overriding the rest of the
+///   profile.
+/// - `protect`: built-in protectors of spans to leave unchanged, out of
+///   `urls`, `emails`, `placeholders` and `quotes`.
+/// - `patterns`: regular expressions for spans to leave unchanged, which
+///   requires the `regex` feature.
+///
+/// Targets are `keep`, `standard`, `halfwidth` and `fullwidth`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "latin", feature = "toml"))] {
+/// use unicode_hfwidth::Profile;
+///
+/// let profile = Profile::from_toml(r#"
+///     name = "catalog"
+///     kana = "keep"
+///     protect = ["placeholders"]
+///
+///     [currency]
+///     yen = "ascii"
+/// "#).unwrap();
+/// assert_eq!(profile.name(), Some("catalog"));
+/// assert_eq!(profile.apply("ＡＢ{ｎ}ｶﾅ￥"), "AB{ｎ}ｶﾅ\\");
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Profile {
+    name: Option<String>,
+    converter: WidthConverter,
+}
+
+impl Profile {
+    /// Loads a profile from `path`, choosing the format by the extension:
+    /// `.toml`, or `.yaml` and `.yml`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Profile, ProfileError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(ProfileError::Io)?;
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        match extension {
+            #[cfg(feature = "toml")]
+            "toml"         => Profile::from_toml(&text),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Profile::from_yaml(&text),
+            _              => Err(ProfileError::UnsupportedFormat(extension.to_owned())),
+        }
+    }
+
+    /// Parses a profile in TOML.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(s: &str) -> Result<Profile, ProfileError> {
+        toml::from_str::<ProfileFile>(s).map_err(ProfileError::Toml)?.build()
+    }
+
+    /// Parses a profile in YAML.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(s: &str) -> Result<Profile, ProfileError> {
+        serde_yaml::from_str::<ProfileFile>(s).map_err(ProfileError::Yaml)?.build()
+    }
+
+    /// Returns the name given in the profile, if any.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the converter the profile describes.
+    pub fn converter(&self) -> &WidthConverter {
+        &self.converter
+    }
+
+    /// Returns the width policy of the profile.
+    pub fn policy(&self) -> &WidthPolicy {
+        self.converter.policy()
+    }
+
+    /// Converts `s` according to the profile.
+    pub fn apply<S: AsRef<str>>(&self, s: S) -> String {
+        let s = s.as_ref();
+        self.converter.convert(s)
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin", feature = "yaml"))]
+#[test]
+fn test_profile_yaml() {
+    let profile = Profile::from_yaml("
+all: fullwidth
+categories:
+  halfwidth_katakana: standard
+space: half
+protect: [quotes]
+map: {\"〜\": \"～\"}
+wave_dash: wave_dash
+").unwrap();
+    assert_eq!(profile.name(), None);
+    assert_eq!(profile.apply("ab ｶﾅ\u{3000}'cd'〜～"), "ａｂ カナ ＇cd＇～〜");
+    assert!(Profile::from_yaml("kana: sideways").is_err());
+    assert!(Profile::from_yaml("colour: red").is_err());
+    let profile = Profile::from_yaml("
+preset: web_form
+space: keep
+").unwrap();
+    assert_eq!(profile.apply("０３ー１２３４　ｶﾞｲﾄﾞ"), "03-1234\u{3000}ガイド");
+    assert!(Profile::from_yaml("preset: fullwidth").is_err());
+}
@@ -0,0 +1,123 @@
+//! Width mapping data behind a provider trait in the style of ICU4X.
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::sync::OnceLock;
+
+use super::{forms_category, to_fullwidth, to_halfwidth, to_standard_width};
+
+/// The mappings a `WidthDataProvider` can load.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize),
+           serde(rename_all = "snake_case"))]
+pub enum WidthMappingKey {
+    /// Characters to their half-width forms, like `to_halfwidth`.
+    Halfwidth,
+    /// Characters to their full-width forms, like `to_fullwidth`.
+    Fullwidth,
+    /// Characters of the "Halfwidth and Fullwidth Forms" block to their
+    /// standard-width forms, like `to_standard_width`.
+    Standard,
+}
+
+/// A width mapping loaded from a `WidthDataProvider`, as pairs of a character
+/// and the character it maps to, sorted by the first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WidthMappingsV1 {
+    /// The pairs of the mapping.
+    pub mappings: Cow<'static, [(char, char)]>,
+}
+
+impl WidthMappingsV1 {
+    /// Returns the character `ch` maps to, if any.
+    pub fn get(&self, ch: char) -> Option<char> {
+        self.mappings.binary_search_by_key(&ch, |&(from, _)| from).ok()
+            .map(|i| self.mappings[i].1)
+    }
+
+    /// Maps each character of `s`, leaving characters without a mapping
+    /// unchanged.
+    pub fn apply<S: AsRef<str>>(&self, s: S) -> String {
+        s.as_ref().chars().map(|ch| self.get(ch).unwrap_or(ch)).collect()
+    }
+}
+
+/// The error returned by a `WidthDataProvider` that has no data for a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MissingWidthData(pub WidthMappingKey);
+
+impl fmt::Display for MissingWidthData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no width mapping data for {:?}", self.0)
+    }
+}
+
+impl Error for MissingWidthData {}
+
+/// A source of width mapping data, so that applications loading their
+/// Unicode data through providers can load width mappings the same way.
+///
+/// Requires the `provider` feature.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin", feature = "provider"))] {
+/// use unicode_hfwidth::{BakedWidthProvider, WidthDataProvider, WidthMappingKey};
+///
+/// let standard = BakedWidthProvider.load(WidthMappingKey::Standard).unwrap();
+/// assert_eq!(standard.get('ｶ'), Some('カ'));
+/// assert_eq!(standard.apply("ｶﾅ ＡＢ"), "カナ AB");
+/// # }
+/// ```
+pub trait WidthDataProvider {
+    /// Loads the mapping for `key`.
+    fn load(&self, key: WidthMappingKey) -> Result<WidthMappingsV1, MissingWidthData>;
+}
+
+/// The provider of the mappings built into this crate. The mappings are
+/// built once and borrowed by every load.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BakedWidthProvider;
+
+impl WidthDataProvider for BakedWidthProvider {
+    fn load(&self, key: WidthMappingKey) -> Result<WidthMappingsV1, MissingWidthData> {
+        static HALFWIDTH: OnceLock<Vec<(char, char)>> = OnceLock::new();
+        static FULLWIDTH: OnceLock<Vec<(char, char)>> = OnceLock::new();
+        static STANDARD: OnceLock<Vec<(char, char)>> = OnceLock::new();
+        let mappings = match key {
+            WidthMappingKey::Halfwidth => HALFWIDTH.get_or_init(|| baked(to_halfwidth)),
+            WidthMappingKey::Fullwidth => FULLWIDTH.get_or_init(|| baked(to_fullwidth)),
+            WidthMappingKey::Standard  => STANDARD.get_or_init(|| baked(to_standard_width)),
+        };
+        Ok(WidthMappingsV1 { mappings: Cow::Borrowed(mappings) })
+    }
+}
+
+/// Returns the pairs `map` gives for the characters of the "Halfwidth and
+/// Fullwidth Forms" block and their counterparts, sorted.
+fn baked(map: fn(char) -> Option<char>) -> Vec<(char, char)> {
+    let mut mappings: Vec<(char, char)> = ('\u{ff00}'..='\u{ffef}')
+        .filter(|&ch| forms_category(ch).is_some())
+        .flat_map(|ch| [Some(ch), to_standard_width(ch)])
+        .flatten()
+        .filter_map(|ch| Some((ch, map(ch)?)))
+        .collect();
+    mappings.sort_unstable();
+    mappings.dedup();
+    mappings
+}
+
+#[test]
+fn test_baked_provider() {
+    let keys = [WidthMappingKey::Halfwidth, WidthMappingKey::Fullwidth, WidthMappingKey::Standard];
+    let maps: [fn(char) -> Option<char>; 3] = [to_halfwidth, to_fullwidth, to_standard_width];
+    for (key, map) in keys.into_iter().zip(maps) {
+        let data = BakedWidthProvider.load(key).unwrap();
+        assert!(matches!(data.mappings, Cow::Borrowed(_)));
+        for ch in '\u{0}'..='\u{ffff}' {
+            assert_eq!(data.get(ch), map(ch), "{:?} {:?}", key, ch);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Python bindings.
+//!
+//! Enabled by the `python` feature. Build an extension module with
+//! `cargo rustc --release --lib --features python,pyo3/extension-module --crate-type cdylib`
+//! and install the library as `unicode_hfwidth.so` (`unicode_hfwidth.pyd` on Windows).
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use super::{WidthPolicy, WidthTarget};
+
+fn parse_target(name: &str, value: &str) -> PyResult<WidthTarget> {
+    value.parse()
+        .map_err(|_| PyValueError::new_err(format!("invalid value '{}' for {}", value, name)))
+}
+
+/// Converts `s` to half-width wherever a half-width form exists.
+#[pyfunction]
+fn to_halfwidth(s: &str) -> String {
+    WidthPolicy::builder().all(WidthTarget::Halfwidth).build().convert(s)
+}
+
+/// Converts `s` to full-width wherever a full-width form exists.
+#[pyfunction]
+fn to_fullwidth(s: &str) -> String {
+    WidthPolicy::builder().all(WidthTarget::Fullwidth).build().convert(s)
+}
+
+/// Converts `s` according to a policy. Each keyword takes "keep", "standard",
+/// "half" or "full", or "halfwidth" or "fullwidth"; unset categories are
+/// converted to standard width.
+#[pyfunction]
+#[pyo3(signature = (s, *, ascii=None, kana=None, hangul=None, symbols=None, space=None))]
+fn convert(s: &str, ascii: Option<&str>, kana: Option<&str>, hangul: Option<&str>,
+           symbols: Option<&str>, space: Option<&str>) -> PyResult<String> {
+    let mut policy = WidthPolicy::builder();
+    if let Some(value) = ascii {
+        policy = policy.ascii(parse_target("ascii", value)?);
+    }
+    if let Some(value) = kana {
+        policy = policy.kana(parse_target("kana", value)?);
+    }
+    if let Some(value) = hangul {
+        policy = policy.hangul(parse_target("hangul", value)?);
+    }
+    if let Some(value) = symbols {
+        policy = policy.symbols(parse_target("symbols", value)?);
+    }
+    if let Some(value) = space {
+        policy = policy.space(parse_target("space", value)?);
+    }
+    Ok(policy.build().convert(s))
+}
+
+/// Returns the number of terminal columns `s` occupies.
+#[pyfunction]
+fn str_width(s: &str) -> usize {
+    super::str_width(s)
+}
+
+#[pymodule]
+fn unicode_hfwidth(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(to_halfwidth, m)?)?;
+    m.add_function(wrap_pyfunction!(to_fullwidth, m)?)?;
+    m.add_function(wrap_pyfunction!(convert, m)?)?;
+    m.add_function(wrap_pyfunction!(str_width, m)?)?;
+    Ok(())
+}
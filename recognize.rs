@@ -0,0 +1,88 @@
+//! Recognizers of width-variant tokens for hand-written parsers.
+//!
+//! Each recognizer matches the longest non-empty run of its characters at the
+//! start of the input and returns the rest of the input and the matched
+//! slice, in the order `nom` parsers do, or `None` if the input doesn't start
+//! with one of its characters.
+
+use super::policy::is_digit;
+use super::{is_fullwidth_digit, is_fullwidth_latin, is_halfwidth_jamo, is_halfwidth_katakana};
+
+/// Returns the rest of `input` and the run of characters matching `f` at its
+/// start, if the run is not empty.
+fn take_while1<F: Fn(char) -> bool>(input: &str, f: F) -> Option<(&str, &str)> {
+    let end = input.find(|ch| !f(ch)).unwrap_or(input.len());
+    if end == 0 {
+        return None;
+    }
+    let (matched, rest) = input.split_at(end);
+    Some((rest, matched))
+}
+
+/// Recognizes full-width digits, `０` to `９`.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::take_fullwidth_digits("１２３円"), Some(("円", "１２３")));
+/// assert_eq!(unicode_hfwidth::take_fullwidth_digits("123"), None);
+/// ```
+pub fn take_fullwidth_digits(input: &str) -> Option<(&str, &str)> {
+    take_while1(input, is_fullwidth_digit)
+}
+
+/// Recognizes ASCII and full-width digits, which documents often mix.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::take_digits_any_width("１2３-4"), Some(("-4", "１2３")));
+/// ```
+pub fn take_digits_any_width(input: &str) -> Option<(&str, &str)> {
+    take_while1(input, is_digit)
+}
+
+/// Recognizes full-width Latin letters, `Ａ` to `Ｚ` and `ａ` to `ｚ`.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::take_fullwidth_latin("ＡＢＣ１"), Some(("１", "ＡＢＣ")));
+/// ```
+pub fn take_fullwidth_latin(input: &str) -> Option<(&str, &str)> {
+    take_while1(input, is_fullwidth_latin)
+}
+
+/// Recognizes half-width katakana, including the middle dot, prolonged sound
+/// mark and sound marks.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::take_halfwidth_katakana("ﾄｳｷｮｳﾄ 千代田区"),
+///            Some((" 千代田区", "ﾄｳｷｮｳﾄ")));
+/// ```
+pub fn take_halfwidth_katakana(input: &str) -> Option<(&str, &str)> {
+    take_while1(input, is_halfwidth_katakana)
+}
+
+/// Recognizes half-width Hangul jamo.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::take_halfwidth_hangul("ﾡﾢ ㄱ"), Some((" ㄱ", "ﾡﾢ")));
+/// ```
+pub fn take_halfwidth_hangul(input: &str) -> Option<(&str, &str)> {
+    take_while1(input, is_halfwidth_jamo)
+}
+
+#[test]
+fn test_recognizers() {
+    /* An invoice line: a half-width katakana name, then an amount */
+    let line = "ﾔﾏﾀﾞ ｼｮｳｼﾞ １２,３４5円";
+    let (rest, name) = take_halfwidth_katakana(line).unwrap();
+    assert_eq!(name, "ﾔﾏﾀﾞ");
+    let (rest, _) = take_halfwidth_katakana(rest.trim_start()).unwrap();
+    let (rest, thousands) = take_fullwidth_digits(rest.trim_start()).unwrap();
+    let (rest, units) = take_digits_any_width(rest.strip_prefix(',').unwrap()).unwrap();
+    assert_eq!((thousands, units, rest), ("１２", "３４5", "円"));
+    assert_eq!(take_fullwidth_digits(""), None);
+    assert_eq!(take_fullwidth_latin("ａ"), Some(("", "ａ")));
+    assert_eq!(take_halfwidth_hangul("ﾡ"), Some(("", "ﾡ")));
+}
@@ -0,0 +1,103 @@
+//! Per-column conversion of CSV records.
+
+use csv::StringRecord;
+
+use super::WidthPolicy;
+
+/// Converts the fields of CSV records with a policy chosen per column.
+///
+/// Columns are selected by index or, once the header record is known through
+/// `bind_headers`, by name. Columns without a policy are left as they are,
+/// unless `others` sets one for them.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// use unicode_hfwidth::{CsvTransformer, WidthPolicy, WidthTarget};
+///
+/// let data = "code,name\nＡ１,ﾔﾏﾀﾞ\n";
+/// let mut reader = csv::Reader::from_reader(data.as_bytes());
+/// let mut transformer = CsvTransformer::new()
+///     .header("code", WidthPolicy::default())
+///     .header("name", WidthPolicy::builder().kana(WidthTarget::Halfwidth).build());
+/// transformer.bind_headers(reader.headers().unwrap());
+/// let record = reader.records().next().unwrap().unwrap();
+/// assert_eq!(transformer.transform(&record), vec!["A1", "ﾔﾏﾀﾞ"]);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CsvTransformer {
+    by_index: Vec<(usize, WidthPolicy)>,
+    by_name: Vec<(String, WidthPolicy)>,
+    bound: Vec<(usize, WidthPolicy)>,
+    others: Option<WidthPolicy>,
+}
+
+impl CsvTransformer {
+    /// Creates a transformer leaving every column as it is.
+    pub fn new() -> CsvTransformer {
+        CsvTransformer::default()
+    }
+
+    /// Converts the column at the 0-based `index` with `policy`.
+    pub fn column(mut self, index: usize, policy: WidthPolicy) -> CsvTransformer {
+        self.by_index.push((index, policy));
+        self
+    }
+
+    /// Converts the column named `name` in the header record with `policy`.
+    pub fn header(mut self, name: &str, policy: WidthPolicy) -> CsvTransformer {
+        self.by_name.push((name.to_owned(), policy));
+        self
+    }
+
+    /// Converts the columns without a policy of their own with `policy`.
+    pub fn others(mut self, policy: WidthPolicy) -> CsvTransformer {
+        self.others = Some(policy);
+        self
+    }
+
+    /// Resolves the columns selected by name against `headers`. Names not
+    /// found in `headers` select no column.
+    pub fn bind_headers(&mut self, headers: &StringRecord) {
+        self.bound = self.by_name.iter().filter_map(|(name, policy)| {
+            headers.iter().position(|header| header == name).map(|i| (i, policy.clone()))
+        }).collect();
+    }
+
+    /// Returns the policy applied to the column at `index`, if any. Columns
+    /// selected by index take precedence over those selected by name.
+    pub fn policy(&self, index: usize) -> Option<&WidthPolicy> {
+        self.by_index.iter().chain(&self.bound)
+            .find(|(i, _)| *i == index)
+            .map(|(_, policy)| policy)
+            .or(self.others.as_ref())
+    }
+
+    /// Returns `record` with its fields converted.
+    pub fn transform(&self, record: &StringRecord) -> StringRecord {
+        record.iter().enumerate().map(|(i, field)| match self.policy(i) {
+            Some(policy) => policy.convert(field),
+            None         => field.to_owned(),
+        }).collect()
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_csv_pipeline() {
+    let data = "id,name,note\nＡ１,ﾔﾏﾀﾞ,ﾒﾓ\n";
+    let mut reader = csv::Reader::from_reader(data.as_bytes());
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut transformer = CsvTransformer::new()
+        .column(0, WidthPolicy::default())
+        .header("name", WidthPolicy::default())
+        .header("missing", WidthPolicy::default());
+    transformer.bind_headers(reader.headers().unwrap());
+    writer.write_record(reader.headers().unwrap()).unwrap();
+    for record in reader.records() {
+        writer.write_record(&transformer.transform(&record.unwrap())).unwrap();
+    }
+    let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+    assert_eq!(output, "id,name,note\nA1,ヤマダ,ﾒﾓ\n");
+}
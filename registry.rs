@@ -0,0 +1,85 @@
+//! Width policies looked up by name.
+
+use std::collections::HashMap;
+
+use super::{Preset, WidthPolicy};
+
+/// Named width policies, registered when an application starts and looked up
+/// by name afterwards, for example from per-tenant configuration.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::{PolicyRegistry, WidthPolicy, WidthTarget};
+///
+/// let mut registry = PolicyRegistry::with_presets();
+/// registry.register("tenant-a", WidthPolicy::builder().kana(WidthTarget::Keep).build());
+/// assert_eq!(registry.convert("tenant-a", "ＡＢｶﾅ").unwrap(), "ABｶﾅ");
+/// assert_eq!(registry.convert("web_form", "ＡＢｶﾅ").unwrap(), "ABカナ");
+/// assert_eq!(registry.convert("tenant-b", "ＡＢｶﾅ"), None);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PolicyRegistry {
+    policies: HashMap<String, WidthPolicy>,
+}
+
+impl PolicyRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> PolicyRegistry {
+        PolicyRegistry::default()
+    }
+
+    /// Creates a registry with the policies of every `Preset`, registered
+    /// under their names.
+    pub fn with_presets() -> PolicyRegistry {
+        let mut registry = PolicyRegistry::new();
+        for preset in Preset::ALL {
+            registry.register(preset.name(), preset.policy());
+        }
+        registry
+    }
+
+    /// Registers `policy` under `name`, returning the policy it replaces, if
+    /// any.
+    pub fn register<S: Into<String>>(&mut self, name: S, policy: WidthPolicy)
+                                     -> Option<WidthPolicy> {
+        self.policies.insert(name.into(), policy)
+    }
+
+    /// Removes the policy registered under `name` and returns it.
+    pub fn unregister(&mut self, name: &str) -> Option<WidthPolicy> {
+        self.policies.remove(name)
+    }
+
+    /// Returns the policy registered under `name`.
+    pub fn get(&self, name: &str) -> Option<&WidthPolicy> {
+        self.policies.get(name)
+    }
+
+    /// Returns the names of the registered policies, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.policies.keys().map(String::as_str)
+    }
+
+    /// Converts `s` with the policy registered under `name`, or returns
+    /// `None` if there is none.
+    pub fn convert<S: AsRef<str>>(&self, name: &str, s: S) -> Option<String> {
+        self.get(name).map(|policy| policy.convert(s))
+    }
+}
+
+#[cfg(feature = "kana")]
+#[test]
+fn test_policy_registry() {
+    let mut registry = PolicyRegistry::new();
+    assert!(registry.register("a", WidthPolicy::default()).is_none());
+    assert!(registry.register("a", Preset::LegacyBank.policy()).is_some());
+    assert_eq!(registry.convert("a", "カナ").unwrap(), "ｶﾅ");
+    assert_eq!(registry.names().collect::<Vec<_>>(), ["a"]);
+    assert_eq!(registry.unregister("a"), Some(Preset::LegacyBank.policy()));
+    assert!(registry.get("a").is_none());
+    let mut names: Vec<_> = PolicyRegistry::with_presets().names().map(str::to_owned).collect();
+    names.sort();
+    assert_eq!(names, ["legacy_bank", "search_fold", "strict", "web_form"]);
+}
@@ -0,0 +1,108 @@
+//! Reports of the changes made by a conversion.
+
+use std::ops::Range;
+
+use super::pretokenize::width_pieces;
+use super::{pair_category, FormsCategory, WidthPolicy};
+
+/// The changes made by `WidthPolicy::convert_with_report`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::{FormsCategory, WidthPolicy};
+///
+/// let (converted, report) = WidthPolicy::default().convert_with_report("ＡＢ ｶﾞｲﾄﾞ");
+/// assert_eq!(converted, "AB ガイド");
+/// assert_eq!(report.count(FormsCategory::FullwidthAscii), 2);
+/// assert_eq!(report.count(FormsCategory::HalfwidthKatakana), 5);
+/// assert_eq!(report.changed(), [0..6, 7..22]);
+/// assert_eq!(report.compositions(), 2);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConversionReport {
+    /// Converted characters per category, indexed like `FormsCategory::ALL`,
+    /// followed by characters outside the block.
+    counts: [usize; 8],
+    changed: Vec<Range<usize>>,
+    compositions: usize,
+}
+
+impl ConversionReport {
+    /// Returns the number of converted characters of `category`, counting
+    /// characters converted into the category as well as out of it.
+    pub fn count(&self, category: FormsCategory) -> usize {
+        self.counts[category as usize]
+    }
+
+    /// Returns the number of converted characters outside the block, such as
+    /// spaces and currency symbols.
+    pub fn other(&self) -> usize {
+        self.counts[7]
+    }
+
+    /// Returns the total number of converted characters.
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// Returns the byte ranges of the input that changed, sorted and merged
+    /// where adjacent.
+    pub fn changed(&self) -> &[Range<usize>] {
+        &self.changed
+    }
+
+    /// Returns the number of kana composed with a following voiced sound
+    /// mark.
+    pub fn compositions(&self) -> usize {
+        self.compositions
+    }
+
+    /// Returns `true` if any kana was composed with a voiced sound mark.
+    pub fn composed(&self) -> bool {
+        self.compositions > 0
+    }
+}
+
+impl WidthPolicy {
+    /// Converts `s` according to the policy, like `convert`, and reports the
+    /// changes made.
+    pub fn convert_with_report<S: AsRef<str>>(&self, s: S) -> (String, ConversionReport) {
+        let s = s.as_ref();
+        let mut out = String::with_capacity(s.len());
+        let mut report = ConversionReport::default();
+        for (converted, range) in width_pieces(s, self) {
+            out.push(converted);
+            let original = &s[range.clone()];
+            let mut chars = original.chars();
+            if chars.next() == Some(converted) && chars.next().is_none() {
+                continue;
+            }
+            for ch in original.chars().filter(|&ch| self.convert_char(ch) != ch) {
+                report.counts[pair_category(ch).map_or(7, |category| category as usize)] += 1;
+            }
+            if original.chars().nth(1).is_some() {
+                report.compositions += 1;
+            }
+            match report.changed.last_mut() {
+                Some(last) if last.end == range.start => last.end = range.end,
+                _                                     => report.changed.push(range),
+            }
+        }
+        (out, report)
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin", feature = "symbols"))]
+#[test]
+fn test_conversion_report() {
+    let policy = WidthPolicy::builder().space(super::WidthTarget::Halfwidth).build();
+    let (converted, report) = policy.convert_with_report("か\u{ff9e}\u{3000}ｈx￦");
+    assert_eq!(converted, "が hx₩");
+    assert_eq!(converted, policy.convert("か\u{ff9e}\u{3000}ｈx￦"));
+    assert_eq!(report.changed(), [0..12, 13..16]);
+    assert_eq!((report.count(FormsCategory::HalfwidthKatakana), report.other()), (1, 1));
+    assert_eq!(report.total(), 4);
+    assert!(report.composed());
+}
@@ -0,0 +1,100 @@
+//! Conversion that can be undone.
+
+use std::ops::Range;
+
+use super::pretokenize::width_pieces;
+use super::WidthPolicy;
+
+/// The original text of the parts of a string changed by conversion, for
+/// restoring the original with `RestoreMap::restore`.
+///
+/// With the `serde` feature, the map can be serialized and stored next to
+/// the converted text.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RestoreMap {
+    /// Ranges of the converted text with the original text they replaced.
+    changes: Vec<(Range<usize>, String)>,
+    len: usize,
+}
+
+impl RestoreMap {
+    /// Returns the original text of the converted `s`. If `s` is not the text
+    /// the map was made for, returns `None`.
+    pub fn restore<S: AsRef<str>>(&self, s: S) -> Option<String> {
+        let s = s.as_ref();
+        if s.len() != self.len {
+            return None;
+        }
+        let mut out = String::with_capacity(s.len());
+        let mut pos = 0;
+        for (range, original) in &self.changes {
+            out.push_str(s.get(pos..range.start)?);
+            s.get(range.clone())?;
+            out.push_str(original);
+            pos = range.end;
+        }
+        out.push_str(s.get(pos..)?);
+        Some(out)
+    }
+
+    /// Returns `true` if conversion changed nothing.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl WidthPolicy {
+    /// Converts `s` according to the policy, like `convert`, and returns a
+    /// map to restore `s` from the result.
+    pub fn convert_reversible<S: AsRef<str>>(&self, s: S) -> (String, RestoreMap) {
+        let s = s.as_ref();
+        let mut out = String::with_capacity(s.len());
+        let mut map = RestoreMap::default();
+        for (converted, range) in width_pieces(s, self) {
+            let start = out.len();
+            out.push(converted);
+            let original = &s[range];
+            if original.len() == converted.len_utf8() && original.starts_with(converted) {
+                continue;
+            }
+            match map.changes.last_mut() {
+                Some((last, text)) if last.end == start => {
+                    last.end = out.len();
+                    text.push_str(original);
+                }
+                _ => map.changes.push((start..out.len(), original.to_owned())),
+            }
+        }
+        map.len = out.len();
+        (out, map)
+    }
+}
+
+/// Converts the characters of `s` that have a standard-width form to it, and
+/// returns a map to restore `s` from the result.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// let (normalized, map) = unicode_hfwidth::normalize_reversible("ｶﾞｲﾄﾞ ＡＢ");
+/// assert_eq!(normalized, "ガイド AB");
+/// assert_eq!(map.restore(&normalized).unwrap(), "ｶﾞｲﾄﾞ ＡＢ");
+/// # }
+/// ```
+pub fn normalize_reversible<S: AsRef<str>>(s: S) -> (String, RestoreMap) {
+    let s = s.as_ref();
+    WidthPolicy::default().convert_reversible(s)
+}
+
+#[test]
+fn test_restore() {
+    let s = "ﾃﾞｰﾀ and ＤＡＴＡ, が\u{ff9e}";
+    let (normalized, map) = normalize_reversible(s);
+    assert_eq!(normalized, WidthPolicy::default().convert(s));
+    assert_eq!(map.restore(&normalized).as_deref(), Some(s));
+    assert_eq!(map.restore("short"), None);
+    let (same, map) = normalize_reversible("plain");
+    assert!(map.is_empty());
+    assert_eq!(map.restore(&same).unwrap(), "plain");
+}
@@ -0,0 +1,123 @@
+//! Conversion into reused thread-local buffers.
+
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use super::policy::Context;
+use super::{contains_nonstandard_width, WidthPolicy};
+
+/// Capacity above which the buffer is shrunk after use, so a single large
+/// string doesn't keep its memory held by the thread.
+const MAX_KEPT_CAPACITY: usize = 64 * 1024;
+
+thread_local! {
+    static BUFFER: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+impl WidthPolicy {
+    /// Converts `s` according to the policy into a buffer reused by later
+    /// calls on the same thread, and passes the result to `f`. After the
+    /// first calls, converting strings of similar length doesn't allocate.
+    ///
+    /// A call made from within `f` converts into a new buffer.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(all(feature = "kana", feature = "latin"))] {
+    /// use unicode_hfwidth::WidthPolicy;
+    ///
+    /// let policy = WidthPolicy::default();
+    /// let lengths: Vec<_> = ["ＡＢ", "ｶﾞ"].iter()
+    ///     .map(|s| policy.convert_reuse(s, |converted| converted.len()))
+    ///     .collect();
+    /// assert_eq!(lengths, [2, 3]);
+    /// # }
+    /// ```
+    pub fn convert_reuse<R, F: FnOnce(&str) -> R>(&self, s: &str, f: F) -> R {
+        BUFFER.with(|buffer| match buffer.try_borrow_mut() {
+            Ok(mut buffer) => {
+                buffer.clear();
+                let mut cx = Context::default();
+                for ch in s.chars() {
+                    self.push_char(ch, &mut cx, &mut buffer);
+                }
+                cx.flush(&mut *buffer);
+                let result = f(&buffer);
+                if buffer.capacity() > MAX_KEPT_CAPACITY {
+                    buffer.clear();
+                    buffer.shrink_to(MAX_KEPT_CAPACITY);
+                }
+                result
+            }
+            Err(_) => f(&self.convert(s)),
+        })
+    }
+}
+
+/// Converts `s` to standard width, like the default `WidthPolicy`, and passes
+/// the result to `f`, reusing a thread-local buffer as
+/// `WidthPolicy::convert_reuse` does. Strings already in standard width are
+/// passed to `f` as they are.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// let key = unicode_hfwidth::to_standard_width_str_reuse("ＩＤ-ｶﾅ", |s| s == "ID-カナ");
+/// assert!(key);
+/// # }
+/// ```
+pub fn to_standard_width_str_reuse<R, F: FnOnce(&str) -> R>(s: &str, f: F) -> R {
+    if contains_nonstandard_width(s) {
+        WidthPolicy::default().convert_reuse(s, f)
+    } else {
+        f(s)
+    }
+}
+
+/// Converts `s` according to `policy`, returning `s` itself rather than a
+/// copy if the conversion doesn't change it, for caches of interned strings
+/// normalizing their keys. The conversion goes through the reused buffer of
+/// `WidthPolicy::convert_reuse`, so unchanged strings are not allocated.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "kana")] {
+/// use std::sync::Arc;
+/// use unicode_hfwidth::WidthPolicy;
+///
+/// let policy = WidthPolicy::default();
+/// let key: Arc<str> = Arc::from("カタカナ");
+/// assert!(Arc::ptr_eq(&unicode_hfwidth::normalize_arc(key.clone(), &policy), &key));
+/// assert_eq!(&*unicode_hfwidth::normalize_arc(Arc::from("ｶﾀｶﾅ"), &policy), "カタカナ");
+/// # }
+/// ```
+pub fn normalize_arc(s: Arc<str>, policy: &WidthPolicy) -> Arc<str> {
+    let converted = policy.convert_reuse(&s, |converted| {
+        if converted == &*s { None } else { Some(Arc::from(converted)) }
+    });
+    converted.unwrap_or(s)
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_convert_reuse() {
+    let policy = WidthPolicy::default();
+    let nested = policy.convert_reuse("ｶﾞＡ", |outer| {
+        let inner = to_standard_width_str_reuse("ﾊﾟＢ", str::to_owned);
+        format!("{}{}", outer, inner)
+    });
+    assert_eq!(nested, "ガAパB");
+    let long = "Ａ".repeat(MAX_KEPT_CAPACITY);
+    assert_eq!(policy.convert_reuse(&long, str::len), MAX_KEPT_CAPACITY);
+    assert!(BUFFER.with(|buffer| buffer.borrow().capacity()) <= MAX_KEPT_CAPACITY);
+}
+
+#[cfg(feature = "latin")]
+#[test]
+fn test_normalize_arc() {
+    let policy = WidthPolicy::builder().ascii(super::WidthTarget::Fullwidth).build();
+    let key: Arc<str> = Arc::from("ＩＤ");
+    assert!(Arc::ptr_eq(&normalize_arc(key.clone(), &policy), &key));
+    assert_eq!(&*normalize_arc(key, &WidthPolicy::default()), "ID");
+    assert_eq!(&*normalize_arc(Arc::from("id"), &policy), "ｉｄ");
+}
@@ -0,0 +1,184 @@
+//! Fast scanning for characters of non-standard width.
+
+use std::iter::FusedIterator;
+
+use super::{forms_category, FormsCategory};
+
+/// Returns the byte index of the first character of `bytes` in the Unicode
+/// "Halfwidth and Fullwidth Forms" block, which `bytes` encodes in UTF-8.
+///
+/// Every such character is encoded as `EF BC..BF xx`, so only positions of
+/// the byte `EF` need a closer look.
+fn find_nonstandard(bytes: &[u8]) -> Option<usize> {
+    let mut from = 0;
+    while let Some(found) = bytes[from..].iter().position(|&b| b == 0xef) {
+        let i = from + found;
+        match bytes.get(i + 1..i + 3) {
+            Some(&[0xbc..=0xbe, _]) | Some(&[0xbf, ..=0xae]) => return Some(i),
+            _                                             => from = i + 1,
+        }
+    }
+    None
+}
+
+/// Returns `true` if `s` contains a character in the Unicode "Halfwidth and
+/// Fullwidth Forms" block, like `s.chars().any(is_nonstandard_width)` but
+/// without decoding characters. ASCII and most other text is skipped a byte
+/// at a time, looking for the lead byte `EF`.
+///
+/// # Example
+/// ```rust
+/// assert!(unicode_hfwidth::contains_nonstandard_width("order no. １２３"));
+/// assert!(!unicode_hfwidth::contains_nonstandard_width("order no. 123"));
+/// ```
+pub fn contains_nonstandard_width<S: AsRef<str>>(s: S) -> bool {
+    let s = s.as_ref();
+    find_nonstandard(s.as_bytes()).is_some()
+}
+
+/// Returns `true` if `s` contains a character of `category`.
+///
+/// Only the sequences `EF BC..BF xx` following an `EF` byte are decoded.
+fn contains_category(s: &str, category: FormsCategory) -> bool {
+    let bytes = s.as_bytes();
+    let mut from = 0;
+    while let Some(found) = bytes[from..].iter().position(|&b| b == 0xef) {
+        let i = from + found;
+        if let Some(&[b1 @ 0xbc..=0xbf, b2]) = bytes.get(i + 1..i + 3) {
+            let code = 0xf000 | (b1 as u32 & 0x3f) << 6 | (b2 as u32 & 0x3f);
+            if char::from_u32(code).and_then(forms_category) == Some(category) {
+                return true;
+            }
+        }
+        from = i + 1;
+    }
+    false
+}
+
+/// Returns `true` if `s` contains full-width ASCII variants, U+FF01 to
+/// U+FF5E.
+///
+/// # Example
+/// ```rust
+/// assert!(unicode_hfwidth::has_fullwidth_ascii("ｐｒｉｃｅ: 10"));
+/// assert!(!unicode_hfwidth::has_fullwidth_ascii("ｶﾀｶﾅ"));
+/// ```
+pub fn has_fullwidth_ascii<S: AsRef<str>>(s: S) -> bool {
+    let s = s.as_ref();
+    contains_category(s, FormsCategory::FullwidthAscii)
+}
+
+/// Returns `true` if `s` contains half-width katakana, U+FF65 to U+FF9F.
+///
+/// # Example
+/// ```rust
+/// assert!(unicode_hfwidth::has_halfwidth_katakana("ｶﾀｶﾅ"));
+/// assert!(!unicode_hfwidth::has_halfwidth_katakana("カタカナ｡"));
+/// ```
+pub fn has_halfwidth_katakana<S: AsRef<str>>(s: S) -> bool {
+    let s = s.as_ref();
+    contains_category(s, FormsCategory::HalfwidthKatakana)
+}
+
+/// Returns `true` if `s` contains half-width Hangul, U+FFA0 to U+FFDC.
+///
+/// # Example
+/// ```rust
+/// assert!(unicode_hfwidth::has_halfwidth_hangul("ﾾￂ"));
+/// assert!(!unicode_hfwidth::has_halfwidth_hangul("한"));
+/// ```
+pub fn has_halfwidth_hangul<S: AsRef<str>>(s: S) -> bool {
+    let s = s.as_ref();
+    contains_category(s, FormsCategory::HalfwidthHangul)
+}
+
+/// Returns the byte index and the first character of `s` in the Unicode
+/// "Halfwidth and Fullwidth Forms" block, if any.
+///
+/// # Example
+/// ```rust
+/// assert_eq!(unicode_hfwidth::first_nonstandard("カナｶﾅ"), Some((6, 'ｶ')));
+/// assert_eq!(unicode_hfwidth::first_nonstandard("カナ"), None);
+/// ```
+pub fn first_nonstandard<S: AsRef<str>>(s: S) -> Option<(usize, char)> {
+    let s = s.as_ref();
+    find_nonstandard(s.as_bytes()).map(|i| (i, s[i..].chars().next().unwrap()))
+}
+
+/// Returns an iterator over the byte indices and characters of `s` in the
+/// Unicode "Halfwidth and Fullwidth Forms" block.
+///
+/// # Example
+/// ```rust
+/// let found: Vec<_> = unicode_hfwidth::all_nonstandard("aＡbｂ").collect();
+/// assert_eq!(found, [(1, 'Ａ'), (5, 'ｂ')]);
+/// ```
+pub fn all_nonstandard(s: &str) -> AllNonstandard<'_> {
+    AllNonstandard { s, pos: 0 }
+}
+
+/// Returns an iterator over the byte indices, characters and sub-blocks of
+/// the assigned characters of `s` in the Unicode "Halfwidth and Fullwidth
+/// Forms" block.
+///
+/// # Example
+/// ```rust
+/// use unicode_hfwidth::FormsCategory;
+///
+/// let found: Vec<_> = unicode_hfwidth::nonstandard_chars("ＩＤ: ﾃｽﾄ").collect();
+/// assert_eq!(found[0], (0, 'Ｉ', FormsCategory::FullwidthAscii));
+/// assert_eq!(found[2], (8, 'ﾃ', FormsCategory::HalfwidthKatakana));
+/// assert_eq!(found.len(), 5);
+/// ```
+pub fn nonstandard_chars(s: &str) -> impl Iterator<Item = (usize, char, FormsCategory)> + '_ {
+    all_nonstandard(s).filter_map(|(i, ch)| forms_category(ch).map(|category| (i, ch, category)))
+}
+
+/// Iterator returned by `all_nonstandard`.
+#[derive(Clone, Debug)]
+pub struct AllNonstandard<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl Iterator for AllNonstandard<'_> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        let (i, ch) = first_nonstandard(&self.s[self.pos..])?;
+        let index = self.pos + i;
+        self.pos = index + ch.len_utf8();
+        Some((index, ch))
+    }
+}
+
+impl FusedIterator for AllNonstandard<'_> {}
+
+#[test]
+fn test_scan_matches_chars() {
+    let s = "\u{feff}a\u{ff00}\u{ffee}\u{ffef}\u{fff0}\u{fe30}ｶ😀\u{ffe8}";
+    let expected: Vec<_> = s.char_indices()
+        .filter(|&(_, ch)| super::is_nonstandard_width(ch))
+        .collect();
+    assert_eq!(all_nonstandard(s).collect::<Vec<_>>(), expected);
+    assert_eq!(first_nonstandard(s), expected.first().copied());
+    assert_eq!(first_nonstandard("\u{ffef}"), None);
+    let categories: Vec<_> = nonstandard_chars(s).map(|(i, _, category)| (i, category)).collect();
+    assert_eq!(categories, [(7, FormsCategory::HalfwidthSymbols),
+                            (19, FormsCategory::HalfwidthKatakana),
+                            (26, FormsCategory::HalfwidthSymbols)]);
+    assert!(contains_nonstandard_width(s));
+    assert!(!contains_nonstandard_width("\u{feff}\u{ffef}\u{fff0}\u{ef00}"));
+}
+
+#[test]
+fn test_has_category() {
+    let edges = ["\u{ff00}", "\u{ff01}", "\u{ff5e}", "\u{ff5f}", "\u{ff64}", "\u{ff65}",
+                 "\u{ff9f}", "\u{ffa0}", "\u{ffbf}", "\u{ffdc}", "\u{ffdd}", "\u{ef00}"];
+    for s in edges {
+        let category = s.chars().next().and_then(forms_category);
+        assert_eq!(has_fullwidth_ascii(s), category == Some(FormsCategory::FullwidthAscii));
+        assert_eq!(has_halfwidth_katakana(s), category == Some(FormsCategory::HalfwidthKatakana));
+        assert_eq!(has_halfwidth_hangul(s), category == Some(FormsCategory::HalfwidthHangul));
+    }
+}
@@ -0,0 +1,84 @@
+//! Predicates for the characters of single scripts.
+
+use super::{forms_category, halfwidth_chars, FormsCategory};
+
+/// Checks if `ch` is a half-width katakana character, including the half-width
+/// middle dot, prolonged sound mark and sound marks, U+FF65 to U+FF9F.
+///
+/// # Example
+/// ```rust
+/// assert!(unicode_hfwidth::is_halfwidth_katakana('ｶ'));
+/// assert!(unicode_hfwidth::is_halfwidth_katakana('ﾞ'));
+/// assert!(!unicode_hfwidth::is_halfwidth_katakana('カ'));
+/// ```
+pub fn is_halfwidth_katakana(ch: char) -> bool {
+    matches!(ch, '\u{ff65}'..='\u{ff9f}')
+}
+
+/// Checks if `ch` is a full-width katakana character that half-width katakana
+/// can represent, alone or followed by a sound mark, such as `カ`, `ガ` and
+/// `ー`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "kana")] {
+/// assert!(unicode_hfwidth::is_fullwidth_katakana_compatible('ガ'));
+/// assert!(!unicode_hfwidth::is_fullwidth_katakana_compatible('ヶ'));
+/// assert!(!unicode_hfwidth::is_fullwidth_katakana_compatible('ｶ'));
+/// # }
+/// ```
+pub fn is_fullwidth_katakana_compatible(ch: char) -> bool {
+    matches!(ch, '\u{30a1}'..='\u{30ff}') && is_halfwidth_katakana(halfwidth_chars(ch)[0])
+}
+
+/// Checks if `ch` is a half-width Hangul jamo, including the half-width filler.
+///
+/// # Example
+/// ```rust
+/// assert!(unicode_hfwidth::is_halfwidth_jamo('ﾡ'));
+/// assert!(!unicode_hfwidth::is_halfwidth_jamo('ㄱ'));
+/// ```
+pub fn is_halfwidth_jamo(ch: char) -> bool {
+    forms_category(ch) == Some(FormsCategory::HalfwidthHangul)
+}
+
+/// Checks if `ch` is a full-width Latin letter, `Ａ` to `Ｚ` or `ａ` to `ｚ`.
+///
+/// # Example
+/// ```rust
+/// assert!(unicode_hfwidth::is_fullwidth_latin('Ａ'));
+/// assert!(!unicode_hfwidth::is_fullwidth_latin('１'));
+/// ```
+pub fn is_fullwidth_latin(ch: char) -> bool {
+    matches!(ch, 'Ａ'..='Ｚ' | 'ａ'..='ｚ')
+}
+
+/// Checks if `ch` is a full-width digit, `０` to `９`.
+///
+/// # Example
+/// ```rust
+/// assert!(unicode_hfwidth::is_fullwidth_digit('７'));
+/// assert!(!unicode_hfwidth::is_fullwidth_digit('7'));
+/// ```
+pub fn is_fullwidth_digit(ch: char) -> bool {
+    matches!(ch, '０'..='９')
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_script_predicates() {
+    use super::{to_fullwidth, to_halfwidth};
+
+    for ch in '\u{ff00}'..='\u{ffef}' {
+        let category = forms_category(ch);
+        assert_eq!(is_halfwidth_katakana(ch), category == Some(FormsCategory::HalfwidthKatakana));
+        let ascii = to_halfwidth(ch).filter(char::is_ascii);
+        assert_eq!(is_fullwidth_latin(ch), ascii.is_some_and(|c| c.is_ascii_alphabetic()));
+        assert_eq!(is_fullwidth_digit(ch), ascii.is_some_and(|c| c.is_ascii_digit()));
+        if is_halfwidth_katakana(ch) && ch != 'ﾞ' && ch != 'ﾟ' {
+            assert!(is_fullwidth_katakana_compatible(to_fullwidth(ch).unwrap()), "{}", ch);
+        }
+    }
+    assert!("ガギグゲゴパピプペポヴ".chars().all(is_fullwidth_katakana_compatible));
+    assert!(!"ヵヶヮヰヱ".chars().any(is_fullwidth_katakana_compatible));
+}
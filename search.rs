@@ -0,0 +1,47 @@
+//! Width folding of search tokens.
+
+use super::WidthPolicy;
+
+/// Folds the width of tokens, so that text indexed and queried in different
+/// widths produces the same terms.
+///
+/// With the `tantivy` feature, this is a tantivy `TokenFilter`, which is
+/// added to the analyzers used for both indexing and querying.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "kana")] {
+/// use unicode_hfwidth::WidthFilter;
+///
+/// let filter = WidthFilter::default();
+/// let mut token = "ﾊﾟｿｺﾝ".to_owned();
+/// assert!(filter.fold(&mut token));
+/// assert_eq!(token, "パソコン");
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WidthFilter {
+    policy: WidthPolicy,
+}
+
+impl WidthFilter {
+    /// Creates a filter converting tokens according to `policy`.
+    pub fn new(policy: WidthPolicy) -> WidthFilter {
+        WidthFilter { policy }
+    }
+
+    /// Returns the policy the filter applies.
+    pub fn policy(&self) -> &WidthPolicy {
+        &self.policy
+    }
+
+    /// Converts `token` in place. Returns `true` if it changed.
+    pub fn fold(&self, token: &mut String) -> bool {
+        let converted = self.policy.convert(&*token);
+        if converted == *token {
+            return false;
+        }
+        *token = converted;
+        true
+    }
+}
@@ -0,0 +1,245 @@
+//! Collecting and extending with width conversion.
+
+use std::fmt;
+use std::iter::{FlatMap, Peekable};
+use std::ops::Deref;
+
+use super::{compose_kana, halfwidth_chars, to_fullwidth, Converted};
+
+/// A string of characters converted to full width as they are added.
+///
+/// Half-width voiced sound marks are composed with the kana before them.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::FullwidthString;
+///
+/// let s: FullwidthString = "ｶﾞｲﾄﾞ 1".chars().collect();
+/// assert_eq!(s.as_str(), "ガイド １");
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FullwidthString(String);
+
+/// A string of characters converted to half width as they are added.
+///
+/// Voiced and semi-voiced katakana are decomposed like `halfwidth_chars`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::HalfwidthString;
+///
+/// let mut s = HalfwidthString::new();
+/// s.extend(["ガイド", "　１"]);
+/// assert_eq!(s.as_str(), "ｶﾞｲﾄﾞ　1");
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HalfwidthString(String);
+
+impl FullwidthString {
+    /// Returns an empty string.
+    pub fn new() -> FullwidthString {
+        FullwidthString(String::new())
+    }
+
+    /// Appends `ch` converted to full width.
+    pub fn push(&mut self, ch: char) {
+        let converted = to_fullwidth(ch).unwrap_or(ch);
+        if matches!(ch, 'ﾞ' | 'ﾟ') {
+            let composed = self.0.chars().next_back().and_then(|base| compose_kana(base, converted));
+            if let Some(composed) = composed {
+                self.0.pop();
+                self.0.push(composed);
+                return;
+            }
+        }
+        self.0.push(converted);
+    }
+
+    /// Returns the string as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the converted `String`.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl HalfwidthString {
+    /// Returns an empty string.
+    pub fn new() -> HalfwidthString {
+        HalfwidthString(String::new())
+    }
+
+    /// Appends `ch` converted to half width.
+    pub fn push(&mut self, ch: char) {
+        self.0.extend(halfwidth_chars(ch));
+    }
+
+    /// Returns the string as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the converted `String`.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+macro_rules! impl_sink {
+    ($name:ident) => {
+        impl Extend<char> for $name {
+            fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+                iter.into_iter().for_each(|ch| self.push(ch));
+            }
+        }
+
+        impl<'a> Extend<&'a str> for $name {
+            fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+                iter.into_iter().for_each(|s| self.extend(s.chars()));
+            }
+        }
+
+        impl FromIterator<char> for $name {
+            fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> $name {
+                let mut s = $name::new();
+                s.extend(iter);
+                s
+            }
+        }
+
+        impl<'a> FromIterator<&'a str> for $name {
+            fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> $name {
+                let mut s = $name::new();
+                s.extend(iter);
+                s
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(s: &str) -> $name {
+                s.chars().collect()
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(s: $name) -> String {
+                s.0
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+    };
+}
+
+impl_sink!(FullwidthString);
+impl_sink!(HalfwidthString);
+
+/// Adapts characters to be converted to full width when iterated, so they can
+/// extend any collection of characters, such as a `String`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::FullwidthOf;
+///
+/// let mut s = String::from("No. ");
+/// s.extend(FullwidthOf("1ﾊﾟ".chars()));
+/// assert_eq!(s, "No. １パ");
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FullwidthOf<I>(pub I);
+
+/// Adapts characters to be converted to half width when iterated, so they can
+/// extend any collection of characters, such as a `String`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::HalfwidthOf;
+///
+/// let mut s = String::from("No. ");
+/// s.extend(HalfwidthOf("１パ".chars()));
+/// assert_eq!(s, "No. 1ﾊﾟ");
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct HalfwidthOf<I>(pub I);
+
+impl<I: IntoIterator<Item = char>> IntoIterator for FullwidthOf<I> {
+    type Item = char;
+    type IntoIter = FullwidthChars<I::IntoIter>;
+
+    fn into_iter(self) -> FullwidthChars<I::IntoIter> {
+        FullwidthChars { iter: self.0.into_iter().peekable() }
+    }
+}
+
+impl<I: IntoIterator<Item = char>> IntoIterator for HalfwidthOf<I> {
+    type Item = char;
+    type IntoIter = FlatMap<I::IntoIter, Converted, fn(char) -> Converted>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter().flat_map(halfwidth_chars as fn(char) -> Converted)
+    }
+}
+
+/// Iterator returned by `FullwidthOf::into_iter`.
+#[derive(Clone, Debug)]
+pub struct FullwidthChars<I: Iterator<Item = char>> {
+    iter: Peekable<I>,
+}
+
+impl<I: Iterator<Item = char>> Iterator for FullwidthChars<I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let converted = self.iter.next().map(|ch| to_fullwidth(ch).unwrap_or(ch))?;
+        let composed = self.iter.peek()
+            .filter(|&&mark| matches!(mark, 'ﾞ' | 'ﾟ'))
+            .and_then(|&mark| compose_kana(converted, to_fullwidth(mark)?));
+        if composed.is_some() {
+            self.iter.next();
+        }
+        Some(composed.unwrap_or(converted))
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_sinks() {
+    let s = "ｶﾞﾞﾊﾟ AB ガパ";
+    let full: FullwidthString = s.chars().collect();
+    let mut extended = String::new();
+    extended.extend(FullwidthOf(s.chars()));
+    assert_eq!(full.as_str(), "ガ\u{3099}パ ＡＢ ガパ");
+    assert_eq!(extended, full.as_str());
+    let half: HalfwidthString = [full.as_str()].into_iter().collect();
+    assert_eq!(String::from(half), HalfwidthOf(full.chars()).into_iter().collect::<String>());
+    assert_eq!(HalfwidthString::from("ガパ").as_str(), "ｶﾞﾊﾟ");
+}
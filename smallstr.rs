@@ -0,0 +1,103 @@
+//! Conversion into small-string types.
+
+#[cfg(feature = "compact_str")]
+use compact_str::CompactString;
+#[cfg(feature = "smol_str")]
+use smol_str::SmolStr;
+
+use super::{to_standard_width_str_reuse, WidthPolicy};
+
+impl WidthPolicy {
+    /// Converts `s` according to the policy into a `CompactString`, which
+    /// stores short results inline. The conversion goes through the reused
+    /// buffer of `convert_reuse` rather than an allocated `String`.
+    ///
+    /// Requires the `compact_str` feature.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(all(feature = "compact_str", feature = "kana", feature = "latin"))] {
+    /// use unicode_hfwidth::WidthPolicy;
+    ///
+    /// let name = WidthPolicy::default().convert_compact("ﾔﾏﾀﾞ ＴＡＲＯ");
+    /// assert_eq!(name, "ヤマダ TARO");
+    /// assert!(!name.is_heap_allocated());
+    /// # }
+    /// ```
+    #[cfg(feature = "compact_str")]
+    pub fn convert_compact<S: AsRef<str>>(&self, s: S) -> CompactString {
+        self.convert_reuse(s.as_ref(), |converted| CompactString::from(converted))
+    }
+
+    /// Converts `s` according to the policy into a `SmolStr`, which stores
+    /// short results inline. The conversion goes through the reused buffer
+    /// of `convert_reuse` rather than an allocated `String`.
+    ///
+    /// Requires the `smol_str` feature.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(all(feature = "kana", feature = "latin", feature = "smol_str"))] {
+    /// use unicode_hfwidth::WidthPolicy;
+    ///
+    /// let name = WidthPolicy::default().convert_smol("ﾔﾏﾀﾞ ＴＡＲＯ");
+    /// assert_eq!(name, "ヤマダ TARO");
+    /// assert!(!name.is_heap_allocated());
+    /// # }
+    /// ```
+    #[cfg(feature = "smol_str")]
+    pub fn convert_smol<S: AsRef<str>>(&self, s: S) -> SmolStr {
+        self.convert_reuse(s.as_ref(), |converted| SmolStr::from(converted))
+    }
+}
+
+/// Converts `s` to standard width, like the default `WidthPolicy`, into a
+/// `CompactString`. Strings already in standard width are copied as they are.
+///
+/// Requires the `compact_str` feature.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "compact_str", feature = "kana", feature = "latin"))] {
+/// assert_eq!(unicode_hfwidth::to_standard_width_compact("ＩＤ-ｶﾅ"), "ID-カナ");
+/// # }
+/// ```
+#[cfg(feature = "compact_str")]
+pub fn to_standard_width_compact<S: AsRef<str>>(s: S) -> CompactString {
+    to_standard_width_str_reuse(s.as_ref(), |converted| CompactString::from(converted))
+}
+
+/// Converts `s` to standard width, like the default `WidthPolicy`, into a
+/// `SmolStr`. Strings already in standard width are copied as they are.
+///
+/// Requires the `smol_str` feature.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin", feature = "smol_str"))] {
+/// assert_eq!(unicode_hfwidth::to_standard_width_smol("ＩＤ-ｶﾅ"), "ID-カナ");
+/// # }
+/// ```
+#[cfg(feature = "smol_str")]
+pub fn to_standard_width_smol<S: AsRef<str>>(s: S) -> SmolStr {
+    to_standard_width_str_reuse(s.as_ref(), |converted| SmolStr::from(converted))
+}
+
+#[cfg(feature = "latin")]
+#[test]
+fn test_small_strings() {
+    let long = "ｶﾀｶﾅ".repeat(10);
+    let policy = WidthPolicy::default();
+    #[cfg(feature = "compact_str")]
+    {
+        assert_eq!(to_standard_width_compact(&long), policy.convert(&long));
+        assert!(policy.convert_compact(&long).is_heap_allocated());
+        assert_eq!(policy.convert_compact("ＡＢ"), "AB");
+    }
+    #[cfg(feature = "smol_str")]
+    {
+        assert_eq!(to_standard_width_smol(&long), policy.convert(&long));
+        assert!(policy.convert_smol(&long).is_heap_allocated());
+        assert_eq!(policy.convert_smol("ＡＢ"), "AB");
+    }
+}
@@ -0,0 +1,58 @@
+//! Detection of identifiers impersonating ASCII with other widths.
+
+use super::{fold_width, is_fullwidth_digit, is_fullwidth_latin};
+
+/// The result of `check_width_spoof`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WidthSpoof {
+    /// The identifier with widths folded like `fold_width` and ASCII letters
+    /// lowercased. Identifiers with the same skeleton look alike, so new
+    /// identifiers can be vetted by looking up their skeleton.
+    pub skeleton: String,
+    /// Whether the identifier uses full-width variants of ASCII letters or
+    /// digits and is plain ASCII once folded, so it passes for an ASCII
+    /// identifier it is not.
+    pub spoof: bool,
+    /// Whether the identifier mixes ASCII letters or digits with their
+    /// full-width variants.
+    pub mixed_form: bool,
+}
+
+/// Checks whether the identifier `s` visually impersonates ASCII with
+/// full-width characters, as in `ａｄｍｉｎ` or `pａypal`, and returns its
+/// skeleton for comparison with other identifiers.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// let check = unicode_hfwidth::check_width_spoof("Ａｄｍｉｎ");
+/// assert_eq!(check.skeleton, "admin");
+/// assert!(check.spoof && !check.mixed_form);
+/// let check = unicode_hfwidth::check_width_spoof("pａypal");
+/// assert!(check.spoof && check.mixed_form);
+/// assert!(!unicode_hfwidth::check_width_spoof("ﾕｰｻﾞｰ1").spoof);
+/// # }
+/// ```
+pub fn check_width_spoof<S: AsRef<str>>(s: S) -> WidthSpoof {
+    let s = s.as_ref();
+    let ascii = s.chars().any(|ch| ch.is_ascii_alphanumeric());
+    let fullwidth = s.chars().any(|ch| is_fullwidth_latin(ch) || is_fullwidth_digit(ch));
+    let skeleton = fold_width(s).to_ascii_lowercase();
+    WidthSpoof {
+        spoof: fullwidth && skeleton.is_ascii(),
+        mixed_form: fullwidth && ascii,
+        skeleton,
+    }
+}
+
+#[cfg(feature = "latin")]
+#[test]
+fn test_check_width_spoof() {
+    let check = check_width_spoof("ｒｏｏｔ＿１");
+    assert_eq!(check, WidthSpoof { skeleton: "root_1".to_owned(), spoof: true, mixed_form: false });
+    assert_eq!(check_width_spoof("root_1").skeleton, check.skeleton);
+    assert!(!check_width_spoof("root＿1").spoof);
+    assert!(!check_width_spoof("ｒｏｏｔ名").spoof);
+    assert!(check_width_spoof("ｒｏｏｔ名a").mixed_form);
+    assert_eq!(check_width_spoof(""), WidthSpoof::default());
+}
@@ -0,0 +1,40 @@
+//! `sqlx` support for `WidthNormalized`.
+
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::{Database, Decode, Encode, Type};
+
+use super::WidthNormalized;
+
+impl<T: Type<DB>, DB: Database> Type<DB> for WidthNormalized<T> {
+    fn type_info() -> DB::TypeInfo {
+        T::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        T::compatible(ty)
+    }
+}
+
+impl<'q, T: Encode<'q, DB>, DB: Database> Encode<'q, DB> for WidthNormalized<T> {
+    fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+        self.0.encode_by_ref(buf)
+    }
+
+    fn produces(&self) -> Option<DB::TypeInfo> {
+        self.0.produces()
+    }
+
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+}
+
+/// Values read from the database are converted as well, so that rows written
+/// before the column was wrapped are also in standard width.
+impl<'r, T, DB> Decode<'r, DB> for WidthNormalized<T>
+    where T: Decode<'r, DB> + AsRef<str> + From<String>, DB: Database {
+    fn decode(value: DB::ValueRef<'r>) -> Result<WidthNormalized<T>, BoxDynError> {
+        T::decode(value).map(WidthNormalized::new)
+    }
+}
@@ -0,0 +1,270 @@
+//! Incremental conversion of input arriving in chunks.
+
+use std::collections::HashMap;
+use std::str::{self, Utf8Error};
+
+use super::policy::{Context, PieceSink};
+use super::WidthPolicy;
+
+/// Converts UTF-8 input fed in arbitrary chunks, giving the same result as
+/// converting the whole input at once.
+///
+/// Chunks may split multi-byte characters, and a kana at the end of one chunk
+/// is held back until it is known whether a voiced sound mark follows.
+/// Characters outside the Basic Multilingual Plane, such as emoji, are never
+/// converted, and are output whole even when a chunk ends inside them.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::{StreamConverter, WidthPolicy};
+///
+/// let mut converter = StreamConverter::new(WidthPolicy::default());
+/// let mut out = String::new();
+/// converter.feed("ＡＢｶ".as_bytes(), &mut out).unwrap();
+/// assert_eq!(out, "AB");
+/// converter.feed("ﾞｷ".as_bytes(), &mut out).unwrap();
+/// converter.finish(&mut out).unwrap();
+/// assert_eq!(out, "ABガキ");
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct StreamConverter {
+    policy: WidthPolicy,
+    /// Characters converted to a fixed replacement instead.
+    overrides: HashMap<char, char>,
+    /// Bytes of a character split across chunks.
+    partial: Vec<u8>,
+    /// The state carried over to the next character, such as a converted
+    /// kana that may compose with it.
+    cx: Context,
+    /// A high surrogate ending the last UTF-16 chunk.
+    surrogate: Option<u16>,
+}
+
+impl StreamConverter {
+    /// Creates a converter applying `policy`.
+    pub fn new(policy: WidthPolicy) -> StreamConverter {
+        StreamConverter::with_mappings(policy, HashMap::new())
+    }
+
+    /// Creates a converter applying `policy`, except to the characters in
+    /// `overrides`, which are replaced as `WidthConverter::map` replaces them.
+    pub(crate) fn with_mappings(policy: WidthPolicy, overrides: HashMap<char, char>)
+                                -> StreamConverter {
+        StreamConverter {
+            policy,
+            overrides,
+            partial: Vec::new(),
+            cx: Context::default(),
+            surrogate: None,
+        }
+    }
+
+    /// Returns the policy the converter applies.
+    pub fn policy(&self) -> &WidthPolicy {
+        &self.policy
+    }
+
+    fn push_char<S: PieceSink>(&mut self, ch: char, out: &mut S) {
+        match self.overrides.get(&ch) {
+            Some(&mapped) => self.cx.push_verbatim(mapped, 0..0, out),
+            None          => self.policy.push_piece(ch, 0..0, &mut self.cx, out),
+        }
+    }
+
+    fn push_str(&mut self, s: &str, out: &mut String) {
+        for ch in s.chars() {
+            self.push_char(ch, out);
+        }
+    }
+
+    /// Converts the next chunk of input, appending the output that is ready
+    /// to `out`. If the input is not valid UTF-8, converts the input before
+    /// the error and returns it, with offsets relative to `chunk`, and skips
+    /// the rest of the chunk. An invalid character begun in an earlier chunk
+    /// is dropped and reported at offset 0, with the bytes it has as the
+    /// length of the error, after converting the chunk up to any error of
+    /// its own. The converter can be fed further chunks after an error.
+    pub fn feed(&mut self, mut chunk: &[u8], out: &mut String) -> Result<(), Utf8Error> {
+        let mut broken = None;
+        while !self.partial.is_empty() && !chunk.is_empty() {
+            self.partial.push(chunk[0]);
+            match str::from_utf8(&self.partial) {
+                Ok(s) => {
+                    let ch = s.chars().next().unwrap();
+                    self.partial.clear();
+                    self.push_char(ch, out);
+                }
+                Err(e) if e.error_len().is_some() => {
+                    /* The byte does not continue the character, and is converted with the rest */
+                    self.partial.clear();
+                    broken = Some(e);
+                    break;
+                }
+                Err(_) => {}
+            }
+            chunk = &chunk[1..];
+        }
+        let result = match str::from_utf8(chunk) {
+            Ok(s) => {
+                self.push_str(s, out);
+                Ok(())
+            }
+            Err(e) => {
+                let (valid, rest) = chunk.split_at(e.valid_up_to());
+                self.push_str(str::from_utf8(valid).unwrap(), out);
+                if e.error_len().is_some() {
+                    Err(e)
+                } else {
+                    self.partial.extend_from_slice(rest);
+                    Ok(())
+                }
+            }
+        };
+        match broken {
+            Some(e) => Err(e),
+            None    => result,
+        }
+    }
+
+    /// Converts the next chunk of UTF-16 encoded input, appending the output
+    /// that is ready to `out`, giving the same result as `feed` would for the
+    /// input in UTF-8. A surrogate pair split between chunks is joined, and
+    /// unpaired surrogates are passed through unchanged. UTF-16 input must not
+    /// be mixed with input to `feed`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "kana")] {
+    /// use unicode_hfwidth::{StreamConverter, WidthPolicy};
+    ///
+    /// let input: Vec<u16> = "ｶ😀ﾞ".encode_utf16().collect();
+    /// let mut converter = StreamConverter::new(WidthPolicy::default());
+    /// let mut out = Vec::new();
+    /// converter.feed_utf16(&input[..2], &mut out);
+    /// converter.feed_utf16(&input[2..], &mut out);
+    /// converter.finish_utf16(&mut out);
+    /// assert_eq!(String::from_utf16(&out).unwrap(), "カ😀\u{3099}");
+    /// # }
+    /// ```
+    pub fn feed_utf16(&mut self, chunk: &[u16], out: &mut Vec<u16>) {
+        let (chunk, surrogate) = match chunk.split_last() {
+            Some((&last, rest)) if matches!(last, 0xd800..=0xdbff) => (rest, Some(last)),
+            _                                                      => (chunk, None),
+        };
+        let units = self.surrogate.take().into_iter().chain(chunk.iter().copied());
+        for unit in char::decode_utf16(units) {
+            match unit {
+                Ok(ch) => self.push_char(ch, out),
+                Err(e) => {
+                    self.cx.finish(out);
+                    out.push(e.unpaired_surrogate());
+                }
+            }
+        }
+        self.surrogate = surrogate;
+    }
+
+    /// Appends the UTF-16 output still held back to `out` and resets the
+    /// converter for new input.
+    pub fn finish_utf16(&mut self, out: &mut Vec<u16>) {
+        self.cx.finish(out);
+        out.extend(self.surrogate.take());
+    }
+
+    /// Appends the output still held back to `out` and resets the converter
+    /// for new input. If the input ended inside a character, returns an error.
+    pub fn finish(&mut self, out: &mut String) -> Result<(), Utf8Error> {
+        self.cx.finish(out);
+        if self.partial.is_empty() {
+            return Ok(());
+        }
+        let e = str::from_utf8(&self.partial).unwrap_err();
+        self.partial.clear();
+        Err(e)
+    }
+}
+
+#[test]
+fn test_stream_chunks() {
+    let input = "ＡＢ ｶﾞｷﾞ ﾊﾟ 😀 ｶ";
+    let expected = WidthPolicy::default().convert(input);
+    for size in 1..input.len() {
+        let mut converter = StreamConverter::new(WidthPolicy::default());
+        let mut out = String::new();
+        for chunk in input.as_bytes().chunks(size) {
+            converter.feed(chunk, &mut out).unwrap();
+        }
+        converter.finish(&mut out).unwrap();
+        assert_eq!(out, expected);
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_stream_cleared_output() {
+    use super::{MinusMapping, QuoteMapping};
+
+    let cases = [
+        (WidthPolicy::builder().quotes(QuoteMapping::Curly).build(), "ａ＇ｓ ＂ｂ＂ (＇ｃ＇)"),
+        (WidthPolicy::builder().minus(MinusMapping::HyphenMinus).build(), "ｱｰ１ ｰ１ ０３ｰ１ ｶｰｰ"),
+        (WidthPolicy::builder().contextual_prolonged_mark(true).build(), "ｱｰ ａｰｂ ｶｰｰ ｰ"),
+    ];
+    for (policy, input) in cases {
+        let expected = policy.convert(input);
+        for size in 1..input.len() {
+            let mut converter = StreamConverter::new(policy.clone());
+            let mut joined = String::new();
+            let mut out = String::new();
+            for chunk in input.as_bytes().chunks(size) {
+                converter.feed(chunk, &mut out).unwrap();
+                joined.push_str(&out);
+                out.clear();
+            }
+            converter.finish(&mut out).unwrap();
+            joined.push_str(&out);
+            assert_eq!(joined, expected, "{} {}", input, size);
+        }
+    }
+}
+
+#[cfg(feature = "kana")]
+#[test]
+fn test_stream_utf16_chunks() {
+    let input: Vec<u16> = "ｶ😀ﾞ 𠀋ｶﾞ Ａ\u{10ffff}ﾊ".encode_utf16()
+        .chain([0xd800, 0xd800, 0xdc00, 0xdc00, 0xff76, 0xd83d])
+        .collect();
+    let valid = String::from_utf16(&input[..input.len() - 6]).unwrap();
+    let mut expected: Vec<u16> = WidthPolicy::default().convert(&valid).encode_utf16().collect();
+    expected.extend([0xd800, 0xd800, 0xdc00, 0xdc00, 0x30ab, 0xd83d]);
+    for size in 1..input.len() {
+        let mut converter = StreamConverter::new(WidthPolicy::default());
+        let mut out = Vec::new();
+        for chunk in input.chunks(size) {
+            converter.feed_utf16(chunk, &mut out);
+        }
+        converter.finish_utf16(&mut out);
+        assert_eq!(out, expected, "{}", size);
+    }
+}
+
+#[test]
+fn test_stream_invalid() {
+    let mut converter = StreamConverter::new(WidthPolicy::default());
+    let mut out = String::new();
+    converter.feed(b"a\xe3\x82", &mut out).unwrap();
+    assert!(converter.finish(&mut out).is_err());
+    assert!(converter.feed(b"\xffb", &mut out).is_err());
+    assert_eq!(out, "a");
+    converter.feed(b"\xe3", &mut out).unwrap();
+    let e = converter.feed(b"b", &mut out).unwrap_err();
+    assert_eq!((e.valid_up_to(), e.error_len()), (0, Some(1)));
+    converter.feed(b"c\xe3\x82", &mut out).unwrap();
+    converter.feed(b"\xab", &mut out).unwrap();
+    converter.feed(b"\xe3", &mut out).unwrap();
+    let e = converter.feed(b"\x82\xe3\x82\xab", &mut out).unwrap_err();
+    assert_eq!((e.valid_up_to(), e.error_len()), (0, Some(2)));
+    converter.finish(&mut out).unwrap();
+    assert_eq!(out, "abcカカ");
+}
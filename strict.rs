@@ -0,0 +1,97 @@
+//! Conversions reporting why a character has no form.
+
+use std::error::Error;
+use std::fmt;
+
+use super::{forms_category, to_fullwidth, to_halfwidth};
+
+/// The error returned by `try_to_halfwidth` and `try_to_fullwidth`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TryConvertError {
+    /// The character has no width variant; it is neither in the "Halfwidth
+    /// and Fullwidth Forms" block nor the counterpart of a character in it.
+    OutsideBlock,
+    /// The character is a code point of the "Halfwidth and Fullwidth Forms"
+    /// block that is not assigned.
+    Unassigned,
+    /// The character is already in the requested form.
+    AlreadyTargetForm,
+}
+
+impl fmt::Display for TryConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TryConvertError::OutsideBlock      => f.write_str("character has no width variant"),
+            TryConvertError::Unassigned        => f.write_str("unassigned width forms code point"),
+            TryConvertError::AlreadyTargetForm => f.write_str("character already in target form"),
+        }
+    }
+}
+
+impl Error for TryConvertError {}
+
+/// Returns why `ch`, which has no form converting it with `to_halfwidth` or
+/// `to_fullwidth`, was not converted. `other` is the conversion to the
+/// opposite form.
+fn failure(ch: char, other: fn(char) -> Option<char>) -> TryConvertError {
+    if other(ch).is_some() {
+        TryConvertError::AlreadyTargetForm
+    } else if matches!(ch, '\u{ff00}'..='\u{ffef}') && forms_category(ch).is_none() {
+        TryConvertError::Unassigned
+    } else {
+        TryConvertError::OutsideBlock
+    }
+}
+
+/// Returns the half-width form for `ch` like `to_halfwidth`, or why there is
+/// none.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::{try_to_halfwidth, TryConvertError};
+///
+/// assert_eq!(try_to_halfwidth('カ'), Ok('ｶ'));
+/// assert_eq!(try_to_halfwidth('a'), Err(TryConvertError::AlreadyTargetForm));
+/// assert_eq!(try_to_halfwidth('漢'), Err(TryConvertError::OutsideBlock));
+/// assert_eq!(try_to_halfwidth('\u{ffbf}'), Err(TryConvertError::Unassigned));
+/// # }
+/// ```
+pub fn try_to_halfwidth(ch: char) -> Result<char, TryConvertError> {
+    to_halfwidth(ch).ok_or_else(|| failure(ch, to_fullwidth))
+}
+
+/// Returns the full-width form for `ch` like `to_fullwidth`, or why there is
+/// none.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::{try_to_fullwidth, TryConvertError};
+///
+/// assert_eq!(try_to_fullwidth('a'), Ok('ａ'));
+/// assert_eq!(try_to_fullwidth('カ'), Err(TryConvertError::AlreadyTargetForm));
+/// # }
+/// ```
+pub fn try_to_fullwidth(ch: char) -> Result<char, TryConvertError> {
+    to_fullwidth(ch).ok_or_else(|| failure(ch, to_halfwidth))
+}
+
+#[cfg(all(feature = "hangul", feature = "kana", feature = "latin", feature = "symbols"))]
+#[test]
+fn test_try_convert() {
+    for ch in '\u{ff00}'..='\u{ffef}' {
+        let result = match forms_category(ch) {
+            Some(_) => Err(TryConvertError::AlreadyTargetForm),
+            None    => Err(TryConvertError::Unassigned),
+        };
+        if to_halfwidth(ch).is_none() {
+            assert_eq!(try_to_halfwidth(ch), result, "{}", ch);
+        }
+        if to_fullwidth(ch).is_none() {
+            assert_eq!(try_to_fullwidth(ch), result, "{}", ch);
+        }
+    }
+    assert_eq!(try_to_fullwidth('ｶ'), Ok('カ'));
+    assert_eq!(try_to_fullwidth(' '), Err(TryConvertError::OutsideBlock));
+}
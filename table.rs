@@ -0,0 +1,134 @@
+//! Column alignment for text tables containing wide characters.
+
+use std::fmt;
+
+use super::display::{center, pad_left, pad_right, str_width, Fill};
+
+/// Alignment of a table column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Align {
+    /// Align cells to the left.
+    #[default]
+    Left,
+    /// Align cells to the right.
+    Right,
+    /// Center cells.
+    Center,
+}
+
+/// A text table whose columns are aligned by display width, so that mixed
+/// half-width and full-width content lines up in a terminal.
+///
+/// Rows may have differing numbers of cells; missing cells are empty.
+///
+/// # Example
+/// ```rust
+/// use unicode_hfwidth::{Align, Table};
+///
+/// let mut table = Table::new();
+/// table.push_row(["名前", "数量"]);
+/// table.push_row(["ﾘﾝｺﾞ", "3"]);
+/// table.push_row(["バナナ", "12"]);
+/// table.align(1, Align::Right);
+/// assert_eq!(table.to_string(), "名前   数量\nﾘﾝｺﾞ      3\nバナナ   12\n");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Table {
+    rows: Vec<Vec<String>>,
+    aligns: Vec<Align>,
+    separator: String,
+    fill: Fill,
+}
+
+impl Table {
+    /// Creates an empty table with left-aligned columns separated by a single
+    /// space.
+    pub fn new() -> Table {
+        Table { separator: " ".to_owned(), ..Table::default() }
+    }
+
+    /// Appends a row of cells.
+    pub fn push_row<I>(&mut self, cells: I) -> &mut Table
+        where I: IntoIterator, I::Item: Into<String>
+    {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the alignment of column `column`.
+    pub fn align(&mut self, column: usize, align: Align) -> &mut Table {
+        if self.aligns.len() <= column {
+            self.aligns.resize(column + 1, Align::Left);
+        }
+        self.aligns[column] = align;
+        self
+    }
+
+    /// Sets the string placed between columns.
+    pub fn separator(&mut self, separator: &str) -> &mut Table {
+        self.separator = separator.to_owned();
+        self
+    }
+
+    /// Sets the character used to pad cells.
+    pub fn fill(&mut self, fill: Fill) -> &mut Table {
+        self.fill = fill;
+        self
+    }
+
+    /// Returns the display width of each column.
+    pub fn column_widths(&self) -> Vec<usize> {
+        let mut widths = Vec::new();
+        for row in &self.rows {
+            if widths.len() < row.len() {
+                widths.resize(row.len(), 0);
+            }
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(str_width(cell));
+            }
+        }
+        widths
+    }
+
+    /// Returns the rendered lines of the table, without line terminators.
+    /// Left-aligned cells in the last column are not padded.
+    pub fn lines(&self) -> Vec<String> {
+        let widths = self.column_widths();
+        self.rows.iter().map(|row| {
+            let mut line = String::new();
+            for (column, &width) in widths.iter().enumerate() {
+                let cell = row.get(column).map_or("", String::as_str);
+                let align = self.aligns.get(column).copied().unwrap_or_default();
+                if column > 0 {
+                    line.push_str(&self.separator);
+                }
+                match align {
+                    Align::Left if column + 1 == widths.len() => line.push_str(cell),
+                    Align::Left   => line.push_str(&pad_right(cell, width, self.fill)),
+                    Align::Right  => line.push_str(&pad_left(cell, width, self.fill)),
+                    Align::Center => line.push_str(&center(cell, width, self.fill)),
+                }
+            }
+            line
+        }).collect()
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in self.lines() {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_table() {
+    let mut table = Table::new();
+    table.push_row(["ＩＤ", "氏名", "備考"]);
+    table.push_row(["1", "山田ﾀﾛｳ"]);
+    table.separator(" | ").align(0, Align::Center);
+    assert_eq!(table.column_widths(), [4, 7, 4]);
+    assert_eq!(table.lines(), ["ＩＤ | 氏名    | 備考", " 1   | 山田ﾀﾛｳ | "]);
+}
@@ -0,0 +1,119 @@
+//! Seeded generation of mixed-width test strings.
+
+use super::{halfwidth_chars, to_fullwidth, to_halfwidth};
+
+const ASCII_WORDS: &[&str] = &[
+    "user", "ID", "No.", "2024", "Tokyo", "order", "test@example.com", "A-12", "(1)", "100%",
+];
+const KATAKANA_WORDS: &[&str] = &[
+    "データ", "ガイド", "パスワード", "コーヒー", "ジュース", "ポイント", "カタカナ", "ヴァイオリン",
+];
+const JAMO_WORDS: &[&str] = &["ㅎㅏㄴㄱㅜㄱ", "ㅅㅓㅇㅜㄹ", "ㄱㅏㄱ", "ㅂㅏㅂ"];
+const STANDARD_WORDS: &[&str] = &[
+    "東京都", "株式会社", "ひらがな", "カナ", "서울", "name", "メモ", "、", "。", "「引用」",
+];
+
+/// Generates strings mixing full-width ASCII, half-width katakana with sound
+/// marks, half-width Hangul and standard text, for testing form handling with
+/// input like users type. The same seed always gives the same strings.
+///
+/// Requires the `testgen` feature.
+///
+/// # Example
+/// ```rust
+/// use unicode_hfwidth::MixedWidthGenerator;
+///
+/// let s = MixedWidthGenerator::new(7).generate(8);
+/// assert_eq!(s, MixedWidthGenerator::new(7).generate(8));
+/// for input in MixedWidthGenerator::new(7).take(100) {
+///     let normalized = unicode_hfwidth::WidthPolicy::default().convert(&input);
+///     assert!(!normalized.chars().any(|ch| matches!(ch, 'ｱ'..='ﾝ' | 'ａ'..='ｚ')));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MixedWidthGenerator {
+    state: u64,
+}
+
+impl MixedWidthGenerator {
+    /// Creates a generator from `seed`.
+    pub fn new(seed: u64) -> MixedWidthGenerator {
+        MixedWidthGenerator { state: seed }
+    }
+
+    /// Returns the next pseudo-random number, using SplitMix64.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, words: &[&'a str]) -> &'a str {
+        words[self.below(words.len())]
+    }
+
+    /// Returns a string of `words` words of random forms, separated by ASCII
+    /// or ideographic spaces or nothing.
+    pub fn generate(&mut self, words: usize) -> String {
+        let mut out = String::new();
+        for i in 0..words {
+            if i > 0 {
+                match self.below(4) {
+                    0 => out.push('\u{3000}'),
+                    1 => {}
+                    _ => out.push(' '),
+                }
+            }
+            match self.below(5) {
+                0 => {
+                    let word = self.pick(ASCII_WORDS);
+                    out.extend(word.chars().map(|ch| to_fullwidth(ch).unwrap_or(ch)));
+                }
+                1 => {
+                    let word = self.pick(KATAKANA_WORDS);
+                    out.extend(word.chars().flat_map(halfwidth_chars));
+                }
+                2 => {
+                    let word = self.pick(JAMO_WORDS);
+                    out.extend(word.chars().map(|ch| to_halfwidth(ch).unwrap_or(ch)));
+                }
+                3 => out.push_str(self.pick(ASCII_WORDS)),
+                _ => out.push_str(self.pick(STANDARD_WORDS)),
+            }
+        }
+        out
+    }
+}
+
+impl Iterator for MixedWidthGenerator {
+    type Item = String;
+
+    /// Returns a string of one to eight words.
+    fn next(&mut self) -> Option<String> {
+        let words = 1 + self.below(8);
+        Some(self.generate(words))
+    }
+}
+
+#[cfg(all(feature = "hangul", feature = "kana", feature = "latin"))]
+#[test]
+fn test_generator() {
+    use super::{analyze, WidthPolicy};
+
+    let strings: Vec<String> = MixedWidthGenerator::new(1).take(200).collect();
+    assert_eq!(strings, MixedWidthGenerator::new(1).take(200).collect::<Vec<_>>());
+    assert_ne!(strings, MixedWidthGenerator::new(2).take(200).collect::<Vec<_>>());
+    let stats = analyze(strings.concat());
+    assert!(stats.fullwidth_ascii > 0 && stats.halfwidth_kana > 0 && stats.halfwidth_hangul > 0);
+    assert!(strings.concat().contains('ﾞ') && strings.concat().contains('ﾟ'));
+    for s in &strings {
+        let converted = WidthPolicy::default().convert(s);
+        assert!(!converted.chars().any(|ch| matches!(ch, 'ﾞ' | 'ﾟ' | 'Ａ'..='Ｚ')), "{}", s);
+    }
+}
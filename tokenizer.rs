@@ -0,0 +1,102 @@
+//! tantivy support for `WidthFilter`.
+
+use tantivy_tokenizer_api::{Token, TokenFilter, TokenStream, Tokenizer};
+
+use super::WidthFilter;
+
+impl TokenFilter for WidthFilter {
+    type Tokenizer<T: Tokenizer> = WidthFilterTokenizer<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> WidthFilterTokenizer<T> {
+        WidthFilterTokenizer { filter: self, tokenizer }
+    }
+}
+
+/// A tokenizer whose tokens are folded by a `WidthFilter`.
+#[derive(Clone, Debug)]
+pub struct WidthFilterTokenizer<T> {
+    filter: WidthFilter,
+    tokenizer: T,
+}
+
+impl<T: Tokenizer> Tokenizer for WidthFilterTokenizer<T> {
+    type TokenStream<'a> = WidthFilterTokenStream<'a, T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        WidthFilterTokenStream { filter: &self.filter, tail: self.tokenizer.token_stream(text) }
+    }
+}
+
+/// The token stream of a `WidthFilterTokenizer`.
+#[derive(Debug)]
+pub struct WidthFilterTokenStream<'a, S> {
+    filter: &'a WidthFilter,
+    tail: S,
+}
+
+impl<S: TokenStream> TokenStream for WidthFilterTokenStream<'_, S> {
+    fn advance(&mut self) -> bool {
+        if !self.tail.advance() {
+            return false;
+        }
+        self.filter.fold(&mut self.tail.token_mut().text);
+        true
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_tantivy_filter() {
+    /// Splits text at spaces.
+    #[derive(Clone)]
+    struct Spaces;
+
+    struct SpacesStream<'a> {
+        words: std::str::Split<'a, char>,
+        token: Token,
+    }
+
+    impl Tokenizer for Spaces {
+        type TokenStream<'a> = SpacesStream<'a>;
+
+        fn token_stream<'a>(&'a mut self, text: &'a str) -> SpacesStream<'a> {
+            SpacesStream { words: text.split(' '), token: Token::default() }
+        }
+    }
+
+    impl TokenStream for SpacesStream<'_> {
+        fn advance(&mut self) -> bool {
+            match self.words.next() {
+                Some(word) => {
+                    self.token.text = word.to_owned();
+                    true
+                }
+                None => false,
+            }
+        }
+
+        fn token(&self) -> &Token {
+            &self.token
+        }
+
+        fn token_mut(&mut self) -> &mut Token {
+            &mut self.token
+        }
+    }
+
+    let mut tokenizer = WidthFilter::default().transform(Spaces);
+    let mut stream = tokenizer.token_stream("ﾊﾟｿｺﾝ パソコン ＰＣ");
+    let mut terms = Vec::new();
+    while stream.advance() {
+        terms.push(stream.token().text.clone());
+    }
+    assert_eq!(terms, ["パソコン", "パソコン", "PC"]);
+}
@@ -0,0 +1,91 @@
+//! Tracing of large conversions, with the `tracing` feature.
+
+/// Conversions of inputs of at least this many bytes are traced.
+#[cfg(feature = "tracing")]
+const TRACED_LEN: usize = 64 * 1024;
+
+/// Runs the conversion `f` of `s`. With the `tracing` feature, a conversion
+/// of a large input runs in a `width_conversion` span recording `operation`
+/// and the input length, and ends with an event recording the number of
+/// characters of each kind and how long it took.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn traced<T, F: FnOnce() -> T>(operation: &'static str, s: &str, f: F) -> T {
+    #[cfg(feature = "tracing")]
+    if s.len() >= TRACED_LEN {
+        let span = tracing::debug_span!("width_conversion", operation, bytes = s.len());
+        if span.is_disabled() {
+            return f();
+        }
+        let _entered = span.enter();
+        let stats = super::analyze(s);
+        let start = std::time::Instant::now();
+        let result = f();
+        tracing::debug!(
+            fullwidth_ascii = stats.fullwidth_ascii,
+            halfwidth_kana = stats.halfwidth_kana,
+            halfwidth_hangul = stats.halfwidth_hangul,
+            symbols = stats.symbols,
+            duration_us = start.elapsed().as_micros() as u64,
+            "converted"
+        );
+        return result;
+    }
+    f()
+}
+
+/// A subscriber counting the `width_conversion` spans and the events it sees.
+#[cfg(all(test, feature = "tracing"))]
+#[derive(Clone, Default)]
+struct Counting {
+    spans: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    events: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+#[cfg(all(test, feature = "tracing"))]
+impl tracing::Subscriber for Counting {
+    fn enabled(&self, _: &tracing::Metadata) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &tracing::span::Attributes) -> tracing::span::Id {
+        if span.metadata().name() == "width_conversion" {
+            self.spans.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+        tracing::span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record) {}
+
+    fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+    fn event(&self, _: &tracing::Event) {
+        self.events.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    fn enter(&self, _: &tracing::span::Id) {}
+
+    fn exit(&self, _: &tracing::span::Id) {}
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_traced() {
+    assert_eq!(traced("test", "ab", || 1), 1);
+    let (large, expected) = ("ＡＢｶﾅ".repeat(16 * 1024), "ABカナ".repeat(16 * 1024));
+    let converter = super::WidthConverter::new(super::WidthPolicy::default());
+    assert_eq!(converter.convert(&large), expected);
+    assert_eq!(converter.try_convert(&large).unwrap(), expected);
+    #[cfg(feature = "tracing")]
+    {
+        use std::sync::atomic::Ordering;
+
+        let counting = Counting::default();
+        tracing::subscriber::with_default(counting.clone(), || {
+            assert_eq!(converter.convert("ＡＢ"), "AB");
+            assert_eq!(counting.spans.load(Ordering::SeqCst), 0);
+            assert_eq!(converter.convert(&large), expected);
+        });
+        assert_eq!(counting.spans.load(Ordering::SeqCst), 1);
+        assert_eq!(counting.events.load(Ordering::SeqCst), 1);
+    }
+}
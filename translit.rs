@@ -0,0 +1,119 @@
+//! Width transforms identified like ICU transliterators.
+
+use std::error::Error;
+use std::fmt;
+
+use super::{FullwidthString, HalfwidthString};
+
+/// The transform of a `Transliterator`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Transform {
+    Halfwidth,
+    Fullwidth,
+    Null,
+}
+
+/// A width transform created from an ICU transform ID, for code moving from
+/// ICU's `Transliterator`.
+///
+/// The IDs `Fullwidth-Halfwidth` and `Halfwidth-Fullwidth` convert like the
+/// ICU transforms of the same names: voiced kana become half-width kana and a
+/// sound mark, and the ideographic space becomes an ASCII space, and back.
+/// `Any-Null` leaves text unchanged. IDs are matched without regard to case,
+/// and the source may be `Any` or left out, as in `Any-Halfwidth`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::Transliterator;
+///
+/// let t = Transliterator::new("Fullwidth-Halfwidth").unwrap();
+/// assert_eq!(t.transliterate("ガイド　ＡＢＣ"), "ｶﾞｲﾄﾞ ABC");
+/// assert_eq!(t.inverse().transliterate("ｶﾞｲﾄﾞ ABC"), "ガイド　ＡＢＣ");
+/// assert!(Transliterator::new("Latin-Katakana").is_err());
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Transliterator {
+    transform: Transform,
+}
+
+impl Transliterator {
+    /// Creates the transliterator for the ICU transform ID `id`.
+    pub fn new(id: &str) -> Result<Transliterator, UnknownTransform> {
+        let id = id.trim();
+        let (source, target) = id.split_once('-').unwrap_or(("Any", id));
+        let transform = match (&*source.to_ascii_lowercase(), &*target.to_ascii_lowercase()) {
+            ("any", "null")                    => Transform::Null,
+            ("any" | "fullwidth", "halfwidth") => Transform::Halfwidth,
+            ("any" | "halfwidth", "fullwidth") => Transform::Fullwidth,
+            _                                  => return Err(UnknownTransform(id.to_owned())),
+        };
+        Ok(Transliterator { transform })
+    }
+
+    /// Returns the canonical ID of the transform.
+    pub fn id(&self) -> &'static str {
+        match self.transform {
+            Transform::Halfwidth => "Fullwidth-Halfwidth",
+            Transform::Fullwidth => "Halfwidth-Fullwidth",
+            Transform::Null      => "Any-Null",
+        }
+    }
+
+    /// Returns the transliterator of the inverse transform, like ICU's
+    /// `createInverse`.
+    pub fn inverse(&self) -> Transliterator {
+        let transform = match self.transform {
+            Transform::Halfwidth => Transform::Fullwidth,
+            Transform::Fullwidth => Transform::Halfwidth,
+            Transform::Null      => Transform::Null,
+        };
+        Transliterator { transform }
+    }
+
+    /// Transforms `s`.
+    pub fn transliterate<S: AsRef<str>>(&self, s: S) -> String {
+        let s = s.as_ref();
+        match self.transform {
+            Transform::Halfwidth => {
+                let chars = s.chars().map(|ch| if ch == '\u{3000}' { ' ' } else { ch });
+                chars.collect::<HalfwidthString>().into_string()
+            }
+            Transform::Fullwidth => {
+                let chars = s.chars().map(|ch| if ch == ' ' { '\u{3000}' } else { ch });
+                chars.collect::<FullwidthString>().into_string()
+            }
+            Transform::Null => s.to_owned(),
+        }
+    }
+}
+
+/// The error returned by `Transliterator::new` for IDs of transforms other
+/// than the width transforms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownTransform(pub String);
+
+impl fmt::Display for UnknownTransform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown transform ID {:?}", self.0)
+    }
+}
+
+impl Error for UnknownTransform {}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_transliterator() {
+    for id in ["fullwidth-halfwidth", " Any-Halfwidth ", "Halfwidth"] {
+        assert_eq!(Transliterator::new(id).unwrap().id(), "Fullwidth-Halfwidth", "{}", id);
+    }
+    assert_eq!(Transliterator::new("Halfwidth-Fullwidth").unwrap().inverse().id(),
+               "Fullwidth-Halfwidth");
+    assert_eq!(Transliterator::new("Null").unwrap().transliterate("ｶ ａ"), "ｶ ａ");
+    for id in ["Halfwidth-Halfwidth", "Latin-Fullwidth", "NFKC; Fullwidth-Halfwidth", ""] {
+        assert_eq!(Transliterator::new(id), Err(UnknownTransform(id.trim().to_owned())));
+    }
+    let t = Transliterator::new("Halfwidth-Fullwidth").unwrap();
+    assert_eq!(t.transliterate("ﾊﾟｿｺﾝ 100"), "パソコン　１００");
+}
@@ -0,0 +1,102 @@
+//! Width normalization of URL components.
+
+use std::fmt::Write;
+
+use super::{normalize_hostname, HostnameError, WidthPolicy, WidthTarget};
+
+/// Returns the policy converting to standard width, including ideographic
+/// spaces.
+fn url_policy() -> WidthPolicy {
+    WidthPolicy::builder().space(WidthTarget::Halfwidth).build()
+}
+
+/// Converts the characters of `s` outside ASCII to standard width, leaving
+/// ASCII, including percent-encoded bytes, as it is. Characters that become
+/// `%`, a space, a control character or one of `reserved` are
+/// percent-encoded, so folding never changes how the component is split or
+/// decoded.
+fn normalize_component(s: &str, reserved: &[char]) -> String {
+    let policy = url_policy();
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while !rest.is_empty() {
+        let ascii = rest.find(|ch: char| !ch.is_ascii()).unwrap_or(rest.len());
+        out.push_str(&rest[..ascii]);
+        rest = &rest[ascii..];
+        let other = rest.find(|ch: char| ch.is_ascii()).unwrap_or(rest.len());
+        for ch in policy.convert(&rest[..other]).chars() {
+            if ch == '%' || ch == ' ' || ch.is_ascii_control() || reserved.contains(&ch) {
+                write!(out, "%{:02X}", ch as u32).unwrap();
+            } else {
+                out.push(ch);
+            }
+        }
+        rest = &rest[other..];
+    }
+    out
+}
+
+/// Converts a URL path typed with an IME to standard width. Ideographic
+/// spaces are converted too, and encoded like spaces. `／` becomes the
+/// segment separator `/`, while characters that would end the path or change
+/// its decoding, such as `？`, `＃` and `％`, are percent-encoded. Existing
+/// percent-encoded bytes are left unchanged.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// assert_eq!(unicode_hfwidth::normalize_url_path("／ｄｏｃｓ／ｶﾞｲﾄﾞ？／%E3%82%AB"),
+///            "/docs/ガイド%3F/%E3%82%AB");
+/// # }
+/// ```
+pub fn normalize_url_path<S: AsRef<str>>(path: S) -> String {
+    normalize_component(path.as_ref(), &['?', '#'])
+}
+
+/// Converts a query parameter name or value typed with an IME to standard
+/// width. Characters that would split or end the query, such as `＆`, `＝`
+/// and `＃`, and `＋`, which decodes to a space, are percent-encoded. Existing
+/// percent-encoded bytes are left unchanged.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// assert_eq!(unicode_hfwidth::normalize_url_query_value("ＡＢ＆Ｃ　１００％"),
+///            "AB%26C%20100%25");
+/// assert_eq!(unicode_hfwidth::normalize_url_query_value("a+b%20ｃ"), "a+b%20c");
+/// # }
+/// ```
+pub fn normalize_url_query_value<S: AsRef<str>>(value: S) -> String {
+    normalize_component(value.as_ref(), &['&', '=', '+', ';', '#'])
+}
+
+/// Converts a URL host, optionally followed by a port, to standard width and
+/// checks it like `normalize_hostname`. A full-width `：` before the port
+/// becomes `:`.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// assert_eq!(unicode_hfwidth::normalize_url_host("ｗｗｗ．ｅｘａｍｐｌｅ．ｃｏｍ：８０８０").unwrap(),
+///            "www.example.com:8080");
+/// # }
+/// ```
+pub fn normalize_url_host<S: AsRef<str>>(host: S) -> Result<String, HostnameError> {
+    let host = url_policy().convert(host);
+    match host.rsplit_once(':') {
+        Some((name, port)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => {
+            Ok(format!("{}:{}", normalize_hostname(name)?, port))
+        }
+        _ => normalize_hostname(host),
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_normalize_url_components() {
+    assert_eq!(normalize_url_path("a％２０b　c"), "a%2520b%20c");
+    assert_eq!(normalize_url_path("/ok/%ZZ/ｶﾞ"), "/ok/%ZZ/ガ");
+    assert_eq!(normalize_url_query_value("ｋｅｙ＝ｖａｌ；＃"), "key%3Dval%3B%23");
+    assert_eq!(normalize_url_host("ＥＸ.com："), Err(HostnameError::InvalidCharacter(':')));
+    assert_eq!(normalize_url_host("ｅｘ.ｃｏｍ").unwrap(), "ex.com");
+}
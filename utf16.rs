@@ -0,0 +1,273 @@
+//! Conversion and measurement of UTF-16 code units.
+//!
+//! Unpaired surrogates are passed through unchanged, so converting text from
+//! Windows APIs or JavaScript strings never loses data. No character outside
+//! the Basic Multilingual Plane has another width, so surrogate pairs, such
+//! as emoji or CJK Extension B ideographs, are always copied whole.
+
+use super::policy::Context;
+use super::{char_width, to_fullwidth, to_halfwidth, to_standard_width, OffsetMap, WidthPolicy};
+
+/// Applies `f` to every character of `s`, copying unpaired surrogates as they
+/// are.
+fn map_utf16<F: FnMut(char) -> char>(s: &[u16], mut f: F) -> Vec<u16> {
+    let mut out = Vec::with_capacity(s.len());
+    let mut buf = [0; 2];
+    for unit in char::decode_utf16(s.iter().copied()) {
+        match unit {
+            Ok(ch) => out.extend_from_slice(f(ch).encode_utf16(&mut buf)),
+            Err(e) => out.push(e.unpaired_surrogate()),
+        }
+    }
+    out
+}
+
+/// Converts the characters of `s` that have a standard-width form to it.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// let s: Vec<u16> = "ＡＢｶﾅ".encode_utf16().collect();
+/// let expected: Vec<u16> = "ABカナ".encode_utf16().collect();
+/// assert_eq!(unicode_hfwidth::to_standard_width_utf16(&s), expected);
+/// # }
+/// ```
+pub fn to_standard_width_utf16(s: &[u16]) -> Vec<u16> {
+    map_utf16(s, |ch| to_standard_width(ch).unwrap_or(ch))
+}
+
+/// Converts the characters of `s` that have a half-width form to it.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "kana")] {
+/// let s: Vec<u16> = "カナ".encode_utf16().collect();
+/// let expected: Vec<u16> = "ｶﾅ".encode_utf16().collect();
+/// assert_eq!(unicode_hfwidth::to_halfwidth_utf16(&s), expected);
+/// # }
+/// ```
+pub fn to_halfwidth_utf16(s: &[u16]) -> Vec<u16> {
+    map_utf16(s, |ch| to_halfwidth(ch).unwrap_or(ch))
+}
+
+/// Converts the characters of `s` that have a full-width form to it.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "latin")] {
+/// let s: Vec<u16> = "ab".encode_utf16().collect();
+/// let expected: Vec<u16> = "ａｂ".encode_utf16().collect();
+/// assert_eq!(unicode_hfwidth::to_fullwidth_utf16(&s), expected);
+/// # }
+/// ```
+pub fn to_fullwidth_utf16(s: &[u16]) -> Vec<u16> {
+    map_utf16(s, |ch| to_fullwidth(ch).unwrap_or(ch))
+}
+
+/// Returns the number of terminal columns `s` occupies. Unpaired surrogates
+/// count as one column, like the replacement character they are displayed as.
+///
+/// # Example
+/// ```rust
+/// let s: Vec<u16> = "aカｶ".encode_utf16().collect();
+/// assert_eq!(unicode_hfwidth::str_width_utf16(&s), 4);
+/// ```
+pub fn str_width_utf16(s: &[u16]) -> usize {
+    char::decode_utf16(s.iter().copied())
+        .map(|unit| unit.map_or(1, char_width))
+        .sum()
+}
+
+impl WidthPolicy {
+    /// Converts UTF-16 encoded `s` according to the policy, giving the same
+    /// result as `convert` would for `s` in UTF-8.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "kana")] {
+    /// use unicode_hfwidth::WidthPolicy;
+    ///
+    /// let s: Vec<u16> = "ｶﾞｲﾄﾞ".encode_utf16().collect();
+    /// let expected: Vec<u16> = "ガイド".encode_utf16().collect();
+    /// assert_eq!(WidthPolicy::default().convert_utf16(&s), expected);
+    /// # }
+    /// ```
+    pub fn convert_utf16(&self, s: &[u16]) -> Vec<u16> {
+        let mut out = Vec::with_capacity(s.len());
+        let mut cx = Context::default();
+        for unit in char::decode_utf16(s.iter().copied()) {
+            match unit {
+                Ok(ch) => self.push_piece(ch, 0..0, &mut cx, &mut out),
+                Err(e) => {
+                    cx.finish(&mut out);
+                    out.push(e.unpaired_surrogate());
+                }
+            }
+        }
+        cx.flush(&mut out);
+        out
+    }
+}
+
+/// Returns the UTF-16 offsets of the non-decreasing byte `offsets` in `s`.
+fn utf16_offsets<I: IntoIterator<Item = usize>>(s: &str, offsets: I) -> Vec<usize> {
+    let mut units = 0;
+    let mut pos = 0;
+    offsets.into_iter()
+        .map(|offset| {
+            units += s[pos..offset].chars().map(char::len_utf16).sum::<usize>();
+            pos = offset;
+            units
+        })
+        .collect()
+}
+
+impl OffsetMap {
+    /// Returns the map in UTF-16 code units instead of bytes, given the
+    /// `original` and `converted` texts it was made for.
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "kana")] {
+    /// use unicode_hfwidth::WidthPolicy;
+    ///
+    /// let s = "ｶﾞｲﾄﾞ ＡＢ";
+    /// let (converted, offsets) = WidthPolicy::default().convert_with_offsets(s);
+    /// let units = offsets.to_utf16(s, &converted);
+    /// assert_eq!(units.to_converted(6), 4);
+    /// assert_eq!(units.original_range(4..6), 6..8);
+    /// # }
+    /// ```
+    pub fn to_utf16(&self, original: &str, converted: &str) -> OffsetMap {
+        let checkpoints = self.checkpoints();
+        let o = utf16_offsets(original, checkpoints.iter().map(|&(o, _)| o)
+            .chain(Some(self.original_len())));
+        let n = utf16_offsets(converted, checkpoints.iter().map(|&(_, n)| n)
+            .chain(Some(self.len())));
+        let pairs = o[..checkpoints.len()].iter().copied()
+            .zip(n[..checkpoints.len()].iter().copied())
+            .collect();
+        OffsetMap::from_checkpoints(pairs, o[checkpoints.len()], n[checkpoints.len()])
+    }
+}
+
+/// A position in text as used by the Language Server Protocol: a zero-based
+/// line, and a zero-based column in UTF-16 code units.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Utf16Position {
+    /// The line, counting line feeds.
+    pub line: usize,
+    /// The column in UTF-16 code units.
+    pub character: usize,
+}
+
+impl Utf16Position {
+    /// Returns the position of the byte `offset` in `s`. Lines end at line
+    /// feeds.
+    ///
+    /// # Example
+    /// ```rust
+    /// use unicode_hfwidth::Utf16Position;
+    ///
+    /// assert_eq!(Utf16Position::of("a\n😀b", 6), Utf16Position { line: 1, character: 2 });
+    /// ```
+    pub fn of(s: &str, offset: usize) -> Utf16Position {
+        let start = s[..offset].rfind('\n').map_or(0, |i| i + 1);
+        Utf16Position {
+            line: s[..start].matches('\n').count(),
+            character: s[start..offset].chars().map(char::len_utf16).sum(),
+        }
+    }
+
+    /// Returns the byte offset of the position in `s`. If the line does not
+    /// exist or the position falls inside a character, returns `None`; a
+    /// column past the end of the line is the end of the line.
+    pub fn offset_in(&self, s: &str) -> Option<usize> {
+        let mut start = 0;
+        for _ in 0..self.line {
+            start += s[start..].find('\n')? + 1;
+        }
+        let line = &s[start..];
+        let line = &line[..line.find('\n').unwrap_or(line.len())];
+        let mut units = 0;
+        for (i, ch) in line.char_indices() {
+            if units >= self.character {
+                return (units == self.character).then_some(start + i);
+            }
+            units += ch.len_utf16();
+        }
+        (units <= self.character).then_some(start + line.len())
+    }
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_surrogates() {
+    let s = [0xff21, 0xd83d, 0xde00, 0xdc00, 0xff76, 0xd800];
+    assert_eq!(to_standard_width_utf16(&s), [0x41, 0xd83d, 0xde00, 0xdc00, 0x30ab, 0xd800]);
+    assert_eq!(str_width_utf16(&s), 1 + 2 + 1 + 2 + 1);
+    let policy = WidthPolicy::default();
+    let mut expected: Vec<u16> = policy.convert("Ａ😀").encode_utf16().collect();
+    expected.extend([0xdc00, 0x30ab, 0xd800]);
+    assert_eq!(policy.convert_utf16(&s), expected);
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_convert_utf16_context() {
+    use super::{MinusMapping, QuoteMapping};
+
+    let cases = [
+        (WidthPolicy::default(), "ｶﾞｲﾄﾞ ﾊﾟ"),
+        (WidthPolicy::builder().quotes(QuoteMapping::Curly).build(), "ａ＇ｓ ＂ｂ＂"),
+        (WidthPolicy::builder().minus(MinusMapping::HyphenMinus).build(), "ｰ１ ｱｰ１"),
+    ];
+    for (policy, input) in cases {
+        let units: Vec<u16> = input.encode_utf16().collect();
+        let expected: Vec<u16> = policy.convert(input).encode_utf16().collect();
+        assert_eq!(policy.convert_utf16(&units), expected, "{}", input);
+    }
+}
+
+#[test]
+fn test_astral_passthrough() {
+    use super::{fold_width, FullwidthString, HalfwidthString, WidthConverter, WidthTarget};
+
+    let astral = "😀𠀋\u{10000}\u{1f1ef}\u{1f1f5}\u{10ffff}";
+    let s = format!("{}ｶ{}ﾞ", "Ａ".repeat(64 * 1024 / 3 - 1), astral);
+    assert!(!s.is_char_boundary(64 * 1024));
+    for target in [WidthTarget::Standard, WidthTarget::Halfwidth, WidthTarget::Fullwidth] {
+        let policy = WidthPolicy::builder().all(target).space(target).build();
+        let converted = policy.convert(&s);
+        assert!(converted.contains(astral));
+        let units: Vec<u16> = s.encode_utf16().collect();
+        let converted_units = String::from_utf16(&policy.convert_utf16(&units)).unwrap();
+        assert!(converted_units.contains(astral));
+        let reported = WidthConverter::new(policy).on_progress(|_| {}).convert(&s);
+        assert_eq!(reported, converted);
+    }
+    let astral_units: Vec<u16> = astral.encode_utf16().collect();
+    for f in [to_standard_width_utf16, to_halfwidth_utf16, to_fullwidth_utf16] {
+        assert_eq!(f(&astral_units), astral_units);
+    }
+    assert_eq!(fold_width(astral), astral);
+    assert_eq!(astral.chars().collect::<FullwidthString>().as_str(), astral);
+    assert_eq!(astral.chars().collect::<HalfwidthString>().as_str(), astral);
+}
+
+#[cfg(feature = "kana")]
+#[test]
+fn test_lsp_positions() {
+    let s = "ｶﾞ😀\nｶﾞﾃﾞ x";
+    let (converted, offsets) = WidthPolicy::default().convert_with_offsets(s);
+    assert_eq!(converted, "ガ😀\nガデ x");
+    let units = offsets.to_utf16(s, &converted);
+    assert_eq!(units.checkpoints(), [(0, 0), (2, 1), (7, 5), (9, 6)]);
+    let units_before = |s: &str| s[..s.find('x').unwrap()].encode_utf16().count();
+    assert_eq!(units.to_converted(units_before(s)), units_before(&converted));
+    assert_eq!(Utf16Position::of(s, s.find('x').unwrap()), Utf16Position { line: 1, character: 5 });
+    assert_eq!(Utf16Position { line: 1, character: 3 }.offset_in(&converted),
+               converted.find('x'));
+    assert_eq!(Utf16Position { line: 0, character: 2 }.offset_in(&converted), None);
+    assert_eq!(Utf16Position { line: 2, character: 0 }.offset_in(&converted), None);
+}
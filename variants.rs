@@ -0,0 +1,66 @@
+//! All width forms of a character.
+
+use super::{is_nonstandard_width, to_fullwidth, to_halfwidth};
+
+/// The width forms of a character, as returned by `width_variants`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WidthVariants {
+    /// The half-width form.
+    pub halfwidth: Option<char>,
+    /// The full-width form.
+    pub fullwidth: Option<char>,
+    /// The standard-width form, the form outside the "Halfwidth and Fullwidth
+    /// Forms" block.
+    pub standard: Option<char>,
+}
+
+impl WidthVariants {
+    /// Returns the half-width and full-width forms, half-width first.
+    pub fn chars(&self) -> impl Iterator<Item = char> {
+        self.halfwidth.into_iter().chain(self.fullwidth)
+    }
+}
+
+/// Returns the half-width, full-width and standard-width forms of `ch`,
+/// including `ch` itself. A character without a counterpart in the other
+/// width has no forms.
+///
+/// # Example
+/// ```rust
+/// # #[cfg(all(feature = "kana", feature = "latin"))] {
+/// use unicode_hfwidth::width_variants;
+///
+/// let variants = width_variants('ｶ');
+/// assert_eq!(variants.halfwidth, Some('ｶ'));
+/// assert_eq!(variants.fullwidth, Some('カ'));
+/// assert_eq!(variants.standard, Some('カ'));
+/// assert_eq!(width_variants('ａ').chars().collect::<String>(), "aａ");
+/// assert_eq!(width_variants('漢').chars().count(), 0);
+/// # }
+/// ```
+pub fn width_variants(ch: char) -> WidthVariants {
+    let (halfwidth, fullwidth) = match (to_halfwidth(ch), to_fullwidth(ch)) {
+        (Some(half), _) => (half, ch),
+        (_, Some(full)) => (ch, full),
+        _               => return WidthVariants::default(),
+    };
+    let standard = if is_nonstandard_width(halfwidth) { fullwidth } else { halfwidth };
+    WidthVariants {
+        halfwidth: Some(halfwidth),
+        fullwidth: Some(fullwidth),
+        standard: Some(standard),
+    }
+}
+
+#[cfg(all(feature = "hangul", feature = "kana", feature = "latin", feature = "symbols"))]
+#[test]
+fn test_width_variants() {
+    for ch in ('\u{ff01}'..='\u{ffee}').filter(|&ch| super::forms_category(ch).is_some()) {
+        let variants = width_variants(ch);
+        assert_eq!(variants.standard, super::to_standard_width(ch), "{}", ch);
+        let standard = variants.standard.unwrap();
+        assert_eq!(width_variants(standard), variants, "{}", ch);
+        assert!(variants.chars().any(|form| form == ch));
+    }
+    assert_eq!(width_variants(' '), WidthVariants::default());
+}
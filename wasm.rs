@@ -0,0 +1,63 @@
+//! JavaScript bindings.
+//!
+//! Enabled by the `wasm` feature. Build with
+//! `cargo rustc --release --lib --features wasm --crate-type cdylib --target wasm32-unknown-unknown`
+//! and generate the JavaScript glue with `wasm-bindgen`.
+
+use wasm_bindgen::prelude::*;
+
+use super::{WidthPolicy, WidthTarget};
+
+/// Converts `s` to standard width.
+#[wasm_bindgen(js_name = toStandardWidth)]
+pub fn to_standard_width(s: &str) -> String {
+    WidthPolicy::default().convert(s)
+}
+
+/// Converts `s` to half-width wherever a half-width form exists.
+#[wasm_bindgen(js_name = toHalfwidth)]
+pub fn to_halfwidth(s: &str) -> String {
+    WidthPolicy::builder().all(WidthTarget::Halfwidth).build().convert(s)
+}
+
+/// Converts `s` to full-width wherever a full-width form exists.
+#[wasm_bindgen(js_name = toFullwidth)]
+pub fn to_fullwidth(s: &str) -> String {
+    WidthPolicy::builder().all(WidthTarget::Fullwidth).build().convert(s)
+}
+
+/// Checks if `s` contains characters in the Unicode "Halfwidth and Fullwidth
+/// Forms" block.
+#[wasm_bindgen(js_name = hasNonstandardWidth)]
+pub fn has_nonstandard_width(s: &str) -> bool {
+    super::contains_nonstandard_width(s)
+}
+
+/// Returns the number of terminal columns `s` occupies.
+#[wasm_bindgen(js_name = strWidth)]
+pub fn str_width(s: &str) -> usize {
+    super::str_width(s)
+}
+
+/// Truncates `s` to `width` columns, appending `…` if anything was cut off and
+/// `ellipsis` is set.
+#[wasm_bindgen(js_name = truncateToWidth)]
+pub fn truncate_to_width(s: &str, width: usize, ellipsis: bool) -> String {
+    let ellipsis = if ellipsis { super::Ellipsis::Unicode } else { super::Ellipsis::None };
+    super::truncate_to_width(s, width, ellipsis).into_owned()
+}
+
+/// Wraps `s` into lines of at most `width` columns.
+#[wasm_bindgen(js_name = wrapToWidth)]
+pub fn wrap_to_width(s: &str, width: usize) -> Vec<String> {
+    super::wrap_to_width(s, width).map(str::to_owned).collect()
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_wasm_exports() {
+    assert_eq!(to_standard_width("ＡＢｶ"), "ABカ");
+    assert_eq!(to_fullwidth("ab"), "ａｂ");
+    assert!(has_nonstandard_width("aｶ"));
+    assert_eq!(truncate_to_width("カタカナ", 5, true), "カタ…");
+}
@@ -0,0 +1,200 @@
+//! Request normalization middleware for `axum`.
+
+use axum::body::{Body, Bytes};
+use axum::extract::{FromRequest, Request, State};
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE, TRANSFER_ENCODING};
+use axum::http::uri::PathAndQuery;
+use axum::http::{HeaderValue, Uri};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use super::json::convert_json_with;
+use super::WidthConverter;
+
+/// Converts the values of the URL-encoded `query`. Returns `None` if nothing
+/// changes.
+fn normalize_query(query: &str, converter: &WidthConverter) -> Option<String> {
+    let mut changed = false;
+    let pairs: Vec<_> = form_urlencoded::parse(query.as_bytes())
+        .map(|(name, value)| {
+            let converted = converter.convert(&value);
+            changed |= converted != value;
+            (name, converted)
+        })
+        .collect();
+    if !changed {
+        return None;
+    }
+    Some(form_urlencoded::Serializer::new(String::new()).extend_pairs(pairs).finish())
+}
+
+/// The body formats `normalize_request` converts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BodyFormat {
+    Form,
+    Json,
+}
+
+/// Returns the format of bodies of the given content type, or `None` if they
+/// are left unchanged.
+fn body_format(content_type: &str) -> Option<BodyFormat> {
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    if mime.eq_ignore_ascii_case("application/x-www-form-urlencoded") {
+        Some(BodyFormat::Form)
+    } else if mime.eq_ignore_ascii_case("application/json") || mime.ends_with("+json") {
+        Some(BodyFormat::Json)
+    } else {
+        None
+    }
+}
+
+/// Converts a body in `format`. Returns `None` if it cannot be parsed or
+/// nothing changes.
+fn normalize_body(format: BodyFormat, bytes: &[u8], converter: &WidthConverter)
+                  -> Option<Vec<u8>> {
+    match format {
+        BodyFormat::Form => {
+            let query = std::str::from_utf8(bytes).ok()?;
+            normalize_query(query, converter).map(String::into_bytes)
+        }
+        BodyFormat::Json => {
+            let mut value: serde_json::Value = serde_json::from_slice(bytes).ok()?;
+            let changes = convert_json_with(&mut value, &|s| converter.convert(s), false);
+            if changes.values == 0 {
+                return None;
+            }
+            serde_json::to_vec(&value).ok()
+        }
+    }
+}
+
+/// Middleware converting query parameters, and URL-encoded form and JSON
+/// bodies, with the converter given as its state.
+///
+/// Only values are converted, not parameter names or object keys. Other
+/// bodies are passed on without being read, and requests that cannot be
+/// parsed are passed on unchanged for the handler to reject. Form and JSON
+/// bodies are read as the `axum` extractors read them: a body over the limit
+/// set with `DefaultBodyLimit`, 2 MiB by default, is rejected with
+/// `413 Payload Too Large`, and one that fails to arrive with `400 Bad
+/// Request`.
+///
+/// # Example
+/// ```rust
+/// use axum::routing::post;
+/// use axum::{middleware, Router};
+/// use unicode_hfwidth::{WidthConverter, WidthPolicy};
+///
+/// async fn register(body: String) -> String {
+///     body
+/// }
+///
+/// let converter = WidthConverter::new(WidthPolicy::default()).protect_defaults();
+/// let app: Router = Router::new()
+///     .route("/register", post(register))
+///     .layer(middleware::from_fn_with_state(converter, unicode_hfwidth::normalize_request));
+/// ```
+pub async fn normalize_request(State(converter): State<WidthConverter>, request: Request,
+                               next: Next) -> Response {
+    let (mut parts, body) = request.into_parts();
+    if let Some(query) = parts.uri.query().and_then(|q| normalize_query(q, &converter)) {
+        let path_and_query = format!("{}?{}", parts.uri.path(), query);
+        let mut uri = std::mem::take(&mut parts.uri).into_parts();
+        uri.path_and_query = PathAndQuery::try_from(path_and_query).ok();
+        parts.uri = Uri::from_parts(uri).unwrap_or_default();
+    }
+    let format = parts.headers.get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(body_format);
+    let body = match format {
+        Some(format) => {
+            let request = Request::from_parts(parts.clone(), body);
+            let bytes = match Bytes::from_request(request, &()).await {
+                Ok(bytes)      => bytes,
+                Err(rejection) => return rejection.into_response(),
+            };
+            match normalize_body(format, &bytes, &converter) {
+                Some(converted) => {
+                    parts.headers.remove(TRANSFER_ENCODING);
+                    parts.headers.insert(CONTENT_LENGTH, HeaderValue::from(converted.len()));
+                    Body::from(converted)
+                }
+                None => Body::from(bytes),
+            }
+        }
+        None => body,
+    };
+    next.run(Request::from_parts(parts, body)).await
+}
+
+#[test]
+fn test_normalize_request_parts() {
+    use super::WidthPolicy;
+
+    let converter = WidthConverter::new(WidthPolicy::default());
+    assert_eq!(normalize_query("q=%EF%BC%A1%EF%BC%A2&n=1", &converter).unwrap(), "q=AB&n=1");
+    assert_eq!(normalize_query("q=AB&n=1", &converter), None);
+    assert_eq!(body_format("application/json; charset=utf-8"), Some(BodyFormat::Json));
+    assert_eq!(body_format("application/problem+json"), Some(BodyFormat::Json));
+    assert_eq!(body_format("multipart/form-data; boundary=x"), None);
+    let json = normalize_body(BodyFormat::Json, "{\"ｷｰ\":[\"ｶﾞ\"]}".as_bytes(), &converter);
+    assert_eq!(String::from_utf8(json.unwrap()).unwrap(), "{\"ｷｰ\":[\"ガ\"]}");
+    let form = normalize_body(BodyFormat::Form, b"a=%EF%BC%91", &converter);
+    assert_eq!(form, Some(b"a=1".to_vec()));
+    assert_eq!(normalize_body(BodyFormat::Json, b"{", &converter), None);
+}
+
+#[cfg(all(feature = "kana", feature = "latin"))]
+#[test]
+fn test_normalize_request_body() {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    use axum::extract::DefaultBodyLimit;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::{middleware, Router, ServiceExt};
+
+    use super::WidthPolicy;
+
+    /* Nothing waits on I/O, so every future is ready when first polled */
+    fn ready<F: Future>(future: F) -> F::Output {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending       => panic!("future not ready"),
+        }
+    }
+
+    fn call<S: ServiceExt<Request>>(mut service: S, request: Request) -> S::Future {
+        service.call(request)
+    }
+
+    async fn echo(headers: HeaderMap, body: String) -> String {
+        format!("{} {}", headers.contains_key(TRANSFER_ENCODING), body)
+    }
+
+    let converter = WidthConverter::new(WidthPolicy::default());
+    let send = |limit: usize, body: String| {
+        let app: Router = Router::new()
+            .route("/", post(echo))
+            .layer(middleware::from_fn_with_state(converter.clone(), normalize_request))
+            .layer(DefaultBodyLimit::max(limit));
+        let request = Request::post("/")
+            .header(CONTENT_TYPE, "application/json")
+            .header(TRANSFER_ENCODING, "chunked")
+            .body(Body::from(body))
+            .unwrap();
+        let response = ready(call(app, request)).unwrap();
+        let status = response.status();
+        let bytes = ready(axum::body::to_bytes(response.into_body(), usize::MAX)).unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    };
+    let json = "{\"a\":\"ｶﾞ\"}".to_owned();
+    assert_eq!(send(64, json.clone()), (StatusCode::OK, "false {\"a\":\"ガ\"}".to_owned()));
+    assert_eq!(send(8, json).0, StatusCode::PAYLOAD_TOO_LARGE);
+    let large = format!("[\"{}\"]", "ｱ".repeat(1024 * 1024));
+    let (status, body) = send(4 * 1024 * 1024, large);
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.len(), "false [\"\"]".len() + 3 * 1024 * 1024);
+}